@@ -0,0 +1,215 @@
+//! The derive macro for `clockwork_base32`.
+//!
+//! Use it through the `derive` feature of `clockwork_base32`, which re-exports
+//! [`Base32`].
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Type};
+
+/// Derives `Display`, `Debug`, `FromStr` and, with the `serde` feature of
+/// `clockwork_base32`, `Serialize` and `Deserialize` for a newtype over bytes or
+/// an unsigned integer.
+///
+/// The struct must have exactly one field, either `[u8; N]`, an unsigned
+/// integer type like `u64` or `NonZeroU64`, or a type which implements
+/// `AsRef<[u8]>` and `From<Vec<u8>>` like `Vec<u8>`. The bytes are displayed as
+/// an encoded string, and parsing an array field fails unless the decoded length
+/// is exactly `N`. Integers are encoded with `clockwork_base32::int`, so parsing
+/// zero into a `NonZero` field fails.
+#[proc_macro_derive(Base32)]
+pub fn derive_base32(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "Base32 can only be derived for structs",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "Base32 cannot be derived for generic structs",
+        ));
+    }
+    let field = match fields.iter().collect::<Vec<_>>().as_slice() {
+        [field] => *field,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                "Base32 can only be derived for structs with exactly one field",
+            ))
+        }
+    };
+    let member = match &field.ident {
+        Some(ident) => quote!(#ident),
+        None => quote!(0),
+    };
+    let construct = match fields {
+        Fields::Named(_) => quote!(|bytes| Self { #member: bytes }),
+        _ => quote!(Self),
+    };
+    let is_array = matches!(field.ty, Type::Array(_));
+    if is_integer(&field.ty) {
+        return Ok(expand_integer(input, &member, &construct));
+    }
+
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let private = quote!(::clockwork_base32::__private);
+
+    let from_str = if is_array {
+        quote!(#private::decode_array(s).map(#construct))
+    } else {
+        quote!(#private::decode_vec(s).map(::core::convert::From::from).map(#construct))
+    };
+    let serde = if cfg!(feature = "serde") {
+        let deserialize = if is_array {
+            quote!(::clockwork_base32::serde::array::deserialize(deserializer).map(#construct))
+        } else {
+            quote!(::clockwork_base32::serde::deserialize(deserializer).map(#construct))
+        };
+        quote! {
+            impl #private::serde::Serialize for #name {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: #private::serde::Serializer,
+                {
+                    ::clockwork_base32::serde::serialize(&self.#member, serializer)
+                }
+            }
+
+            impl<'de> #private::serde::Deserialize<'de> for #name {
+                fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                where
+                    D: #private::serde::Deserializer<'de>,
+                {
+                    #deserialize
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        impl ::core::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #private::fmt_encoded(::core::convert::AsRef::<[u8]>::as_ref(&self.#member), f)
+            }
+        }
+
+        impl ::core::fmt::Debug for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #private::fmt_debug(
+                    #name_str,
+                    ::core::convert::AsRef::<[u8]>::as_ref(&self.#member),
+                    f,
+                )
+            }
+        }
+
+        impl ::core::str::FromStr for #name {
+            type Err = ::clockwork_base32::ParseError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                #from_str
+            }
+        }
+
+        #serde
+    })
+}
+
+// is_integer returns whether `ty` names one of the unsigned integer types
+// supported by `clockwork_base32::int`.
+fn is_integer(ty: &Type) -> bool {
+    const INTEGERS: [&str; 10] = [
+        "u8",
+        "u16",
+        "u32",
+        "u64",
+        "u128",
+        "NonZeroU8",
+        "NonZeroU16",
+        "NonZeroU32",
+        "NonZeroU64",
+        "NonZeroU128",
+    ];
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| INTEGERS.iter().any(|name| segment.ident == name)),
+        _ => false,
+    }
+}
+
+fn expand_integer(
+    input: &DeriveInput,
+    member: &TokenStream2,
+    construct: &TokenStream2,
+) -> TokenStream2 {
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let private = quote!(::clockwork_base32::__private);
+
+    let serde = if cfg!(feature = "serde") {
+        quote! {
+            impl #private::serde::Serialize for #name {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: #private::serde::Serializer,
+                {
+                    #private::serialize_int(self.#member, serializer)
+                }
+            }
+
+            impl<'de> #private::serde::Deserialize<'de> for #name {
+                fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                where
+                    D: #private::serde::Deserializer<'de>,
+                {
+                    #private::deserialize_int(deserializer).map(#construct)
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    quote! {
+        impl ::core::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #private::fmt_int(self.#member, f)
+            }
+        }
+
+        impl ::core::fmt::Debug for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #private::fmt_debug_int(#name_str, self.#member, f)
+            }
+        }
+
+        impl ::core::str::FromStr for #name {
+            type Err = ::clockwork_base32::ParseError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                #private::decode_int(s).map(#construct)
+            }
+        }
+
+        #serde
+    }
+}
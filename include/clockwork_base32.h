@@ -0,0 +1,212 @@
+#ifndef CLOCKWORK_BASE32_H
+#define CLOCKWORK_BASE32_H
+
+/* This file is generated by cbindgen from src/ffi.rs. Do not edit it manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Returned by the functions on success.
+#define CWB32_OK 0
+
+// A pointer argument is `NULL` when it must not be.
+#define CWB32_ERROR_NULL_POINTER -1
+
+// The input contains a byte which is not a valid symbol.
+#define CWB32_ERROR_INVALID_SYMBOL 1
+
+// The decoded bytes are not valid UTF-8.
+#define CWB32_ERROR_INVALID_UTF8 2
+
+// The output buffer is too small for the result.
+#define CWB32_ERROR_BUFFER_TOO_SMALL 3
+
+// The input is not the canonical encoding of any bytes.
+#define CWB32_ERROR_NON_CANONICAL 4
+
+// The decoded length does not match the expected length.
+#define CWB32_ERROR_LENGTH_MISMATCH 5
+
+// The input contains invalid symbols, without telling which ones.
+#define CWB32_ERROR_INVALID_SYMBOLS 6
+
+// A streaming adapter read or wrote more bytes than its limit.
+#define CWB32_ERROR_LIMIT_EXCEEDED 7
+
+// The decoded value is out of the range of the target type.
+#define CWB32_ERROR_OUT_OF_RANGE 8
+
+// The state of a streaming decoder, created by `cwb32_decoder_new`.
+typedef struct Cwb32Decoder Cwb32Decoder;
+
+// The state of a streaming encoder, created by `cwb32_encoder_new`.
+typedef struct Cwb32Encoder Cwb32Encoder;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns the code of the last failure on the calling thread, or `CWB32_OK`
+// if no function has failed on it.
+int cwb32_last_error_code(void);
+
+// Returns the byte offset of the problem in the input of the last failure on
+// the calling thread.
+//
+// For the streaming decoder, the offset counts from the start of the stream.
+// Returns 0 if the failure has no position in the input.
+size_t cwb32_last_error_offset(void);
+
+// Copies the message of the last failure on the calling thread to the
+// `buf_cap` bytes buffer at `buf` as a NUL-terminated string, and returns the
+// length of the whole message without the NUL.
+//
+// The message is truncated if it does not fit, like `snprintf`. The message is
+// empty if no function has failed on the thread. `buf` may be `NULL` if
+// `buf_cap` is 0, to get the needed length.
+//
+// # Safety
+// `buf` must be valid for writes of `buf_cap` bytes.
+size_t cwb32_last_error_message(char *buf, size_t buf_cap);
+
+// Returns the length of the encoded text of `input_len` bytes, or `SIZE_MAX`
+// if it doesn't fit in `size_t`.
+size_t cwb32_encoded_len(size_t input_len);
+
+// Returns the length of the decoded bytes of `input_len` symbols.
+size_t cwb32_decoded_len(size_t input_len);
+
+// Encodes `input_len` bytes at `input` into the `output_cap` bytes buffer at
+// `output`, and stores the length of the encoded text to `*output_len`.
+//
+// The output is not NUL-terminated. Returns `CWB32_OK` on success,
+// `CWB32_ERROR_BUFFER_TOO_SMALL` if `output_cap` is less than
+// `cwb32_encoded_len(input_len)`, or `CWB32_ERROR_NULL_POINTER`.
+//
+// # Safety
+// `input` must be valid for reads of `input_len` bytes, `output` must be
+// valid for writes of `output_cap` bytes, and `output_len` must be valid for
+// a write. The buffers must not overlap.
+int cwb32_encode(const uint8_t *input,
+                 size_t input_len,
+                 uint8_t *output,
+                 size_t output_cap,
+                 size_t *output_len);
+
+// Decodes `input_len` symbols at `input` into the `output_cap` bytes buffer at
+// `output`, and stores the length of the decoded bytes to `*output_len`.
+//
+// Lowercase symbols and the aliases `I`, `L` and `O` are accepted. Returns
+// `CWB32_OK` on success, `CWB32_ERROR_INVALID_SYMBOL` if the input contains an
+// invalid symbol, `CWB32_ERROR_BUFFER_TOO_SMALL` if `output_cap` is less than
+// `cwb32_decoded_len(input_len)`, or `CWB32_ERROR_NULL_POINTER`.
+//
+// # Safety
+// `input` must be valid for reads of `input_len` bytes, `output` must be
+// valid for writes of `output_cap` bytes, and `output_len` must be valid for
+// a write. The buffers must not overlap.
+int cwb32_decode(const uint8_t *input,
+                 size_t input_len,
+                 uint8_t *output,
+                 size_t output_cap,
+                 size_t *output_len);
+
+// Returns a new streaming encoder, which must be released with
+// `cwb32_encoder_free`.
+struct Cwb32Encoder *cwb32_encoder_new(void);
+
+// Encodes `input_len` bytes at `input` into the `output_cap` bytes buffer at
+// `output`, and stores the number of consumed input bytes to `*consumed` and
+// the number of written output bytes to `*written`.
+//
+// Bytes not forming a whole block of 5 bytes are kept in the encoder. Fewer
+// bytes than `input_len` are consumed when `output` is full, so call this again
+// with the rest of the input. Returns `CWB32_OK` on success, or
+// `CWB32_ERROR_NULL_POINTER`.
+//
+// # Safety
+// `encoder` must be returned by `cwb32_encoder_new` and not be freed yet. The
+// other pointers must be valid like for `cwb32_encode`.
+int cwb32_encoder_update(struct Cwb32Encoder *encoder,
+                         const uint8_t *input,
+                         size_t input_len,
+                         uint8_t *output,
+                         size_t output_cap,
+                         size_t *consumed,
+                         size_t *written);
+
+// Encodes the bytes kept in the encoder into the `output_cap` bytes buffer at
+// `output`, and stores the number of written bytes, which is at most 8, to
+// `*written`.
+//
+// Returns `CWB32_OK` on success, `CWB32_ERROR_BUFFER_TOO_SMALL` if
+// `output_cap` is less than needed, or `CWB32_ERROR_NULL_POINTER`. The encoder
+// can be used again after success.
+//
+// # Safety
+// The pointers must be valid like for `cwb32_encoder_update`.
+int cwb32_encoder_finish(struct Cwb32Encoder *encoder,
+                         uint8_t *output,
+                         size_t output_cap,
+                         size_t *written);
+
+// Releases an encoder. Does nothing if `encoder` is `NULL`.
+//
+// # Safety
+// `encoder` must be `NULL` or returned by `cwb32_encoder_new` and not be freed yet.
+void cwb32_encoder_free(struct Cwb32Encoder *encoder);
+
+// Returns a new streaming decoder, which must be released with
+// `cwb32_decoder_free`.
+struct Cwb32Decoder *cwb32_decoder_new(void);
+
+// Decodes `input_len` symbols at `input` into the `output_cap` bytes buffer at
+// `output`, and stores the number of consumed input bytes to `*consumed` and
+// the number of written output bytes to `*written`.
+//
+// Symbols not forming a whole block of 8 symbols are kept in the decoder.
+// Fewer symbols than `input_len` are consumed when `output` is full, so call
+// this again with the rest of the input. Returns `CWB32_OK` on success,
+// `CWB32_ERROR_INVALID_SYMBOL` if the input contains an invalid symbol, or
+// `CWB32_ERROR_NULL_POINTER`. Nothing is consumed on failure.
+//
+// # Safety
+// `decoder` must be returned by `cwb32_decoder_new` and not be freed yet. The
+// other pointers must be valid like for `cwb32_decode`.
+int cwb32_decoder_update(struct Cwb32Decoder *decoder,
+                         const uint8_t *input,
+                         size_t input_len,
+                         uint8_t *output,
+                         size_t output_cap,
+                         size_t *consumed,
+                         size_t *written);
+
+// Decodes the symbols kept in the decoder into the `output_cap` bytes buffer at
+// `output`, and stores the number of written bytes, which is at most 4, to
+// `*written`.
+//
+// Returns `CWB32_OK` on success, `CWB32_ERROR_BUFFER_TOO_SMALL` if
+// `output_cap` is less than needed, or `CWB32_ERROR_NULL_POINTER`. The decoder
+// can be used again after success.
+//
+// # Safety
+// The pointers must be valid like for `cwb32_decoder_update`.
+int cwb32_decoder_finish(struct Cwb32Decoder *decoder,
+                         uint8_t *output,
+                         size_t output_cap,
+                         size_t *written);
+
+// Releases a decoder. Does nothing if `decoder` is `NULL`.
+//
+// # Safety
+// `decoder` must be `NULL` or returned by `cwb32_decoder_new` and not be freed yet.
+void cwb32_decoder_free(struct Cwb32Decoder *decoder);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CLOCKWORK_BASE32_H */
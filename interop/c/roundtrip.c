@@ -0,0 +1,34 @@
+// Links against the C API built with `cargo rustc --features ffi` and checks
+// that it encodes and decodes through the generated header.
+
+#include <stdio.h>
+#include <string.h>
+
+#include "clockwork_base32.h"
+
+int main(void) {
+    const char *plain = "Hello, world!";
+    uint8_t encoded[32];
+    uint8_t decoded[32];
+    size_t encoded_len;
+    size_t decoded_len;
+
+    if (cwb32_encode((const uint8_t *)plain, strlen(plain), encoded, sizeof(encoded),
+                     &encoded_len) != CWB32_OK ||
+        encoded_len != 21 || memcmp(encoded, "91JPRV3F5GG7EVVJDHJ22", 21) != 0) {
+        fprintf(stderr, "encode failed\n");
+        return 1;
+    }
+    if (cwb32_decode(encoded, encoded_len, decoded, sizeof(decoded), &decoded_len) != CWB32_OK ||
+        decoded_len != strlen(plain) || memcmp(decoded, plain, decoded_len) != 0) {
+        fprintf(stderr, "decode failed\n");
+        return 1;
+    }
+    if (cwb32_decode((const uint8_t *)"CSQPU", 5, decoded, sizeof(decoded), &decoded_len) !=
+            CWB32_ERROR_INVALID_SYMBOL ||
+        cwb32_last_error_offset() != 4) {
+        fprintf(stderr, "invalid symbol not reported\n");
+        return 1;
+    }
+    return 0;
+}
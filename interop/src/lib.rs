@@ -0,0 +1,9 @@
+//! Tests of `clockwork_base32` against the crates it integrates with.
+//!
+//! They live in this unpublished crate so that the database drivers, the async
+//! runtime and the other crates they need are not dev-dependencies of
+//! `clockwork_base32` itself. Run them with
+//! `cargo test -p clockwork_base32_interop`.
+//!
+//! `c/roundtrip.c` links the C API of the `ffi` feature, built with
+//! `cargo rustc --crate-type cdylib` or `staticlib`, and is run in CI.
@@ -0,0 +1,71 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use clockwork_base32::axum::{Base32Path, Base32Query};
+use clockwork_base32::{Base32Bytes, Key};
+use tower::ServiceExt;
+
+#[derive(serde::Deserialize)]
+struct Download {
+    key: Key<5>,
+    name: Option<Base32Bytes>,
+}
+
+async fn get_item(Base32Path(id): Base32Path<Base32Bytes>) -> Vec<u8> {
+    id.to_vec()
+}
+
+async fn download(Base32Query(query): Base32Query<Download>) -> Vec<u8> {
+    let mut body = query.key.as_bytes().to_vec();
+    body.extend_from_slice(query.name.as_deref().unwrap_or_default());
+    body
+}
+
+async fn request(uri: &str) -> (StatusCode, Vec<u8>) {
+    let app: Router = Router::new()
+        .route("/items/{id}", get(get_item))
+        .route("/download", get(download));
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, body.to_vec())
+}
+
+#[tokio::test]
+async fn test_path() {
+    assert_eq!(
+        request("/items/csqpyrk1e8").await,
+        (StatusCode::OK, b"foobar".to_vec())
+    );
+    assert_eq!(
+        request("/items/CSQ-PY").await,
+        (
+            StatusCode::BAD_REQUEST,
+            b"invalid symbol '-' at position 3, remove hyphens".to_vec()
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_query() {
+    assert_eq!(
+        request("/download?key=CSQPYRK1&name=C4").await,
+        (StatusCode::OK, b"foobaa".to_vec())
+    );
+    assert_eq!(
+        request("/download?key=CSQPYRK1").await,
+        (StatusCode::OK, b"fooba".to_vec())
+    );
+    let (status, body) = request("/download?key=CSQPY").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let body = String::from_utf8(body).unwrap();
+    assert!(
+        body.contains("decoded length 3 does not match the expected length 5"),
+        "{}",
+        body
+    );
+    let (status, _) = request("/download").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
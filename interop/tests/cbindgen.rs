@@ -0,0 +1,18 @@
+#[test]
+fn test_header() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/..");
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", dir)).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{}/src/ffi.rs", dir))
+        .generate()
+        .unwrap()
+        .write(&mut generated);
+    let header = std::fs::read(format!("{}/include/clockwork_base32.h", dir)).unwrap();
+    assert!(
+        header == generated,
+        "include/clockwork_base32.h is outdated, regenerate it with \
+         `cbindgen --config cbindgen.toml --output include/clockwork_base32.h src/ffi.rs`"
+    );
+}
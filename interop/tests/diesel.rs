@@ -0,0 +1,47 @@
+use clockwork_base32::{Base32Bytes, Base32String};
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sqlite::SqliteConnection;
+
+diesel::table! {
+    ids (id) {
+        id -> Text,
+        key -> Text,
+    }
+}
+
+#[derive(Queryable, Insertable, Debug, PartialEq)]
+#[diesel(table_name = ids)]
+struct Row {
+    id: Base32String,
+    key: Base32String,
+}
+
+#[test]
+fn test_sqlite() {
+    let mut conn = SqliteConnection::establish(":memory:").unwrap();
+    sql_query("CREATE TABLE ids (id TEXT PRIMARY KEY, key TEXT NOT NULL)")
+        .execute(&mut conn)
+        .unwrap();
+    let row = Row {
+        id: Base32String::encode("foo"),
+        key: Base32Bytes::from(&b"bar"[..]).encode(),
+    };
+    diesel::insert_into(ids::table)
+        .values(&row)
+        .execute(&mut conn)
+        .unwrap();
+
+    let rows: Vec<Row> = ids::table
+        .filter(ids::id.eq(Base32String::encode("foo")))
+        .load(&mut conn)
+        .unwrap();
+    assert_eq!(rows, [row]);
+    let keys: Vec<Base32Bytes> = ids::table.select(ids::key).load(&mut conn).unwrap();
+    assert_eq!(keys, [Base32Bytes::from(&b"bar"[..])]);
+
+    sql_query("UPDATE ids SET id = 'csqpy'")
+        .execute(&mut conn)
+        .unwrap();
+    assert!(ids::table.load::<Row>(&mut conn).is_err());
+}
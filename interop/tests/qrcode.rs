@@ -0,0 +1,33 @@
+use clockwork_base32::qr::{
+    alphanumeric_bit_len, max_input_len, min_version, EcLevel, MAX_VERSION,
+};
+use qrcode::bits::Bits;
+use qrcode::types::Version;
+
+#[test]
+fn test_same_as_qrcode() {
+    let levels = [
+        (EcLevel::L, qrcode::EcLevel::L),
+        (EcLevel::M, qrcode::EcLevel::M),
+        (EcLevel::Q, qrcode::EcLevel::Q),
+        (EcLevel::H, qrcode::EcLevel::H),
+    ];
+    for version in 1..=MAX_VERSION {
+        for (ec_level, expected) in levels {
+            let max_len = max_input_len(version, ec_level).unwrap();
+            for len in [max_len, max_len + 1] {
+                let encoded = clockwork_base32::encode_to_vec(&vec![0xff; len]);
+                let mut bits = Bits::new(Version::Normal(i16::from(version)));
+                bits.push_alphanumeric_data(&encoded).unwrap();
+                assert_eq!(alphanumeric_bit_len(len, version), Some(bits.len()));
+                let fits = bits.len() <= bits.max_len(expected).unwrap();
+                assert_eq!(fits, len == max_len, "{} {:?} {}", version, ec_level, len);
+            }
+        }
+    }
+    assert_eq!(max_input_len(0, EcLevel::L), None);
+    assert_eq!(max_input_len(41, EcLevel::L), None);
+    assert_eq!(min_version(0, EcLevel::H), Some(1));
+    assert_eq!(min_version(2685, EcLevel::L), Some(40));
+    assert_eq!(min_version(2686, EcLevel::L), None);
+}
@@ -0,0 +1,35 @@
+use clockwork_base32::{Base32Bytes, Base32String};
+use sqlx::{Connection, SqliteConnection};
+
+#[tokio::test]
+async fn test_sqlite() {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+    sqlx::query("CREATE TABLE ids (id TEXT NOT NULL, key TEXT NOT NULL)")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+    let id = Base32String::encode("foo");
+    let key = Base32Bytes::from(&b"bar"[..]);
+    sqlx::query("INSERT INTO ids VALUES (?, ?)")
+        .bind(&id)
+        .bind(&key)
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let (text,): (String,) = sqlx::query_as("SELECT key FROM ids")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(text, "C9GQ4");
+    let row: (Base32String, Base32Bytes) = sqlx::query_as("SELECT id, key FROM ids")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(row, (id, key));
+
+    let err = sqlx::query_as::<_, (Base32String,)>("SELECT 'csqpy'")
+        .fetch_one(&mut conn)
+        .await;
+    assert!(err.is_err());
+}
@@ -0,0 +1,132 @@
+//! Extractors for `axum` which decode path and query parameters.
+//!
+//! [`Base32Path`] decodes the single path parameter of a route into a type
+//! parsed from encoded text, like [`Base32Bytes`](crate::Base32Bytes),
+//! [`Base32String`](crate::Base32String), [`UrlSafe`](crate::UrlSafe) or
+//! [`Key`](crate::Key).
+//! [`Base32Query`] deserializes the query string into a type whose fields are
+//! such types. Invalid input is rejected with `400 Bad Request` and the error
+//! message of this crate, like the position of an invalid symbol and how to fix
+//! it, as the plain text body.
+//!
+//! # Examples
+//! ```
+//! use axum::routing::get;
+//! use axum::Router;
+//! use clockwork_base32::axum::{Base32Path, Base32Query};
+//! use clockwork_base32::{Base32Bytes, Key};
+//! use serde::Deserialize;
+//!
+//! async fn get_item(Base32Path(id): Base32Path<Base32Bytes>) -> String {
+//!     format!("{} bytes", id.len())
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Download {
+//!     key: Key<5>,
+//! }
+//!
+//! async fn download(Base32Query(query): Base32Query<Download>) -> String {
+//!     format!("{} bytes", query.key.as_bytes().len())
+//! }
+//!
+//! let app: Router = Router::new()
+//!     .route("/items/{id}", get(get_item))
+//!     .route("/download", get(download));
+//! ```
+
+use core::fmt;
+use core::str::FromStr;
+use std::string::{String, ToString};
+
+use ::axum::extract::{FromRequestParts, Path, Query};
+use ::axum::http::request::Parts;
+use ::axum::http::StatusCode;
+use ::axum::response::{IntoResponse, Response};
+use ::serde::de::DeserializeOwned;
+
+/// An extractor which parses the single path parameter of a route into `T`.
+///
+/// A route with more than one parameter is a programming error, rejected with
+/// `500 Internal Server Error` like in `axum::extract::Path`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Base32Path<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Base32Path<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    S: Send + Sync,
+{
+    type Rejection = Base32Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Path(param) = Path::<String>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| Base32Rejection::new(rejection.status(), rejection.body_text()))?;
+        param
+            .parse()
+            .map(Self)
+            .map_err(|err| Base32Rejection::new(StatusCode::BAD_REQUEST, err.to_string()))
+    }
+}
+
+/// An extractor which deserializes the query string into `T`, like
+/// `axum::extract::Query`.
+///
+/// The fields of `T` can be any types deserialized from encoded text, like
+/// [`Base32Bytes`](crate::Base32Bytes) or [`Key`](crate::Key).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Base32Query<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Base32Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Base32Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Query::try_from_uri(&parts.uri)
+            .map(|Query(query)| Self(query))
+            .map_err(|rejection| Base32Rejection::new(rejection.status(), rejection.body_text()))
+    }
+}
+
+/// The rejection of [`Base32Path`] and [`Base32Query`], responding with its
+/// status and message as a plain text body.
+#[derive(Debug)]
+pub struct Base32Rejection {
+    status: StatusCode,
+    message: String,
+}
+
+impl Base32Rejection {
+    fn new(status: StatusCode, message: String) -> Self {
+        Self { status, message }
+    }
+
+    /// Returns the status code of the response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the message in the body of the response.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Base32Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Base32Rejection {}
+
+impl IntoResponse for Base32Rejection {
+    fn into_response(self) -> Response {
+        (self.status, self.message).into_response()
+    }
+}
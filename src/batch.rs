@@ -0,0 +1,200 @@
+//! Encoding and decoding many values, like whole columns of IDs.
+//!
+//! [`encode_lines`] and [`decode_lines`] take the values as an iterator and
+//! return an iterator of the results, one per value, so a failed value doesn't
+//! stop the others. Each value is transcoded into one scratch buffer which is
+//! reused for all values, and each result is allocated once with its exact
+//! size. [`EncodeLines::next_str`] and [`DecodeLines::next_slice`] return the
+//! result in the scratch buffer without allocating at all.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::{batch, Error};
+//!
+//! let ids = ["CSQPYRK1", "91JPRV3F", "CSQPU"];
+//! let decoded: Vec<_> = batch::decode_lines(ids).collect();
+//! assert_eq!(decoded[0].as_deref().ok(), Some(&b"fooba"[..]));
+//! assert_eq!(decoded[1].as_deref().ok(), Some(&b"Hello"[..]));
+//! assert!(matches!(decoded[2], Err(Error::InvalidSymbol { byte: b'U', .. })));
+//!
+//! let mut encoder = batch::encode_lines([&b"fooba"[..], b"Hello"]);
+//! let mut csv = String::new();
+//! while let Some(encoded) = encoder.next_str() {
+//!     csv.push_str(encoded);
+//!     csv.push('\n');
+//! }
+//! assert_eq!(csv, "CSQPYRK1\n91JPRV3F\n");
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{
+    capacity_hint_for_decode, capacity_hint_for_encode, decode_to_slice, encode_to_slice, Result,
+};
+
+/// Returns an iterator which encodes each of `items` to a [`String`].
+///
+/// The items may be anything viewable as bytes, like [`str`] and [`Vec<u8>`].
+pub fn encode_lines<I>(items: I) -> EncodeLines<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    EncodeLines {
+        items: items.into_iter(),
+        scratch: Vec::new(),
+    }
+}
+
+/// Returns an iterator which decodes each of `items` to a [`Vec<u8>`], or the
+/// error for the item.
+///
+/// The items may be anything viewable as bytes, like [`str`] and [`String`].
+pub fn decode_lines<I>(items: I) -> DecodeLines<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    DecodeLines {
+        items: items.into_iter(),
+        scratch: Vec::new(),
+    }
+}
+
+/// An iterator which encodes each item, created by [`encode_lines`].
+#[derive(Debug, Clone)]
+pub struct EncodeLines<I> {
+    items: I,
+    scratch: Vec<u8>,
+}
+
+impl<I> EncodeLines<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    /// Encodes the next item and returns the result in the scratch buffer,
+    /// which is valid until the next call.
+    pub fn next_str(&mut self) -> Option<&str> {
+        let item = self.items.next()?;
+        let input = item.as_ref();
+        self.scratch
+            .resize(capacity_hint_for_encode(input.len()), 0);
+        let len = encode_to_slice(input, &mut self.scratch).unwrap_or_default();
+        let encoded = self.scratch.get(..len).unwrap_or_default();
+        Some(core::str::from_utf8(encoded).unwrap_or_default())
+    }
+}
+
+impl<I> Iterator for EncodeLines<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.next_str().map(String::from)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+/// An iterator which decodes each item, created by [`decode_lines`].
+#[derive(Debug, Clone)]
+pub struct DecodeLines<I> {
+    items: I,
+    scratch: Vec<u8>,
+}
+
+impl<I> DecodeLines<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    /// Decodes the next item and returns the result in the scratch buffer,
+    /// which is valid until the next call.
+    pub fn next_slice(&mut self) -> Option<Result<&[u8]>> {
+        let item = self.items.next()?;
+        let input = item.as_ref();
+        self.scratch
+            .resize(capacity_hint_for_decode(input.len()), 0);
+        match decode_to_slice(input, &mut self.scratch) {
+            Ok(len) => Some(Ok(self.scratch.get(..len).unwrap_or_default())),
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+impl<I> Iterator for DecodeLines<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Result<Vec<u8>>> {
+        self.next_slice().map(|result| result.map(<[u8]>::to_vec))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_to_vec, encode_to_string, Error};
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn test_encode_lines() {
+        let items: Vec<Vec<u8>> = (0..50).map(|len| vec![0xa5; len]).collect();
+        let encoded: Vec<String> = encode_lines(&items).collect();
+        assert_eq!(encoded.len(), items.len());
+        for (item, encoded) in items.iter().zip(&encoded) {
+            assert_eq!(*encoded, encode_to_string(item));
+            assert_eq!(encoded.capacity(), encoded.len());
+        }
+
+        let mut lines = encode_lines(items.iter().rev());
+        assert_eq!(lines.size_hint(), (50, Some(50)));
+        assert_eq!(lines.next_str(), Some(encoded[49].as_str()));
+        assert_eq!(lines.nth(48), Some(String::new()));
+        assert_eq!(lines.next_str(), None);
+    }
+
+    #[test]
+    fn test_decode_lines() {
+        let items = ["", "CSQPYRK1", "csqpy", "CSQPU", "91JPRV3F5GG7EVVJDHJ22"];
+        let decoded: Vec<Result<Vec<u8>>> = decode_lines(items).collect();
+        assert_eq!(decoded.len(), items.len());
+        for (item, decoded) in items.iter().zip(&decoded) {
+            assert_eq!(
+                decoded.as_ref().ok(),
+                decode_to_vec(item.as_bytes()).ok().as_ref()
+            );
+        }
+        assert!(matches!(
+            decoded[3],
+            Err(Error::InvalidSymbol { byte: b'U', .. })
+        ));
+
+        let owned: Vec<String> = items.iter().map(|s| s.to_string()).collect();
+        let mut lines = decode_lines(owned.iter().rev());
+        assert!(matches!(lines.next_slice(), Some(Ok(b"Hello, world!"))));
+        assert!(matches!(
+            lines.next_slice(),
+            Some(Err(Error::InvalidSymbol { byte: b'U', .. }))
+        ));
+        assert!(matches!(lines.next_slice(), Some(Ok(b"foo"))));
+        assert!(matches!(lines.next_slice(), Some(Ok(b"fooba"))));
+        assert!(matches!(lines.next_slice(), Some(Ok(b""))));
+        assert!(lines.next_slice().is_none());
+    }
+}
@@ -0,0 +1,221 @@
+//! Checks files against a manifest of their expected encodings, like
+//! `sha256sum -c`.
+//!
+//! Each line of a manifest is an encoding and a file name separated by
+//! whitespace, as printed by `cw-base32 -w 0 FILE`. The files are encoded
+//! again and compared with the encodings, ignoring ASCII case.
+
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use clockwork_base32::io::EncodeWriter;
+
+use super::open_input;
+
+/// The numbers of the results of checking a manifest.
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    /// The number of files which matched.
+    pub ok: usize,
+    /// The number of files which didn't match.
+    pub mismatched: usize,
+    /// The number of files which couldn't be read.
+    pub unreadable: usize,
+    /// The number of lines which are not an encoding and a file name.
+    pub malformed: usize,
+}
+
+impl Summary {
+    /// Returns true if all files matched and all lines are well-formed.
+    pub fn is_ok(&self) -> bool {
+        self.mismatched == 0 && self.unreadable == 0 && self.malformed == 0
+    }
+
+    /// Writes warnings for the problems like `sha256sum -c` does.
+    pub fn warn(&self, errors: &mut dyn Write) -> io::Result<()> {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        if self.malformed > 0 {
+            writeln!(
+                errors,
+                "cw-base32: WARNING: {} line{} improperly formatted",
+                self.malformed,
+                if self.malformed == 1 { " is" } else { "s are" }
+            )?;
+        }
+        if self.unreadable > 0 {
+            writeln!(
+                errors,
+                "cw-base32: WARNING: {} listed file{} could not be read",
+                self.unreadable,
+                plural(self.unreadable)
+            )?;
+        }
+        if self.mismatched > 0 {
+            writeln!(
+                errors,
+                "cw-base32: WARNING: {} computed encoding{} did NOT match",
+                self.mismatched,
+                plural(self.mismatched)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the files listed in `manifest`, writing `NAME: OK` or
+/// `NAME: FAILED` per file to `output`, or only the failures if `quiet` is
+/// true, and the problems with the manifest itself to `errors`.
+pub fn check(
+    manifest: &mut dyn BufRead,
+    output: &mut dyn Write,
+    errors: &mut dyn Write,
+    quiet: bool,
+) -> io::Result<Summary> {
+    let mut summary = Summary::default();
+    let mut line_number = 0;
+    for line in manifest.lines() {
+        let line = line?;
+        line_number += 1;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((expected, name)) = line
+            .split_once(char::is_whitespace)
+            .map(|(expected, name)| (expected, name.trim_start()))
+            .filter(|(_, name)| !name.is_empty())
+        else {
+            writeln!(
+                errors,
+                "cw-base32: line {}: expected an encoding and a file name",
+                line_number
+            )?;
+            summary.malformed += 1;
+            continue;
+        };
+        match matches(expected.as_bytes(), &PathBuf::from(name)) {
+            Ok(true) => {
+                summary.ok += 1;
+                if !quiet {
+                    writeln!(output, "{}: OK", name)?;
+                }
+            }
+            Ok(false) => {
+                summary.mismatched += 1;
+                writeln!(output, "{}: FAILED", name)?;
+            }
+            Err(err) => {
+                summary.unreadable += 1;
+                writeln!(errors, "cw-base32: {}: {}", name, err)?;
+                writeln!(output, "{}: FAILED open or read", name)?;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+// matches encodes the file at `path` and returns whether the result is
+// `expected`, comparing while encoding.
+fn matches(expected: &[u8], path: &PathBuf) -> io::Result<bool> {
+    let mut input = open_input(Some(path))?;
+    let mut writer = EncodeWriter::new(Comparer {
+        expected,
+        matched: true,
+    });
+    io::copy(&mut input, &mut writer)?;
+    let comparer = writer.finish()?;
+    Ok(comparer.matched && comparer.expected.is_empty())
+}
+
+// A writer which compares the written bytes with the rest of `expected`.
+struct Comparer<'a> {
+    expected: &'a [u8],
+    matched: bool,
+}
+
+impl Write for Comparer<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.matched {
+            match self.expected.split_at_checked(buf.len()) {
+                Some((head, rest)) if head.eq_ignore_ascii_case(buf) => self.expected = rest,
+                _ => self.matched = false,
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_check() {
+        let dir = std::env::temp_dir().join(format!("cw-base32-check-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let hello = dir.join("hello world.txt");
+        let empty = dir.join("empty");
+        fs::write(&hello, b"Hello, world!").unwrap();
+        fs::write(&empty, b"").unwrap();
+        let manifest = format!(
+            "91JPRV3F5GG7EVVJDHJ22  {}\n\
+             91jprv3f5gg7evvjdhj22 {}\n\
+             91JPRV3F5GG7EVVJDHJ2  {}\n\
+             \n\
+             CSQPY  {}\n\
+             CSQPY\n\
+             0  {}\n",
+            hello.display(),
+            hello.display(),
+            hello.display(),
+            empty.display(),
+            dir.join("missing").display(),
+        );
+
+        let mut output = Vec::new();
+        let mut errors = Vec::new();
+        let summary = check(&mut manifest.as_bytes(), &mut output, &mut errors, false).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                ok: 2,
+                mismatched: 2,
+                unreadable: 1,
+                malformed: 1,
+            }
+        );
+        assert!(!summary.is_ok());
+        let output = String::from_utf8(output).unwrap();
+        let results: Vec<_> = output
+            .lines()
+            .map(|line| line.rsplit(": ").next().unwrap())
+            .collect();
+        assert_eq!(
+            results,
+            ["OK", "OK", "FAILED", "FAILED", "FAILED open or read"]
+        );
+        let errors = String::from_utf8(errors).unwrap();
+        assert!(errors.starts_with("cw-base32: line 6: expected an encoding and a file name\n"));
+
+        let mut errors = Vec::new();
+        summary.warn(&mut errors).unwrap();
+        assert_eq!(
+            String::from_utf8(errors).unwrap(),
+            "cw-base32: WARNING: 1 line is improperly formatted\n\
+             cw-base32: WARNING: 1 listed file could not be read\n\
+             cw-base32: WARNING: 2 computed encodings did NOT match\n"
+        );
+
+        let mut output = Vec::new();
+        let manifest = format!("91JPRV3F5GG7EVVJDHJ22  {}\n", hello.display());
+        let summary = check(&mut manifest.as_bytes(), &mut output, &mut Vec::new(), true).unwrap();
+        assert!(summary.is_ok());
+        assert!(output.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
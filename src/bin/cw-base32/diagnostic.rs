@@ -0,0 +1,115 @@
+//! Rendering problems for terminals, with the offending byte highlighted in
+//! its line and a caret below it by the library's `Snippet`.
+
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+use clockwork_base32::Snippet;
+
+use crate::validate::Problem;
+
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// How to render problems.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Style {
+    /// One line per problem, for scripts and logs.
+    Plain,
+    /// The line and a caret below the offending byte, colored if `color` is
+    /// true.
+    Snippet { color: bool },
+}
+
+impl Style {
+    /// Returns the style for the standard error: snippets if it is a terminal,
+    /// colored unless the `NO_COLOR` environment variable is set to a
+    /// non-empty value.
+    pub fn for_stderr() -> Self {
+        if !io::stderr().is_terminal() {
+            return Style::Plain;
+        }
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Style::Snippet { color: !no_color }
+    }
+}
+
+/// Writes `problem` found in the input at `path` in `style`.
+pub fn render(
+    output: &mut dyn Write,
+    path: &Path,
+    problem: &Problem,
+    style: Style,
+) -> io::Result<()> {
+    let (bold, reset) = match style {
+        Style::Snippet { color: true } => (BOLD, RESET),
+        _ => ("", ""),
+    };
+    writeln!(output, "{}{}:{}{}", bold, path.display(), reset, problem)?;
+    match (style, &problem.context) {
+        (Style::Snippet { color }, Some(context)) => {
+            let snippet = Snippet::new(&context.text, context.index)
+                .continued(context.continued)
+                .color(color);
+            writeln!(output, "{}", snippet)
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::validate;
+
+    fn render_to_string(input: &[u8], style: Style) -> String {
+        let problem = validate(&mut &input[..], true).unwrap().unwrap_err();
+        let mut output = Vec::new();
+        render(&mut output, Path::new("ids.txt"), &problem, style).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_render() {
+        let input = b"CSQPYRK1\n5GG7-E\tVJ\n";
+        assert_eq!(
+            render_to_string(input, Style::Plain),
+            "ids.txt:2:5: invalid symbol '-' at position 13, remove hyphens\n"
+        );
+        assert_eq!(
+            render_to_string(input, Style::Snippet { color: false }),
+            concat!(
+                "ids.txt:2:5: invalid symbol '-' at position 13, remove hyphens\n",
+                "    5GG7-E.VJ\n",
+                "        ^\n",
+            )
+        );
+        assert_eq!(
+            render_to_string(input, Style::Snippet { color: true }),
+            concat!(
+                "\x1b[1mids.txt:\x1b[0m2:5: invalid symbol '-' at position 13, remove hyphens\n",
+                "    5GG7\x1b[1;31m-\x1b[0mE.VJ\n",
+                "        \x1b[1;31m^\x1b[0m\n",
+            )
+        );
+
+        let mut input = vec![b'0'; 100];
+        input[50] = b'u';
+        let rendered = render_to_string(&input, Style::Snippet { color: false });
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(
+            lines[1],
+            format!("    ...{}u{}...", "0".repeat(32), "0".repeat(32))
+        );
+        assert_eq!(lines[2].find('^'), Some(4 + 3 + 32));
+
+        // Problems without a location in a line render on one line.
+        assert_eq!(
+            render_to_string(b"CSQPYR", Style::Snippet { color: false })
+                .lines()
+                .count(),
+            1
+        );
+    }
+}
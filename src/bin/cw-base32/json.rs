@@ -0,0 +1,106 @@
+//! Writing check results as JSON lines for CI pipelines and editors.
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::validate::Problem;
+
+/// Writes the result of checking `file` as a JSON object on one line.
+///
+/// A valid input is written as
+/// `{"file":"a.txt","status":"valid","decoded_len":13}`, and an invalid one as
+/// `{"file":"a.txt","status":"invalid","offset":4,"line":1,"column":5,"byte":85,
+/// "message":"invalid symbol 'U' at position 4, ..."}` where `byte` may be
+/// `null` and `message` is the library's message with a suggested fix, if any.
+pub fn write_result(
+    output: &mut dyn Write,
+    file: &Path,
+    result: &Result<usize, Problem>,
+) -> io::Result<()> {
+    output.write_all(b"{\"file\":")?;
+    write_str(output, &file.to_string_lossy())?;
+    match result {
+        Ok(decoded_len) => write!(
+            output,
+            ",\"status\":\"valid\",\"decoded_len\":{}",
+            decoded_len
+        )?,
+        Err(problem) => {
+            write!(
+                output,
+                ",\"status\":\"invalid\",\"offset\":{},\"line\":{},\"column\":{},\"byte\":",
+                problem.error.position(),
+                problem.line,
+                problem.column
+            )?;
+            match problem.error.byte() {
+                Some(b) => write!(output, "{}", b)?,
+                None => output.write_all(b"null")?,
+            }
+            output.write_all(b",\"message\":")?;
+            write_str(output, &problem.error.to_string())?;
+        }
+    }
+    output.write_all(b"}\n")
+}
+
+// write_str writes `s` as a JSON string.
+fn write_str(output: &mut dyn Write, s: &str) -> io::Result<()> {
+    output.write_all(b"\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => output.write_all(b"\\\"")?,
+            '\\' => output.write_all(b"\\\\")?,
+            '\n' => output.write_all(b"\\n")?,
+            '\r' => output.write_all(b"\\r")?,
+            '\t' => output.write_all(b"\\t")?,
+            c if c.is_control() => write!(output, "\\u{:04x}", c as u32)?,
+            c => write!(output, "{}", c)?,
+        }
+    }
+    output.write_all(b"\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::validate;
+
+    fn result_json(input: &[u8], strict: bool) -> String {
+        let result = validate(&mut &input[..], strict).unwrap();
+        let mut output = Vec::new();
+        write_result(&mut output, Path::new("dir/a \"b\".txt"), &result).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_write_result() {
+        assert_eq!(
+            result_json(b"91JPRV3F\n5GG7EVVJDHJ22\n", false),
+            "{\"file\":\"dir/a \\\"b\\\".txt\",\"status\":\"valid\",\"decoded_len\":13}\n"
+        );
+        assert_eq!(
+            result_json(b"CSQPYRK1\nCSQPU", false),
+            "{\"file\":\"dir/a \\\"b\\\".txt\",\"status\":\"invalid\",\"offset\":13,\
+             \"line\":2,\"column\":5,\"byte\":85,\"message\":\"invalid symbol 'U' at \
+             position 13, 'U' is excluded from the alphabet, did you mean 'V'?\"}\n"
+        );
+        assert_eq!(
+            result_json(b"CSQPYR\n", true),
+            "{\"file\":\"dir/a \\\"b\\\".txt\",\"status\":\"invalid\",\"offset\":7,\
+             \"line\":2,\"column\":1,\"byte\":null,\
+             \"message\":\"invalid length 7, no bytes are encoded to this length; \
+             is a symbol missing or extra?\"}\n"
+        );
+        assert_eq!(
+            result_json(b"CS\x01Q", false),
+            "{\"file\":\"dir/a \\\"b\\\".txt\",\"status\":\"invalid\",\"offset\":2,\
+             \"line\":1,\"column\":3,\"byte\":1,\
+             \"message\":\"invalid symbol '\\\\u{1}' at position 2\"}\n"
+        );
+
+        let mut output = Vec::new();
+        write_str(&mut output, "a\\\n\u{1}é").unwrap();
+        assert_eq!(output, "\"a\\\\\\n\\u0001é\"".as_bytes());
+    }
+}
@@ -0,0 +1,1009 @@
+//! A command line tool which encodes/decodes data in Clockwork Base32.
+//!
+//! Without a subcommand, it takes the same options as GNU `base32(1)`, so it can
+//! replace it in scripts.
+//!
+//! Inputs are encoded and decoded as streams through buffers of [`BUF_LEN`] bytes,
+//! so files of any size are processed in constant memory. With `-j/--jobs`, the
+//! input is encoded or decoded in chunks on multiple threads.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use clockwork_base32::io::{DecodeWriter, EncodeWriter};
+use clockwork_base32::transcode::{TextFormat, TextReader, TextWriter};
+use clockwork_base32::{generate, is_valid_symbol};
+
+mod check;
+mod diagnostic;
+mod json;
+mod parallel;
+mod per_file;
+mod validate;
+use diagnostic::Style;
+use validate::{Checker, Problem};
+
+/// The size of the input and output buffers.
+const BUF_LEN: usize = 64 * 1024;
+
+// The default number of random bits in generated tokens.
+const DEFAULT_BITS: usize = 128;
+
+// The default number of columns to wrap encoded lines after, same as GNU base32.
+const DEFAULT_WRAP: usize = 76;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "cw-base32",
+    version,
+    about,
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Decode the input instead of encoding.
+    #[arg(short, long)]
+    decode: bool,
+
+    #[command(flatten)]
+    args: Args,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Encode the input and write the encoded text.
+    Encode(Args),
+    /// Decode the input, ignoring line breaks, and write the decoded bytes.
+    Decode(Args),
+    /// Check that the inputs are valid encoded text, ignoring line breaks.
+    ///
+    /// Prints the location of the first problem in each invalid input and exits
+    /// with a non-zero status if any input is invalid.
+    Validate(ValidateArgs),
+    /// Print random tokens or ULIDs, one per line.
+    Generate(GenerateArgs),
+    /// Check files against a manifest of their expected encodings, like
+    /// `sha256sum -c`.
+    ///
+    /// Each line of the manifest is an encoding and a file name separated by
+    /// whitespace. Prints OK or FAILED per file and exits with a non-zero
+    /// status if any file didn't match or couldn't be read.
+    Check(CheckArgs),
+    /// Print the completion script for a shell.
+    ///
+    /// For example, `cw-base32 completions bash > /etc/bash_completion.d/cw-base32`.
+    Completions {
+        /// The shell to complete commands in.
+        shell: Shell,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+struct CheckArgs {
+    /// The manifest file. Reads the standard input if omitted or "-".
+    manifest: Option<PathBuf>,
+
+    /// Don't print OK for each file which matched.
+    #[arg(short, long)]
+    quiet: bool,
+}
+
+#[derive(clap::Args, Debug)]
+#[group(multiple = false)]
+struct GenerateKind {
+    /// Encode N random bits, rounded up to whole bytes. This is the default with 128 bits.
+    #[arg(long, value_name = "N")]
+    bits: Option<usize>,
+
+    /// Generate ULIDs of the current time.
+    #[arg(long)]
+    ulid: bool,
+
+    /// Generate N random symbols, which are not always canonical encodings.
+    #[arg(long, value_name = "N")]
+    len: Option<usize>,
+}
+
+#[derive(clap::Args, Debug)]
+struct GenerateArgs {
+    #[command(flatten)]
+    kind: GenerateKind,
+
+    /// The number of values to generate.
+    #[arg(short = 'n', long, default_value_t = 1)]
+    count: usize,
+
+    /// Re-roll values containing any of these comma separated words, where O,
+    /// I and L match 0 and 1. For customer-visible codes like vouchers.
+    #[arg(
+        long,
+        value_name = "WORDS",
+        value_delimiter = ',',
+        conflicts_with = "ulid"
+    )]
+    avoid: Vec<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ValidateArgs {
+    /// The input files. Reads the standard input if omitted or "-".
+    files: Vec<PathBuf>,
+
+    /// Also require canonical encodings: uppercase symbols without the aliases
+    /// I, L and O, a length some bytes encode to, and zero padding bits.
+    #[arg(short, long)]
+    strict: bool,
+
+    /// How to report the results. JSON prints one object per input to the
+    /// standard output.
+    #[arg(long, value_enum, default_value_t = MessageFormat::Text)]
+    message_format: MessageFormat,
+}
+
+#[derive(clap::Args, Debug)]
+struct Args {
+    /// The input files. Reads the standard input if omitted or "-". More than
+    /// one file needs --per-file or --output-dir.
+    files: Vec<PathBuf>,
+
+    /// The output file. Writes to the standard output if omitted or "-".
+    #[arg(short, long, conflicts_with_all = ["per_file", "output_dir"])]
+    output: Option<PathBuf>,
+
+    /// Write each input file to its own output file, named by appending the
+    /// suffix when encoding and removing it when decoding. The input files are
+    /// kept.
+    #[arg(long)]
+    per_file: bool,
+
+    /// The suffix of encoded files for --per-file.
+    #[arg(short = 'S', long, value_name = "SUF", default_value = per_file::DEFAULT_SUFFIX)]
+    suffix: String,
+
+    /// Write the output files of --per-file to DIR instead of next to the
+    /// input files. Implies --per-file.
+    #[arg(long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    /// Overwrite existing output files of --per-file instead of skipping them.
+    #[arg(short, long)]
+    force: bool,
+
+    /// Wrap encoded lines after COLS characters. Use 0 to disable line wrapping.
+    #[arg(short, long, value_name = "COLS", default_value_t = DEFAULT_WRAP)]
+    wrap: usize,
+
+    /// When decoding, ignore bytes which are not valid symbols.
+    #[arg(short, long)]
+    ignore_garbage: bool,
+
+    /// Encode or decode each input line as a separate value, writing one result
+    /// per line without wrapping. A line which fails is reported with its line
+    /// number and written as an empty line, and the rest are still processed.
+    #[arg(long, conflicts_with = "message_format")]
+    lines: bool,
+
+    /// Encode or decode in chunks on N threads, writing the results in order.
+    /// Use 0 for one thread per CPU.
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        conflicts_with_all = ["lines", "message_format"]
+    )]
+    jobs: usize,
+
+    /// The format of the bytes to encode.
+    #[arg(long, value_enum, default_value_t = Format::Raw)]
+    in_format: Format,
+
+    /// The format of the decoded bytes.
+    #[arg(long, value_enum, default_value_t = Format::Raw)]
+    out_format: Format,
+
+    /// How to report the result of decoding. JSON prints an object with the
+    /// location of the first problem, if any, to the standard error.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = MessageFormat::Text,
+        conflicts_with = "ignore_garbage"
+    )]
+    message_format: MessageFormat,
+}
+
+/// The representation of the bytes before encoding or after decoding.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Format {
+    /// Raw bytes.
+    Raw,
+    /// Hex text, ignoring whitespace in input and ending with a newline in output.
+    Hex,
+    /// Base64 text with padding, ignoring whitespace in input and ending with a
+    /// newline in output.
+    Base64,
+}
+
+impl Format {
+    // text returns the text format of the library, or None for raw bytes.
+    fn text(self) -> Option<TextFormat> {
+        match self {
+            Format::Raw => None,
+            Format::Hex => Some(TextFormat::Hex),
+            Format::Base64 => Some(TextFormat::Base64),
+        }
+    }
+}
+
+/// The format of the diagnostics.
+// This is --message-format, like Cargo's, since -o/--output is the output file.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum MessageFormat {
+    /// Human readable messages.
+    Text,
+    /// JSON objects, one per line, for other programs.
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(&cli) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("cw-base32: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// run runs the command of `cli` and returns whether it succeeded. A failure
+// which is not an error has already been reported.
+fn run(cli: &Cli) -> io::Result<bool> {
+    let (args, decoding) = match &cli.command {
+        Some(Command::Encode(args)) => (args, false),
+        Some(Command::Decode(args)) => (args, true),
+        Some(Command::Validate(args)) => return validate_files(args),
+        Some(Command::Check(args)) => return check_manifest(args),
+        Some(Command::Completions { shell }) => {
+            let mut output = create_output(None)?;
+            clap_complete::generate(*shell, &mut Cli::command(), "cw-base32", &mut output);
+            output.flush()?;
+            return Ok(true);
+        }
+        Some(Command::Generate(args)) => {
+            let mut output = create_output(None)?;
+            generate_to(args, &mut output)?;
+            output.flush()?;
+            return Ok(true);
+        }
+        None => (&cli.args, cli.decode),
+    };
+    if decoding && args.in_format != Format::Raw {
+        return Err(usage_error("--in-format applies to encoding only"));
+    }
+    if !decoding && args.out_format != Format::Raw {
+        return Err(usage_error("--out-format applies to decoding only"));
+    }
+    if args.per_file || args.output_dir.is_some() {
+        return per_file::transcode_files(args, decoding);
+    }
+    if args.files.len() > 1 {
+        return Err(usage_error(
+            "more than one input file needs --per-file or --output-dir",
+        ));
+    }
+    let path = args.files.first();
+    let mut input = open_input(path)?;
+    let mut output = create_output(args.output.as_ref())?;
+    let succeeded = transcode(args, decoding, path, &mut input, &mut output)?;
+    output.flush()?;
+    Ok(succeeded)
+}
+
+// transcode encodes or decodes `input` read from `path` to `output` as `args`
+// tell, and returns whether it succeeded.
+fn transcode(
+    args: &Args,
+    decoding: bool,
+    path: Option<&PathBuf>,
+    mut input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> io::Result<bool> {
+    if args.lines {
+        let failed = transcode_lines(input, output, &mut io::stderr().lock(), args, decoding)?;
+        return Ok(failed == 0);
+    }
+    if decoding {
+        if args.message_format == MessageFormat::Json {
+            return decode_with_report(path, &mut input, output, args.out_format);
+        }
+        let style = Style::for_stderr();
+        if style != Style::Plain && args.jobs == 1 && !args.ignore_garbage {
+            return decode_with_diagnostic(path, &mut input, output, args.out_format, style);
+        }
+        write_formatted(output, args.out_format, |output| {
+            decode_jobs(&mut input, output, args)
+        })?;
+    } else {
+        if args.message_format != MessageFormat::Text {
+            return Err(usage_error("--message-format applies to decoding only"));
+        }
+        match args.in_format.text() {
+            None => encode_jobs(&mut input, output, args)?,
+            Some(format) => encode_jobs(&mut TextReader::new(format, &mut input), output, args)?,
+        }
+    }
+    Ok(true)
+}
+
+fn generate_to(args: &GenerateArgs, output: &mut dyn Write) -> io::Result<()> {
+    let kind = &args.kind;
+    let avoid: Vec<&str> = args.avoid.iter().map(String::as_str).collect();
+    for _ in 0..args.count {
+        let value = match (kind.ulid, kind.len) {
+            (true, _) => generate::ulid()?,
+            (false, Some(len)) => generate::symbols_avoiding(len, &avoid)?,
+            (false, None) => {
+                let bits = kind.bits.unwrap_or(DEFAULT_BITS);
+                generate::token_avoiding(bits.div_ceil(8), &avoid)?.into_string()
+            }
+        };
+        writeln!(output, "{}", value)?;
+    }
+    Ok(())
+}
+
+fn check_manifest(args: &CheckArgs) -> io::Result<bool> {
+    let mut manifest = open_input(args.manifest.as_ref())?;
+    let mut output = create_output(None)?;
+    let mut errors = io::stderr().lock();
+    let summary = check::check(&mut manifest, &mut output, &mut errors, args.quiet)?;
+    output.flush()?;
+    summary.warn(&mut errors)?;
+    Ok(summary.is_ok())
+}
+
+fn validate_files(args: &ValidateArgs) -> io::Result<bool> {
+    let stdin = [PathBuf::from("-")];
+    let files = if args.files.is_empty() {
+        &stdin[..]
+    } else {
+        &args.files[..]
+    };
+    let mut valid = true;
+    let mut stdout = io::stdout().lock();
+    let style = Style::for_stderr();
+    for path in files {
+        let mut input = open_input(Some(path))?;
+        let result = validate::validate(&mut input, args.strict)?;
+        match args.message_format {
+            MessageFormat::Text => {
+                if let Err(problem) = &result {
+                    diagnostic::render(&mut io::stderr().lock(), path, problem, style)?;
+                }
+            }
+            MessageFormat::Json => json::write_result(&mut stdout, path, &result)?,
+        }
+        valid &= result.is_ok();
+    }
+    Ok(valid)
+}
+
+// decode_with_report decodes with `decode_checked` and prints the result as
+// JSON to the standard error.
+fn decode_with_report(
+    path: Option<&PathBuf>,
+    input: &mut dyn Read,
+    output: &mut dyn Write,
+    out_format: Format,
+) -> io::Result<bool> {
+    let result = write_formatted(output, out_format, |output| decode_checked(input, output))?;
+    output.flush()?;
+    let stdin = PathBuf::from("-");
+    let path = path.unwrap_or(&stdin);
+    json::write_result(&mut io::stderr().lock(), path, &result)?;
+    Ok(result.is_ok())
+}
+
+// decode_with_diagnostic decodes with `decode_checked` and renders the problem,
+// if any, to the standard error in `style`.
+fn decode_with_diagnostic(
+    path: Option<&PathBuf>,
+    input: &mut dyn Read,
+    output: &mut dyn Write,
+    out_format: Format,
+    style: Style,
+) -> io::Result<bool> {
+    let result = write_formatted(output, out_format, |output| decode_checked(input, output))?;
+    output.flush()?;
+    match result {
+        Ok(_) => Ok(true),
+        Err(problem) => {
+            let stdin = PathBuf::from("-");
+            let path = path.unwrap_or(&stdin);
+            diagnostic::render(&mut io::stderr().lock(), path, &problem, style)?;
+            Ok(false)
+        }
+    }
+}
+
+// write_formatted calls `f` with a writer which writes the bytes written to it
+// to `output` in `format`.
+fn write_formatted<T>(
+    output: &mut dyn Write,
+    format: Format,
+    f: impl FnOnce(&mut dyn Write) -> io::Result<T>,
+) -> io::Result<T> {
+    match format.text() {
+        None => f(output),
+        Some(format) => {
+            let mut writer = TextLineWriter::new(format, output);
+            let result = f(&mut writer)?;
+            writer.finish()?;
+            Ok(result)
+        }
+    }
+}
+
+fn usage_error(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn open_input(path: Option<&PathBuf>) -> io::Result<Box<dyn BufRead>> {
+    match path {
+        Some(path) if path.as_os_str() != "-" => Ok(Box::new(BufReader::with_capacity(
+            BUF_LEN,
+            File::open(path)?,
+        ))),
+        _ => Ok(Box::new(BufReader::with_capacity(
+            BUF_LEN,
+            io::stdin().lock(),
+        ))),
+    }
+}
+
+fn create_output(path: Option<&PathBuf>) -> io::Result<Box<dyn Write>> {
+    match path {
+        Some(path) if path.as_os_str() != "-" => Ok(Box::new(BufWriter::with_capacity(
+            BUF_LEN,
+            File::create(path)?,
+        ))),
+        // The standard output is line buffered, which is slow for wrapped lines.
+        _ => Ok(Box::new(BufWriter::with_capacity(
+            BUF_LEN,
+            io::stdout().lock(),
+        ))),
+    }
+}
+
+// encode_jobs encodes on the number of threads given by `--jobs`.
+fn encode_jobs(input: &mut dyn Read, output: &mut dyn Write, args: &Args) -> io::Result<()> {
+    if args.jobs == 1 {
+        return encode(input, output, args.wrap);
+    }
+    let pool = parallel::thread_pool(args.jobs)?;
+    let mut wrapper = LineWrapper::new(output, args.wrap);
+    parallel::encode(&pool, input, &mut wrapper, parallel::ENCODE_CHUNK_LEN)?;
+    wrapper.finish()
+}
+
+// decode_jobs decodes on the number of threads given by `--jobs`.
+fn decode_jobs(input: &mut dyn Read, output: &mut dyn Write, args: &Args) -> io::Result<()> {
+    if args.jobs == 1 {
+        return decode(input, output, args.ignore_garbage);
+    }
+    let pool = parallel::thread_pool(args.jobs)?;
+    parallel::decode(
+        &pool,
+        input,
+        output,
+        parallel::DECODE_CHUNK_LEN,
+        args.ignore_garbage,
+    )
+}
+
+fn encode(input: &mut dyn Read, output: &mut dyn Write, wrap: usize) -> io::Result<()> {
+    let mut writer = EncodeWriter::new(LineWrapper::new(output, wrap));
+    io::copy(input, &mut writer)?;
+    writer.finish()?.finish()
+}
+
+fn decode(input: &mut dyn Read, output: &mut dyn Write, ignore_garbage: bool) -> io::Result<()> {
+    let mut writer = DecodeWriter::new(output);
+    let mut buf = vec![0; BUF_LEN];
+    loop {
+        let n = match input.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        let is_separator = |b: &u8| {
+            if ignore_garbage {
+                !is_valid_symbol(*b)
+            } else {
+                *b == b'\n' || *b == b'\r'
+            }
+        };
+        for symbols in buf[..n].split(is_separator) {
+            writer.write_all(symbols)?;
+        }
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+// transcode_lines encodes or decodes each line of `input` as a separate value
+// and writes the results one per line. A line which fails is reported to
+// `errors` and written as an empty line, so the results stay aligned with the
+// input lines. Returns the number of failed lines.
+fn transcode_lines(
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+    errors: &mut dyn Write,
+    args: &Args,
+    decoding: bool,
+) -> io::Result<usize> {
+    let mut failed = 0;
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        if input.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+        let value = line.strip_suffix(b"\n").map_or(&line[..], |value| {
+            value.strip_suffix(b"\r").unwrap_or(value)
+        });
+        let result = if decoding {
+            decode_line(value, args.ignore_garbage).and_then(|bytes| match args.out_format.text() {
+                None => output.write_all(&bytes),
+                Some(format) => {
+                    let mut writer = TextWriter::new(format, &mut *output);
+                    writer.write_all(&bytes)?;
+                    writer.finish().map(drop)
+                }
+            })
+        } else {
+            encode_line(value, args.in_format).and_then(|encoded| output.write_all(&encoded))
+        };
+        if let Err(err) = result {
+            writeln!(errors, "cw-base32: line {}: {}", line_number, err)?;
+            failed += 1;
+        }
+        output.write_all(b"\n")?;
+    }
+    Ok(failed)
+}
+
+fn encode_line(line: &[u8], in_format: Format) -> io::Result<Vec<u8>> {
+    match in_format.text() {
+        None => Ok(clockwork_base32::encode_to_vec(line)),
+        Some(format) => {
+            let mut bytes = Vec::new();
+            TextReader::new(format, line).read_to_end(&mut bytes)?;
+            Ok(clockwork_base32::encode_to_vec(&bytes))
+        }
+    }
+}
+
+fn decode_line(line: &[u8], ignore_garbage: bool) -> io::Result<Vec<u8>> {
+    if ignore_garbage {
+        let symbols: Vec<u8> = line
+            .iter()
+            .copied()
+            .filter(|&b| is_valid_symbol(b))
+            .collect();
+        Ok(clockwork_base32::decode_to_vec(&symbols)?)
+    } else {
+        Ok(clockwork_base32::decode_to_vec(line)?)
+    }
+}
+
+// decode_checked decodes like `decode` does without ignoring garbage, but checks
+// each chunk before decoding it, to return where the first problem is. The bytes
+// decoded before the chunk with the problem are written.
+fn decode_checked(
+    input: &mut dyn Read,
+    output: &mut dyn Write,
+) -> io::Result<Result<usize, Problem>> {
+    let mut checker = Checker::new(false);
+    let mut writer = DecodeWriter::new(output);
+    let mut buf = vec![0; BUF_LEN];
+    loop {
+        let n = match input.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        if let Err(problem) = checker.feed(&buf[..n]) {
+            return Ok(Err(problem));
+        }
+        for symbols in buf[..n].split(|&b| b == b'\n' || b == b'\r') {
+            writer.write_all(symbols)?;
+        }
+    }
+    writer.finish()?;
+    Ok(checker.finish())
+}
+
+// A writer which writes the bytes as hex or base64 text, terminated with a
+// newline unless nothing was written.
+struct TextLineWriter<W: Write> {
+    inner: TextWriter<W>,
+    written: bool,
+}
+
+impl<W: Write> TextLineWriter<W> {
+    fn new(format: TextFormat, inner: W) -> Self {
+        Self {
+            inner: TextWriter::new(format, inner),
+            written: false,
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        let mut inner = self.inner.finish()?;
+        if self.written {
+            inner.write_all(b"\n")?;
+        }
+        inner.flush()
+    }
+}
+
+impl<W: Write> Write for TextLineWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written |= n > 0;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// A writer which inserts a newline after every `wrap` bytes.
+struct LineWrapper<W: Write> {
+    inner: W,
+    wrap: usize,
+    column: usize,
+}
+
+impl<W: Write> LineWrapper<W> {
+    fn new(inner: W, wrap: usize) -> Self {
+        Self {
+            inner,
+            wrap,
+            column: 0,
+        }
+    }
+
+    // Terminates the last line like GNU base32 does, unless wrapping is disabled.
+    fn finish(mut self) -> io::Result<()> {
+        if self.column > 0 {
+            self.inner.write_all(b"\n")?;
+        }
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Write for LineWrapper<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.wrap == 0 {
+            return self.inner.write(buf);
+        }
+        if self.column == self.wrap {
+            self.inner.write_all(b"\n")?;
+            self.column = 0;
+        }
+        let len = buf.len().min(self.wrap - self.column);
+        self.inner.write_all(&buf[..len])?;
+        self.column += len;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_to_vec(input: &[u8], wrap: usize) -> Vec<u8> {
+        let mut output = Vec::new();
+        encode(&mut &input[..], &mut output, wrap).unwrap();
+        output
+    }
+
+    fn decode_to_vec(input: &[u8], ignore_garbage: bool) -> io::Result<Vec<u8>> {
+        let mut output = Vec::new();
+        decode(&mut &input[..], &mut output, ignore_garbage)?;
+        Ok(output)
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        assert_eq!(encode_to_vec(b"", DEFAULT_WRAP), b"");
+        assert_eq!(
+            encode_to_vec(b"Hello, world!", DEFAULT_WRAP),
+            b"91JPRV3F5GG7EVVJDHJ22\n"
+        );
+        assert_eq!(
+            decode_to_vec(b"91JPRV3F5GG7EVVJDHJ22\n", false).unwrap(),
+            b"Hello, world!"
+        );
+        assert_eq!(
+            decode_to_vec(b"91JPRV3F\r\n5GG7EVVJ\nDHJ22", false).unwrap(),
+            b"Hello, world!"
+        );
+
+        let err = decode_to_vec(b"CSQPU", false).unwrap_err();
+        assert_eq!(err.to_string(), "invalid symbol value U");
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(encode_to_vec(b"Hello, world!", 0), b"91JPRV3F5GG7EVVJDHJ22");
+        assert_eq!(
+            encode_to_vec(b"Hello, world!", 8),
+            b"91JPRV3F\n5GG7EVVJ\nDHJ22\n"
+        );
+        assert_eq!(encode_to_vec(b"fooba", 8), b"CSQPYRK1\n");
+        let input = vec![0xa5; 1000];
+        let wrapped = encode_to_vec(&input, 7);
+        assert!(wrapped.split(|&b| b == b'\n').all(|line| line.len() <= 7));
+        assert_eq!(decode_to_vec(&wrapped, false).unwrap(), input);
+    }
+
+    #[test]
+    fn test_ignore_garbage() {
+        let input = b"91JP-RV3F 5GG7EVVJ#DHJ22\n";
+        assert!(decode_to_vec(input, false).is_err());
+        assert_eq!(decode_to_vec(input, true).unwrap(), b"Hello, world!");
+    }
+
+    #[test]
+    fn test_stream() {
+        // Counts the written bytes instead of keeping them.
+        struct Counter(usize);
+
+        impl Write for Counter {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0 += buf.len();
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let len = 10 * BUF_LEN + 3;
+        let mut counter = Counter(0);
+        encode(&mut io::repeat(0xa5).take(len as u64), &mut counter, 0).unwrap();
+        assert_eq!(counter.0, clockwork_base32::capacity_hint_for_encode(len));
+
+        let mut counter = Counter(0);
+        let mut input = io::repeat(b'Z').take(8 * len as u64);
+        decode(&mut input, &mut counter, false).unwrap();
+        assert_eq!(counter.0, 5 * len);
+    }
+
+    #[test]
+    fn test_decode_checked() {
+        let mut output = Vec::new();
+        let result = decode_checked(&mut &b"91JPRV3F\n5GG7EVVJDHJ22\n"[..], &mut output).unwrap();
+        assert_eq!(result, Ok(13));
+        assert_eq!(output, b"Hello, world!");
+
+        let mut output = Vec::new();
+        let problem = decode_checked(&mut &b"91JPRV3F\n5GG7-EVVJ"[..], &mut output)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(
+            (problem.error.position(), problem.line, problem.column),
+            (13, 2, 5)
+        );
+        assert_eq!(problem.error.byte(), Some(b'-'));
+    }
+
+    #[test]
+    fn test_lines() {
+        let transcode = |args: &[&str], input: &[u8]| {
+            let cli = Cli::try_parse_from([&["cw-base32", "--lines"], args].concat()).unwrap();
+            let mut output = Vec::new();
+            let mut errors = Vec::new();
+            let failed = transcode_lines(
+                &mut &input[..],
+                &mut output,
+                &mut errors,
+                &cli.args,
+                cli.decode,
+            )
+            .unwrap();
+            (
+                String::from_utf8(output).unwrap(),
+                String::from_utf8(errors).unwrap(),
+                failed,
+            )
+        };
+
+        assert_eq!(
+            transcode(&[], b"Hello\r\n\nfooba"),
+            ("91JPRV3F\n\nCSQPYRK1\n".to_string(), String::new(), 0)
+        );
+        assert_eq!(
+            transcode(&["-d"], b"91JPRV3F\nCSQPU\r\nCSQPYRK1\n"),
+            (
+                "Hello\n\nfooba\n".to_string(),
+                "cw-base32: line 2: invalid symbol value U\n".to_string(),
+                1
+            )
+        );
+        assert_eq!(
+            transcode(&["-d", "-i", "--out-format", "hex"], b"91JP-RV3F\n"),
+            ("48656c6c6f\n".to_string(), String::new(), 0)
+        );
+        assert_eq!(
+            transcode(&["-d", "--out-format", "base64"], b"91JPRV3F\n\nCSQPYRK1\n"),
+            ("SGVsbG8=\n\nZm9vYmE=\n".to_string(), String::new(), 0)
+        );
+        assert_eq!(
+            transcode(&["--in-format", "base64"], b"SGVsbG8=\nZm9vYmE\n"),
+            ("91JPRV3F\nCSQPYRK1\n".to_string(), String::new(), 0)
+        );
+        assert_eq!(
+            transcode(&["--in-format", "hex"], b"48656c6c6f\n4x\n"),
+            (
+                "91JPRV3F\n\n".to_string(),
+                "cw-base32: line 2: invalid hex symbol 'x'\n".to_string(),
+                1
+            )
+        );
+        assert!(
+            Cli::try_parse_from(["cw-base32", "-d", "--lines", "--message-format=json"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_text_formats() {
+        for (format, text) in [
+            (TextFormat::Hex, &b"48656c6C6f\n"[..]),
+            (TextFormat::Base64, b"SGVs\nbG8=\n"),
+        ] {
+            let mut output = Vec::new();
+            encode(&mut TextReader::new(format, text), &mut output, 0).unwrap();
+            assert_eq!(output, b"91JPRV3F");
+        }
+
+        for (format, input, expected) in [
+            (Format::Hex, &b"91JPRV3F"[..], &b"48656c6c6f\n"[..]),
+            (Format::Hex, b"", b""),
+            (Format::Base64, b"91JPRV3F", b"SGVsbG8=\n"),
+            (Format::Base64, b"", b""),
+        ] {
+            let mut output = Vec::new();
+            write_formatted(&mut output, format, |writer| {
+                decode(&mut &input[..], writer, false)
+            })
+            .unwrap();
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn test_generate() {
+        let generate_lines = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["cw-base32", "generate"], args].concat()).unwrap();
+            let args = match cli.command {
+                Some(Command::Generate(args)) => args,
+                command => panic!("unexpected command {:?}", command),
+            };
+            let mut output = Vec::new();
+            generate_to(&args, &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let lengths = |output: String| output.lines().map(str::len).collect::<Vec<_>>();
+        assert_eq!(lengths(generate_lines(&[])), [26]);
+        assert_eq!(
+            lengths(generate_lines(&["--bits", "60", "-n", "2"])),
+            [13, 13]
+        );
+        assert_eq!(
+            lengths(generate_lines(&["--ulid", "-n", "3"])),
+            [26, 26, 26]
+        );
+        assert_eq!(lengths(generate_lines(&["--len", "9"])), [9]);
+        let output = generate_lines(&["--len", "9", "-n", "20", "--avoid", "0,x"]);
+        assert!(!output.contains('0') && !output.contains('X'));
+        assert!(Cli::try_parse_from(["cw-base32", "generate", "--ulid", "--avoid", "A"]).is_err());
+        assert!(Cli::try_parse_from(["cw-base32", "generate", "--ulid", "--len", "9"]).is_err());
+    }
+
+    #[test]
+    fn test_completions() {
+        for (shell, needle) in [
+            (Shell::Bash, "complete -F _cw"),
+            (Shell::Zsh, "#compdef cw-base32"),
+            (Shell::Fish, "complete -c cw-base32"),
+            (Shell::PowerShell, "Register-ArgumentCompleter"),
+        ] {
+            let mut output = Vec::new();
+            clap_complete::generate(shell, &mut Cli::command(), "cw-base32", &mut output);
+            let output = String::from_utf8(output).unwrap();
+            assert!(output.contains(needle), "{}", shell);
+            assert!(output.contains("validate"), "{}", shell);
+        }
+        let cli = Cli::try_parse_from(["cw-base32", "completions", "zsh"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Completions { shell: Shell::Zsh })
+        ));
+        assert!(Cli::try_parse_from(["cw-base32", "completions", "tcsh"]).is_err());
+    }
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["cw-base32", "decode", "in.txt", "-o", "out.bin"]).unwrap();
+        match cli.command {
+            Some(Command::Decode(args)) => {
+                assert_eq!(args.files, [PathBuf::from("in.txt")]);
+                assert_eq!(args.output, Some(PathBuf::from("out.bin")));
+                assert_eq!(args.wrap, DEFAULT_WRAP);
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        let cli = Cli::try_parse_from(["cw-base32", "-d", "-i", "in.txt"]).unwrap();
+        assert!(cli.command.is_none() && cli.decode && cli.args.ignore_garbage);
+        let cli = Cli::try_parse_from(["cw-base32", "--in-format", "hex"]).unwrap();
+        assert_eq!(cli.args.in_format, Format::Hex);
+        let cli = Cli::try_parse_from(["cw-base32", "--in-format", "base64"]).unwrap();
+        assert_eq!(cli.args.in_format, Format::Base64);
+        assert!(Cli::try_parse_from(["cw-base32", "--in-format", "base58"]).is_err());
+        let cli = Cli::try_parse_from(["cw-base32", "validate", "--strict", "a", "b"]).unwrap();
+        match cli.command {
+            Some(Command::Validate(args)) => {
+                assert!(args.strict);
+                assert_eq!(args.files, [PathBuf::from("a"), PathBuf::from("b")]);
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+        let cli = Cli::try_parse_from(["cw-base32", "-d", "--message-format", "json"]).unwrap();
+        assert_eq!(cli.args.message_format, MessageFormat::Json);
+        assert!(Cli::try_parse_from(["cw-base32", "-d", "-i", "--message-format=json"]).is_err());
+        let cli = Cli::try_parse_from(["cw-base32", "validate", "--message-format=json"]).unwrap();
+        match cli.command {
+            Some(Command::Validate(args)) => assert_eq!(args.message_format, MessageFormat::Json),
+            command => panic!("unexpected command {:?}", command),
+        }
+        let cli = Cli::try_parse_from(["cw-base32", "-d", "-j", "0"]).unwrap();
+        assert_eq!(cli.args.jobs, 0);
+        assert!(Cli::try_parse_from(["cw-base32", "-j", "4", "--lines"]).is_err());
+        let cli = Cli::try_parse_from(["cw-base32", "check", "-q", "SUMS"]).unwrap();
+        match cli.command {
+            Some(Command::Check(args)) => {
+                assert!(args.quiet);
+                assert_eq!(args.manifest, Some(PathBuf::from("SUMS")));
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+        let cli = Cli::try_parse_from(["cw-base32", "--wrap=0"]).unwrap();
+        assert!(!cli.decode && cli.args.wrap == 0 && cli.args.files.is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+//! Encoding and decoding on multiple threads with `rayon`.
+//!
+//! The input is read in batches of one chunk per thread. The chunks are split
+//! on block boundaries, so each one is encoded or decoded independently, and the
+//! results are written in the input order.
+
+use std::io::{self, Read, Write};
+
+use clockwork_base32::{is_valid_symbol, Error};
+use rayon::prelude::*;
+use rayon::ThreadPool;
+
+// The number of symbols encoding a block of 5 bytes.
+const BLOCK_SYMBOL_LEN: usize = 8;
+
+/// The number of bytes each thread encodes at once, a multiple of 5 bytes.
+pub const ENCODE_CHUNK_LEN: usize = 5 * 256 * 1024;
+
+/// The number of symbols each thread decodes at once, a multiple of 8 symbols.
+pub const DECODE_CHUNK_LEN: usize = 8 * 256 * 1024;
+
+/// Builds a thread pool with `jobs` threads, or one per CPU if `jobs` is zero.
+pub fn thread_pool(jobs: usize) -> io::Result<ThreadPool> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(io::Error::other)
+}
+
+/// Encodes `input` in chunks of `chunk_len` bytes on the threads of `pool`.
+pub fn encode(
+    pool: &ThreadPool,
+    input: &mut dyn Read,
+    output: &mut dyn Write,
+    chunk_len: usize,
+) -> io::Result<()> {
+    let mut batch = vec![0; chunk_len * pool.current_num_threads()];
+    loop {
+        let n = read_full(input, &mut batch)?;
+        if n == 0 {
+            return Ok(());
+        }
+        let encoded: Vec<Vec<u8>> = pool.install(|| {
+            batch[..n]
+                .par_chunks(chunk_len)
+                .map(clockwork_base32::encode_to_vec)
+                .collect()
+        });
+        for chunk in &encoded {
+            output.write_all(chunk)?;
+        }
+        if n < batch.len() {
+            return Ok(());
+        }
+    }
+}
+
+/// Decodes `input` in chunks of `chunk_len` symbols on the threads of `pool`,
+/// skipping line breaks, or all bytes which are not valid symbols if
+/// `ignore_garbage` is true.
+///
+/// The position of an invalid symbol is counted in the symbols of the whole
+/// input, as when decoding on one thread.
+pub fn decode(
+    pool: &ThreadPool,
+    input: &mut dyn Read,
+    output: &mut dyn Write,
+    chunk_len: usize,
+    ignore_garbage: bool,
+) -> io::Result<()> {
+    let batch_len = chunk_len * pool.current_num_threads();
+    let mut buf = vec![0; batch_len];
+    // The symbols read but not decoded yet, less than a block after decoding.
+    let mut symbols = Vec::with_capacity(batch_len + BLOCK_SYMBOL_LEN);
+    // The number of symbols decoded in the earlier batches.
+    let mut offset = 0;
+    loop {
+        let n = read_full(input, &mut buf)?;
+        symbols.extend(buf[..n].iter().copied().filter(|&b| {
+            if ignore_garbage {
+                is_valid_symbol(b)
+            } else {
+                b != b'\n' && b != b'\r'
+            }
+        }));
+        let end = n < buf.len();
+        // Leave the last partial block for the next batch, unless this is the
+        // end of the input.
+        let len = if end {
+            symbols.len()
+        } else {
+            symbols.len() / BLOCK_SYMBOL_LEN * BLOCK_SYMBOL_LEN
+        };
+        let decoded: Vec<Vec<u8>> = pool.install(|| {
+            symbols[..len]
+                .par_chunks(chunk_len)
+                .enumerate()
+                .map(|(i, chunk)| {
+                    clockwork_base32::decode_to_vec(chunk)
+                        .map_err(|err| offset_by(err, offset + i * chunk_len))
+                })
+                .collect::<Result<_, _>>()
+        })?;
+        for chunk in &decoded {
+            output.write_all(chunk)?;
+        }
+        symbols.drain(..len);
+        offset += len;
+        if end {
+            return Ok(());
+        }
+    }
+}
+
+// offset_by returns `err` with `offset` added to its position, for an error
+// found in a chunk starting at `offset` in the symbols.
+fn offset_by(err: Error, offset: usize) -> Error {
+    match err {
+        Error::InvalidSymbol { byte, position } => Error::InvalidSymbol {
+            byte,
+            position: position + offset,
+        },
+        err => err,
+    }
+}
+
+// read_full reads until `buf` is full or the end of `input`, and returns the
+// number of bytes read.
+fn read_full(input: &mut dyn Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match input.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel() {
+        let pool = thread_pool(3).unwrap();
+        let input: Vec<u8> = (0..1000).map(|i| (i * 31) as u8).collect();
+        for len in [0, 1, 5, 29, 30, 31, 500, 1000] {
+            let input = &input[..len];
+            let expected = clockwork_base32::encode_to_vec(input);
+            for chunk_len in [5, 10, 25] {
+                let mut encoded = Vec::new();
+                encode(&pool, &mut &input[..], &mut encoded, chunk_len).unwrap();
+                assert_eq!(encoded, expected);
+            }
+
+            let wrapped: Vec<u8> = expected
+                .chunks(7)
+                .flat_map(|line| line.iter().chain(b"\r\n"))
+                .copied()
+                .collect();
+            for chunk_len in [8, 16, 40] {
+                let mut decoded = Vec::new();
+                decode(&pool, &mut &wrapped[..], &mut decoded, chunk_len, false).unwrap();
+                assert_eq!(decoded, input);
+            }
+        }
+
+        let mut decoded = Vec::new();
+        decode(
+            &pool,
+            &mut &b"91JP-RV3F 5GG7EVVJ#DHJ22\n"[..],
+            &mut decoded,
+            8,
+            true,
+        )
+        .unwrap();
+        assert_eq!(decoded, b"Hello, world!");
+        let err = decode(
+            &pool,
+            &mut &b"91JPRV3F5GG7EVVJDHJ2U"[..],
+            &mut Vec::new(),
+            8,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "invalid symbol value U");
+    }
+
+    #[test]
+    fn test_error_position() {
+        let pool = thread_pool(3).unwrap();
+        let position = |input: &[u8], chunk_len| {
+            let err =
+                decode(&pool, &mut &input[..], &mut Vec::new(), chunk_len, false).unwrap_err();
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<Error>())
+                .and_then(Error::position)
+        };
+        let sequential = |input: &[u8]| {
+            let err = crate::decode(&mut &input[..], &mut Vec::new(), false).unwrap_err();
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<Error>())
+                .and_then(Error::position)
+        };
+
+        // The invalid symbol is in the third chunk of the first batch, and in
+        // the second batch.
+        let input = b"91JPRV3F\n5GG7EVVJ\nDHJ2U";
+        assert_eq!(position(input, 8), Some(20));
+        assert_eq!(position(input, 8), sequential(input));
+        let mut input = b"CSQPYRK1\n".repeat(7);
+        input.extend_from_slice(b"CSQPU");
+        assert_eq!(position(&input, 8), Some(60));
+        assert_eq!(position(&input, 8), sequential(&input));
+    }
+}
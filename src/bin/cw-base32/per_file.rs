@@ -0,0 +1,207 @@
+//! Transcoding each input file to its own output file, like `gzip` does.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clockwork_base32::fs;
+
+use super::{open_input, transcode, Args};
+
+/// The default suffix of encoded files.
+pub const DEFAULT_SUFFIX: &str = ".cb32";
+
+/// Encodes or decodes each input file of `args` to its own output file.
+///
+/// Each output file is written atomically, so a file which fails is reported
+/// and leaves no partial output and any existing output as it was, and the rest
+/// are still processed. Returns whether every file succeeded.
+pub fn transcode_files(args: &Args, decoding: bool) -> io::Result<bool> {
+    let mut succeeded = true;
+    for path in &args.files {
+        let result = output_path(path, decoding, &args.suffix, args.output_dir.as_deref())
+            .and_then(|output_path| transcode_file(args, decoding, path, &output_path));
+        match result {
+            Ok(true) => {}
+            Ok(false) => succeeded = false,
+            Err(err) => {
+                eprintln!("cw-base32: {}: {}", path.display(), err);
+                succeeded = false;
+            }
+        }
+    }
+    Ok(succeeded)
+}
+
+fn transcode_file(
+    args: &Args,
+    decoding: bool,
+    path: &PathBuf,
+    output_path: &Path,
+) -> io::Result<bool> {
+    let mut input = open_input(Some(path))?;
+    if !args.force && output_path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists, skipped; use --force to overwrite it",
+                output_path.display()
+            ),
+        ));
+    }
+    // A failure which `transcode` already reported comes back as `false`, so
+    // it is turned into an error for `write_atomic` to discard the output.
+    let mut succeeded = true;
+    let result = fs::write_atomic(output_path, |output| {
+        succeeded = transcode(args, decoding, Some(path), &mut input, output)?;
+        if succeeded {
+            Ok(())
+        } else {
+            Err(io::ErrorKind::InvalidData.into())
+        }
+    });
+    match result {
+        Err(_) if !succeeded => Ok(false),
+        result => result.map(|()| succeeded),
+    }
+}
+
+// output_path returns the path of the output file for `path`: with `suffix`
+// appended when encoding or removed when decoding, in `output_dir` if given.
+fn output_path(
+    path: &Path,
+    decoding: bool,
+    suffix: &str,
+    output_dir: Option<&Path>,
+) -> io::Result<PathBuf> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidInput, message);
+    if path.as_os_str() == "-" {
+        return Err(invalid(
+            "the standard input has no file name to derive the output file from",
+        ));
+    }
+    let name = path.file_name().ok_or_else(|| invalid("not a file name"))?;
+    let mut name = name.to_os_string();
+    if decoding {
+        let stripped = name
+            .to_str()
+            .and_then(|name| name.strip_suffix(suffix))
+            .filter(|stripped| !stripped.is_empty())
+            .ok_or_else(|| invalid(&format!("no {} suffix, skipped", suffix)))?;
+        name = stripped.into();
+    } else {
+        name.push(suffix);
+    }
+    Ok(match output_dir {
+        Some(dir) => dir.join(name),
+        None => path.with_file_name(name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use std::fs;
+
+    #[test]
+    fn test_output_path() {
+        let output = |path: &str, decoding, output_dir: Option<&str>| {
+            output_path(
+                Path::new(path),
+                decoding,
+                DEFAULT_SUFFIX,
+                output_dir.map(Path::new),
+            )
+            .map_err(|err| err.to_string())
+        };
+        assert_eq!(
+            output("dir/a.bin", false, None),
+            Ok(PathBuf::from("dir/a.bin.cb32"))
+        );
+        assert_eq!(
+            output("dir/a.bin.cb32", true, None),
+            Ok(PathBuf::from("dir/a.bin"))
+        );
+        assert_eq!(
+            output("dir/a.bin", false, Some("out")),
+            Ok(PathBuf::from("out/a.bin.cb32"))
+        );
+        assert_eq!(
+            output("a.bin", true, None),
+            Err("no .cb32 suffix, skipped".to_string())
+        );
+        assert!(output(".cb32", true, None).is_err());
+        assert!(output("-", false, None).is_err());
+    }
+
+    #[test]
+    fn test_transcode_files() {
+        let dir = std::env::temp_dir().join(format!("cw-base32-per-file-{}", std::process::id()));
+        let out = dir.join("out");
+        fs::create_dir_all(&out).unwrap();
+        let a = dir.join("a.bin");
+        let b = dir.join("b.bin");
+        fs::write(&a, b"Hello, world!").unwrap();
+        fs::write(&b, b"fooba").unwrap();
+
+        let try_run = |args: &[&Path]| {
+            let args: Vec<_> = ["cw-base32".as_ref()]
+                .iter()
+                .chain(args)
+                .map(|arg| arg.as_os_str())
+                .collect();
+            let cli = crate::Cli::try_parse_from(args).unwrap();
+            crate::run(&cli)
+        };
+        let run = |args: &[&Path]| try_run(args).unwrap();
+        let per_file: &Path = "--per-file".as_ref();
+        // More than one file need --per-file.
+        assert!(try_run(&[&a, &b]).is_err());
+        assert!(!dir.join("a.bin.cb32").exists());
+        assert!(run(&[per_file, &a, &b]));
+        assert_eq!(
+            fs::read(dir.join("a.bin.cb32")).unwrap(),
+            b"91JPRV3F5GG7EVVJDHJ22\n"
+        );
+        assert_eq!(fs::read(dir.join("b.bin.cb32")).unwrap(), b"CSQPYRK1\n");
+
+        // The existing outputs are not overwritten without --force.
+        fs::write(&a, b"foo").unwrap();
+        assert!(!run(&[per_file, &a, &b]));
+        assert_eq!(
+            fs::read(dir.join("a.bin.cb32")).unwrap(),
+            b"91JPRV3F5GG7EVVJDHJ22\n"
+        );
+        assert!(run(&[per_file, "-f".as_ref(), &a]));
+        assert_eq!(fs::read(dir.join("a.bin.cb32")).unwrap(), b"CSQPY\n");
+
+        let encoded = dir.join("a.bin.cb32");
+        let output_dir = ["--output-dir".as_ref(), out.as_path()];
+        assert!(run(
+            &[&["-d".as_ref()], &output_dir[..], &[&encoded]].concat()
+        ));
+        assert_eq!(fs::read(out.join("a.bin")).unwrap(), b"foo");
+
+        // A failed run leaves an existing output as it was even with --force,
+        // and no partial output, also when the failure is reported without an
+        // error like with --message-format json.
+        fs::write(&encoded, b"CSQPU").unwrap();
+        let json: [&Path; 2] = ["--message-format".as_ref(), "json".as_ref()];
+        for format in [&[][..], &json[..]] {
+            let failing: Vec<&Path> = [
+                &["-d".as_ref(), "-f".as_ref()],
+                format,
+                &output_dir[..],
+                &[encoded.as_path()],
+            ]
+            .concat();
+            assert!(!run(&failing));
+            assert_eq!(fs::read(out.join("a.bin")).unwrap(), b"foo");
+            fs::remove_file(out.join("a.bin")).unwrap();
+            assert!(!run(&failing));
+            assert_eq!(fs::read_dir(&out).unwrap().count(), 0);
+            fs::write(out.join("a.bin"), b"foo").unwrap();
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
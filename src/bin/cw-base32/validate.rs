@@ -0,0 +1,297 @@
+//! Checks encoded input without decoding it, reporting where the first problem is.
+//!
+//! The checks are those of the library's [`Validator`] with line breaks ignored
+//! like decoding does. This module adds the line, the column and the line around
+//! the problem for the messages.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Read};
+use std::rc::Rc;
+
+use clockwork_base32::validator::Validator;
+use clockwork_base32::{ErrorKind, ParseError};
+
+/// A problem found in the input.
+#[derive(Debug, PartialEq)]
+pub struct Problem {
+    /// The problem as the library reports it, with its byte offset in the input.
+    pub error: ParseError,
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 1-based column in bytes.
+    pub column: usize,
+    /// The part of the line around the offending byte, if any.
+    pub context: Option<Context>,
+}
+
+/// The line of an offending byte, for showing where it is with
+/// [`Snippet`](clockwork_base32::Snippet).
+#[derive(Debug, PartialEq)]
+pub struct Context {
+    /// The bytes of the line in the chunk the problem was found in.
+    pub text: Vec<u8>,
+    /// The index of the offending byte in `text`.
+    pub index: usize,
+    /// Whether the line started in an earlier chunk.
+    pub continued: bool,
+}
+
+impl Problem {
+    fn new(error: ParseError, location: Location) -> Self {
+        Self {
+            error,
+            line: location.line,
+            column: location.column,
+            context: None,
+        }
+    }
+
+    // with_context returns the problem with its line around the byte at
+    // `index` in `buf`, the chunk the problem was found in.
+    fn with_context(mut self, buf: &[u8], index: usize) -> Self {
+        let line_start = buf[..index]
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(0, |i| i + 1);
+        let line_end = buf[index..]
+            .iter()
+            .position(|&b| is_line_break(b))
+            .map_or(buf.len(), |i| index + i);
+        self.context = Some(Context {
+            text: buf[line_start..line_end].to_vec(),
+            index: index - line_start,
+            continued: self.column - 1 > index - line_start,
+        });
+        self
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.error)
+    }
+}
+
+// A location in the input.
+#[derive(Clone, Copy, Debug)]
+struct Location {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Location {
+    // advance moves the location past the byte `b`.
+    fn advance(&mut self, b: u8) {
+        self.offset += 1;
+        if b == b'\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+    }
+}
+
+// The callback of the validator, which keeps the first problem not ignored.
+type OnProblem = Box<dyn FnMut(&ParseError)>;
+
+/// Checks encoded input fed in chunks, ignoring line breaks like decoding does.
+///
+/// In strict mode, the input must also be a canonical encoding: uppercase
+/// symbols without aliases, a length some bytes encode to, and zero padding bits.
+pub struct Checker {
+    validator: Validator<OnProblem>,
+    // The first problem the validator reported which is not ignored.
+    first_problem: Rc<RefCell<Option<ParseError>>>,
+    // The locations of the start of the next chunk and of the last symbol.
+    location: Location,
+    last_symbol: Location,
+}
+
+impl Checker {
+    pub fn new(strict: bool) -> Self {
+        let first_problem = Rc::new(RefCell::new(None));
+        let found = Rc::clone(&first_problem);
+        let on_problem = move |err: &ParseError| {
+            let mut found = found.borrow_mut();
+            if found.is_none() && is_reported(err, strict) {
+                *found = Some(err.clone());
+            }
+        };
+        let start = Location {
+            offset: 0,
+            line: 1,
+            column: 1,
+        };
+        Self {
+            validator: Validator::with_callback(Box::new(on_problem)),
+            first_problem,
+            location: start,
+            last_symbol: start,
+        }
+    }
+
+    /// Checks the next chunk of the input.
+    pub fn feed(&mut self, buf: &[u8]) -> Result<(), Problem> {
+        self.validator.update(buf);
+        if let Some(error) = self.first_problem.borrow_mut().take() {
+            let index = error.position() - self.location.offset;
+            let mut location = self.location;
+            for &b in &buf[..index] {
+                location.advance(b);
+            }
+            return Err(Problem::new(error, location).with_context(buf, index));
+        }
+        for &b in buf {
+            if !is_line_break(b) {
+                self.last_symbol = self.location;
+            }
+            self.location.advance(b);
+        }
+        Ok(())
+    }
+
+    /// Finishes checking, returning the decoded length.
+    pub fn finish(self) -> Result<usize, Problem> {
+        let validation = self.validator.finish();
+        match self.first_problem.take() {
+            None => Ok(validation.decoded_len()),
+            // An invalid length is at the end of the input, and non-zero
+            // padding bits are in the last symbol.
+            Some(error) if error.position() == self.location.offset => {
+                Err(Problem::new(error, self.location))
+            }
+            Some(error) => Err(Problem::new(error, self.last_symbol)),
+        }
+    }
+}
+
+// is_reported returns whether the library's `err` is a problem: line breaks are
+// ignored, and non-canonical input is a problem only if `strict` is true.
+fn is_reported(err: &ParseError, strict: bool) -> bool {
+    match err.kind() {
+        ErrorKind::InvalidSymbol => !err.byte().is_some_and(is_line_break),
+        _ => strict,
+    }
+}
+
+fn is_line_break(b: u8) -> bool {
+    b == b'\n' || b == b'\r'
+}
+
+/// Checks the whole input read from `input`.
+///
+/// Returns `Ok(Err(problem))` if the input is invalid.
+pub fn validate(input: &mut dyn Read, strict: bool) -> io::Result<Result<usize, Problem>> {
+    let mut checker = Checker::new(strict);
+    let mut buf = vec![0; crate::BUF_LEN];
+    loop {
+        let n = match input.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        if let Err(problem) = checker.feed(&buf[..n]) {
+            return Ok(Err(problem));
+        }
+    }
+    Ok(checker.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(input: &[u8], strict: bool) -> Result<usize, String> {
+        validate(&mut &input[..], strict)
+            .unwrap()
+            .map_err(|problem| problem.to_string())
+    }
+
+    #[test]
+    fn test_lenient() {
+        assert_eq!(check(b"", false), Ok(0));
+        assert_eq!(check(b"91JPRV3F\r\n5GG7EVVJ\nDHJ22\n", false), Ok(13));
+        assert_eq!(check(b"csqpyr", false), Ok(3));
+        assert_eq!(
+            check(b"91JPRV3F\n5GG7-EVVJ\n", false),
+            Err("2:5: invalid symbol '-' at position 13, remove hyphens".to_string())
+        );
+        assert_eq!(
+            check(b"CSQPU", false),
+            Err(
+                "1:5: invalid symbol 'U' at position 4, 'U' is excluded from the \
+                 alphabet, did you mean 'V'?"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            check(b"CS\xffQ", false),
+            Err("1:3: invalid symbol 'ÿ' at position 2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_context() {
+        let context = |input: &[u8]| {
+            validate(&mut &input[..], true)
+                .unwrap()
+                .unwrap_err()
+                .context
+                .unwrap()
+        };
+        assert_eq!(
+            context(b"CSQPYRK1\r\n5GG7-EVVJ\r\nDHJ22"),
+            Context {
+                text: b"5GG7-EVVJ".to_vec(),
+                index: 4,
+                continued: false,
+            }
+        );
+        let mut input = vec![b'0'; 100];
+        input[50] = b'u';
+        assert_eq!(
+            context(&input),
+            Context {
+                text: input.clone(),
+                index: 50,
+                continued: false,
+            }
+        );
+
+        // The line started in an earlier chunk.
+        let mut checker = Checker::new(false);
+        checker.feed(b"CSQPYRK1\nCSQ").unwrap();
+        let problem = checker.feed(b"PU").unwrap_err();
+        let context = problem.context.unwrap();
+        assert_eq!((&context.text[..], context.index), (&b"PU"[..], 1));
+        assert!(context.continued);
+        let problem = validate(&mut &b"CSQPYR"[..], true).unwrap().unwrap_err();
+        assert_eq!(problem.context, None);
+    }
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(check(b"91JPRV3F\n5GG7EVVJ\nDHJ22\n", true), Ok(13));
+        assert_eq!(check(b"CSQPYRK1", true), Ok(5));
+        assert_eq!(
+            check(b"CSQPYRK1\nCSqP", true),
+            Err("2:3: non-canonical symbol at position 11, expected 'Q'".to_string())
+        );
+        assert_eq!(
+            check(b"CSQPYRK1\nCSQPYR\n", true),
+            Err(
+                "3:1: invalid length 16, no bytes are encoded to this length; \
+                 is a symbol missing or extra?"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            check(b"CSQPYRK1\nCSQPYRK\n", true),
+            Err("2:7: non-canonical symbol at position 15, expected 'G'".to_string())
+        );
+    }
+}
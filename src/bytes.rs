@@ -0,0 +1,282 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+
+use crate::{
+    find_invalid_symbol, fmt_encoded, Base32String, IntoInput, ParseError, Result, CLOCKWORK,
+};
+
+/// Owned bytes which are displayed and serialized as an encoded string.
+///
+/// Unlike [`Base32String`], which holds the encoded text, this holds the decoded
+/// bytes. Parsing accepts any valid input, including lowercase and aliased symbols.
+///
+/// With the `serde` feature enabled, this implements `Serialize` and `Deserialize`
+/// with the helpers in the `serde` module.
+///
+/// With the `schemars` feature enabled, this implements `schemars::JsonSchema`
+/// as a string with a pattern constraint.
+///
+/// With the `arbitrary` feature enabled, this implements `arbitrary::Arbitrary`
+/// for fuzzing.
+///
+/// With the `zeroize` feature enabled, this implements `Zeroize` and
+/// `ZeroizeOnDrop`, so the bytes are wiped when dropped.
+///
+/// With the `subtle` feature enabled, this implements `ConstantTimeEq`.
+///
+/// With the `defmt` feature enabled, this implements `defmt::Format`, which
+/// logs the bytes encoded like [`Display`](fmt::Display) without allocating.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32::Base32Bytes;
+///
+/// let bytes = Base32Bytes::decode("csqpyrk1e8")?;
+/// assert_eq!(&*bytes, b"foobar");
+/// assert_eq!(bytes.to_string(), "CSQPYRK1E8");
+/// assert_eq!(format!("{:?}", bytes), "Base32Bytes(\"CSQPYRK1E8\")");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow),
+    diesel(sql_type = diesel::sql_types::Text)
+)]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Base32Bytes(Vec<u8>);
+
+impl Base32Bytes {
+    /// Creates bytes holding `bytes`.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Decodes the input to new bytes.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input contains an invalid symbol.
+    pub fn decode<'a, I>(input: I) -> Result<Self>
+    where
+        I: IntoInput<'a>,
+    {
+        CLOCKWORK.decode(input).map(Self)
+    }
+
+    /// Encodes the bytes to a new [`Base32String`].
+    pub fn encode(&self) -> Base32String {
+        Base32String::encode(&self.0)
+    }
+
+    /// Returns the bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Converts into the [`Vec<u8>`] of the bytes.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        core::mem::take(&mut self.0)
+    }
+}
+
+impl From<Vec<u8>> for Base32Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<&[u8]> for Base32Bytes {
+    fn from(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+}
+
+impl FromStr for Base32Bytes {
+    type Err = ParseError;
+
+    /// Decodes a string.
+    ///
+    /// Unlike [`Base32Bytes::decode`], the error holds the position of the problem
+    /// and suggests how to fix it.
+    fn from_str(s: &str) -> core::result::Result<Self, ParseError> {
+        find_invalid_symbol(s.as_bytes())?;
+        // The symbols are validated above, so this never fails.
+        Ok(Self(CLOCKWORK.decode(s).unwrap_or_default()))
+    }
+}
+
+impl From<Base32Bytes> for Vec<u8> {
+    fn from(bytes: Base32Bytes) -> Self {
+        bytes.into_vec()
+    }
+}
+
+impl Deref for Base32Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Base32Bytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Base32Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_encoded(&self.0, f)
+    }
+}
+
+impl fmt::Debug for Base32Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Base32Bytes(\"{}\")", self)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Base32Bytes {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "Base32Bytes(");
+        for chunk in crate::chunk::encode_chunks::<32>(&self.0) {
+            defmt::write!(f, "{=str}", chunk.as_str());
+        }
+        defmt::write!(f, ")");
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Base32Bytes {
+    fn serialize<S: ::serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        crate::serde::serialize(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Base32Bytes {
+    fn deserialize<D: ::serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        crate::serde::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl ::schemars::JsonSchema for Base32Bytes {
+    fn schema_name() -> alloc::borrow::Cow<'static, str> {
+        "Base32Bytes".into()
+    }
+
+    fn schema_id() -> alloc::borrow::Cow<'static, str> {
+        "clockwork_base32::Base32Bytes".into()
+    }
+
+    fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        ::schemars::json_schema!({
+            "type": "string",
+            "pattern": "^[0-9A-TV-Za-tv-z]*$",
+            "description": "Bytes encoded in Clockwork Base32",
+        })
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for Base32Bytes {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        <Vec<u8> as ::arbitrary::Arbitrary>::arbitrary(u).map(Self)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <Vec<u8> as ::arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl ::subtle::ConstantTimeEq for Base32Bytes {
+    fn ct_eq(&self, other: &Self) -> ::subtle::Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ::zeroize::Zeroize for Base32Bytes {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Base32Bytes {
+    fn drop(&mut self) {
+        ::zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for Base32Bytes {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        for len in [0, 1, 39, 40, 41, 100] {
+            let bytes: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let expected = crate::encode_to_string(&bytes);
+            let bytes = Base32Bytes::from(bytes);
+            assert_eq!(bytes.to_string(), expected);
+            assert_eq!(bytes.encode(), expected.as_str());
+            assert_eq!(Base32Bytes::decode(&expected).unwrap(), bytes);
+        }
+        assert!(Base32Bytes::decode("CSQPU").is_err());
+    }
+
+    #[test]
+    fn test_from_str() {
+        let bytes: Base32Bytes = "csqpyrk1e8".parse().unwrap();
+        assert_eq!(&*bytes, b"foobar");
+        let err = "CSQPYRuK".parse::<Base32Bytes>().unwrap_err();
+        assert_eq!(err.position(), 6);
+        assert_eq!(
+            err.to_string(),
+            "invalid symbol 'u' at position 6, 'u' is excluded from the alphabet, did you mean 'V'?"
+        );
+    }
+
+    #[test]
+    fn test_clap_value_parser() {
+        use clap::Parser;
+
+        #[derive(Parser, Debug)]
+        struct Args {
+            #[arg(long)]
+            id: Base32String,
+            #[arg(long)]
+            key: Base32Bytes,
+        }
+
+        let args = Args::try_parse_from(["prog", "--id", "CSQPY", "--key", "c9gq4"]).unwrap();
+        assert_eq!(args.id, "CSQPY");
+        assert_eq!(&*args.key, b"bar");
+
+        let err = Args::try_parse_from(["prog", "--id", "CSQ", "--key", ""]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        assert!(err.to_string().contains("invalid length 3"), "{}", err);
+        let err = Args::try_parse_from(["prog", "--id", "", "--key", "C9-GQ4"]).unwrap_err();
+        assert!(
+            err.to_string().contains("at position 2, remove hyphens"),
+            "{}",
+            err
+        );
+    }
+}
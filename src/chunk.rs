@@ -0,0 +1,182 @@
+//! An iterator yielding the encoded result in fixed-size groups of symbols, for
+//! packet and radio protocols which send one group per frame.
+//!
+//! The input is encoded block by block as the groups are taken, so the whole
+//! encoded result is never buffered. This works without the `alloc` feature.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::chunk;
+//!
+//! let mut frames = Vec::new();
+//! for group in chunk::encode_chunks::<8>(b"Hello, world!") {
+//!     frames.push(group.as_str().to_string());
+//! }
+//! assert_eq!(frames, ["91JPRV3F", "5GG7EVVJ", "DHJ22"]);
+//!
+//! let groups: Vec<_> = chunk::encode_chunks::<6>(b"fooba").collect();
+//! assert_eq!(groups[0].as_bytes(), b"CSQPYR");
+//! assert_eq!(groups[1].as_bytes(), b"K1");
+//! ```
+
+use core::iter::FusedIterator;
+use core::ops::Deref;
+
+use crate::{capacity_hint_for_encode, encode_to_slice, BLOCK_BYTE_LEN, BLOCK_SYMBOL_LEN};
+
+/// Returns an iterator which encodes `input` and yields the result in groups of
+/// `N` symbols, and the remaining symbols in a last partial group.
+///
+/// If `N` is 0, the iterator yields nothing.
+pub fn encode_chunks<const N: usize>(input: &[u8]) -> EncodeChunks<'_, N> {
+    EncodeChunks {
+        input,
+        block: [0; BLOCK_SYMBOL_LEN],
+        block_pos: 0,
+        block_len: 0,
+        remaining: if N == 0 {
+            0
+        } else {
+            capacity_hint_for_encode(input.len())
+        },
+    }
+}
+
+/// An iterator yielding groups of encoded symbols, created by [`encode_chunks`].
+#[derive(Debug, Clone)]
+pub struct EncodeChunks<'a, const N: usize> {
+    input: &'a [u8],
+    // The encoded symbols of the current block, of which `block_pos..block_len`
+    // are not yielded yet.
+    block: [u8; BLOCK_SYMBOL_LEN],
+    block_pos: usize,
+    block_len: usize,
+    // The number of symbols not yielded yet.
+    remaining: usize,
+}
+
+impl<const N: usize> Iterator for EncodeChunks<'_, N> {
+    type Item = Chunk<N>;
+
+    fn next(&mut self) -> Option<Chunk<N>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let mut chunk = Chunk {
+            symbols: [0; N],
+            len: 0,
+        };
+        let chunk_len = self.remaining.min(N);
+        while chunk.len < chunk_len {
+            if self.block_pos == self.block_len {
+                let len = self.input.len().min(BLOCK_BYTE_LEN);
+                let (block, rest) = self.input.split_at(len);
+                self.input = rest;
+                self.block_len = encode_to_slice(block, &mut self.block).unwrap_or_default();
+                self.block_pos = 0;
+            }
+            let dest = chunk.symbols.get_mut(chunk.len..chunk_len);
+            let src = self.block.get(self.block_pos..self.block_len);
+            let len = match (dest, src) {
+                (Some(dest), Some(src)) => crate::copy_prefix(dest, src),
+                _ => break,
+            };
+            chunk.len += len;
+            self.block_pos += len;
+        }
+        self.remaining -= chunk.len;
+        Some(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining.div_ceil(N.max(1));
+        (len, Some(len))
+    }
+}
+
+impl<const N: usize> ExactSizeIterator for EncodeChunks<'_, N> {}
+
+impl<const N: usize> FusedIterator for EncodeChunks<'_, N> {}
+
+/// A group of up to `N` encoded symbols yielded by [`EncodeChunks`].
+///
+/// All groups but the last one are full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chunk<const N: usize> {
+    symbols: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Chunk<N> {
+    /// Returns the symbols.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.symbols.get(..self.len).unwrap_or_default()
+    }
+
+    /// Returns the symbols as a string.
+    pub fn as_str(&self) -> &str {
+        // The symbols are ASCII, so this never fails.
+        core::str::from_utf8(self.as_bytes()).unwrap_or_default()
+    }
+
+    /// Returns whether the group has `N` symbols.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+}
+
+impl<const N: usize> Deref for Chunk<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for Chunk<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::encode_to_vec;
+
+    fn check<const N: usize>(input: &[u8]) {
+        let encoded = encode_to_vec(input);
+        let chunks = encode_chunks::<N>(input);
+        let expected: Vec<&[u8]> = encoded.chunks(N).collect();
+        assert_eq!(chunks.len(), expected.len());
+        let chunks: Vec<Chunk<N>> = chunks.collect();
+        assert_eq!(chunks.len(), expected.len());
+        for (i, (chunk, expected)) in chunks.iter().zip(&expected).enumerate() {
+            assert_eq!(chunk.as_bytes(), *expected, "N={} len={}", N, input.len());
+            assert_eq!(chunk.is_full(), i + 1 < chunks.len() || expected.len() == N);
+        }
+    }
+
+    #[test]
+    fn test_encode_chunks() {
+        let input: Vec<u8> = (0..100).map(|i| (i * 37) as u8).collect();
+        for len in 0..input.len() {
+            let input = &input[..len];
+            check::<1>(input);
+            check::<3>(input);
+            check::<7>(input);
+            check::<8>(input);
+            check::<16>(input);
+            check::<100>(input);
+        }
+        assert_eq!(encode_chunks::<0>(b"foo").count(), 0);
+
+        let mut chunks = encode_chunks::<8>(b"Hello, world!");
+        assert_eq!(chunks.next().unwrap().as_str(), "91JPRV3F");
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(&*chunks.next().unwrap(), b"5GG7EVVJ");
+        assert_eq!(chunks.next().unwrap().as_ref(), b"DHJ22");
+        assert!(chunks.next().is_none());
+        assert!(chunks.next().is_none());
+    }
+}
@@ -0,0 +1,574 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::convert::TryFrom;
+
+#[cfg(feature = "alloc")]
+use crate::pad::{Padding, MARKER_LEN};
+use crate::validator::Measurement;
+use crate::{
+    capacity_hint_for_decode, capacity_hint_for_encode, check_canonical, decode_slice,
+    decode_symbol, encode_slice, find_invalid_symbol, is_symbol_value, Error, IntoInput,
+    ParseError, Result, SliceSink,
+};
+
+/// The Clockwork Base32 codec, used through the [`CLOCKWORK`] static.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clockwork {
+    _private: (),
+}
+
+/// The Clockwork Base32 codec, the primary way to decode and encode.
+///
+/// Its methods take contiguous inputs implementing [`IntoInput`], including
+/// [`str`], while the free functions at the crate root take any iterator of
+/// bytes and call them chunk by chunk. New variants and options are added as
+/// methods here, so existing signatures don't change.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32::CLOCKWORK;
+///
+/// let encoded = CLOCKWORK.encode(b"Hello, world!");
+/// assert_eq!(encoded, "91JPRV3F5GG7EVVJDHJ22");
+/// assert_eq!(CLOCKWORK.decode(&encoded)?, b"Hello, world!");
+///
+/// let err = CLOCKWORK.validate("CSQPU").unwrap_err();
+/// assert_eq!(err.position(), 4);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "alloc"))]
+/// # fn main() {}
+/// ```
+pub static CLOCKWORK: Clockwork = Clockwork { _private: () };
+
+/// The result of [`Clockwork::decode_recovering`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Recovered {
+    /// The bytes decoded from the valid symbols.
+    pub decoded: Vec<u8>,
+    /// The positions of the skipped invalid bytes in the input.
+    pub skipped: Vec<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl Recovered {
+    /// Returns whether no byte was skipped, so the result is the same as
+    /// [`Clockwork::decode`] gives.
+    pub fn is_complete(&self) -> bool {
+        self.skipped.is_empty()
+    }
+}
+
+impl Clockwork {
+    /// Encodes bytes and returns the result as a new [`String`].
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::CLOCKWORK;
+    /// assert_eq!(CLOCKWORK.encode(b"Hello, world!"), "91JPRV3F5GG7EVVJDHJ22");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn encode<'a, I>(&self, input: I) -> String
+    where
+        I: IntoInput<'a>,
+    {
+        let encoded = self.encode_to_vec(input);
+        // The encoded result consists of ASCII symbols only, so this never fails.
+        String::from_utf8(encoded).unwrap_or_default()
+    }
+
+    /// Encodes bytes and returns the result as a new [`Vec<u8>`].
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::CLOCKWORK;
+    /// assert_eq!(CLOCKWORK.encode_to_vec(b"Hello, world!"), b"91JPRV3F5GG7EVVJDHJ22");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn encode_to_vec<'a, I>(&self, input: I) -> Vec<u8>
+    where
+        I: IntoInput<'a>,
+    {
+        let input = input.into_input();
+        let mut dest = Vec::with_capacity(capacity_hint_for_encode(input.len()));
+        encode_slice(&mut dest, input);
+        dest
+    }
+
+    /// Encodes bytes into `dest` and returns the number of bytes written.
+    ///
+    /// This method is available without the `alloc` feature.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if `dest` is shorter than [`encoded_len`](Self::encoded_len)
+    /// of the input length.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), clockwork_base32::Error> {
+    /// use clockwork_base32::CLOCKWORK;
+    /// let mut dest = [0; 32];
+    /// let len = CLOCKWORK.encode_to_slice(b"Hello, world!", &mut dest)?;
+    /// assert_eq!(&dest[..len], b"91JPRV3F5GG7EVVJDHJ22");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encode_to_slice<'a, I>(&self, input: I, dest: &mut [u8]) -> Result<usize>
+    where
+        I: IntoInput<'a>,
+    {
+        let input = input.into_input();
+        let len = capacity_hint_for_encode(input.len());
+        let dest = dest.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        encode_slice(&mut SliceSink { buf: dest, len: 0 }, input);
+        Ok(len)
+    }
+
+    /// Decodes bytes and returns the result as a new [`Vec<u8>`].
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input contains a invalid byte.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), clockwork_base32::Error> {
+    /// use clockwork_base32::CLOCKWORK;
+    /// assert_eq!(CLOCKWORK.decode("91JPRV3F5GG7EVVJDHJ22")?, b"Hello, world!");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn decode<'a, I>(&self, input: I) -> Result<Vec<u8>>
+    where
+        I: IntoInput<'a>,
+    {
+        let input = input.into_input();
+        let mut dest = Vec::with_capacity(capacity_hint_for_decode(input.len()));
+        decode_slice(&mut dest, input)?;
+        Ok(dest)
+    }
+
+    /// Decodes bytes which must decode to exactly `len` bytes.
+    ///
+    /// The length is checked from the input length before decoding, so input
+    /// of a wrong length is rejected without decoding it, like garbage given to
+    /// a protocol parser expecting a fixed-size field.
+    ///
+    /// # Errors
+    /// Returns [`Error::LengthMismatch`] if the input doesn't decode to `len`
+    /// bytes, or [`Err`] if it contains a invalid byte.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), clockwork_base32::Error> {
+    /// use clockwork_base32::{Error, CLOCKWORK};
+    /// assert_eq!(CLOCKWORK.decode_exact("CSQPYRK1", 5)?, b"fooba");
+    /// assert_eq!(
+    ///     CLOCKWORK.decode_exact("CSQPY", 5),
+    ///     Err(Error::LengthMismatch { expected: 5, actual: 3 })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn decode_exact<'a, I>(&self, input: I, len: usize) -> Result<Vec<u8>>
+    where
+        I: IntoInput<'a>,
+    {
+        let input = input.into_input();
+        let actual = capacity_hint_for_decode(input.len());
+        if actual != len {
+            return Err(Error::LengthMismatch {
+                expected: len,
+                actual,
+            });
+        }
+        let mut dest = Vec::with_capacity(len);
+        decode_slice(&mut dest, input)?;
+        Ok(dest)
+    }
+
+    /// Decodes bytes, skipping invalid symbols instead of failing, and returns
+    /// the best-effort result with the positions of the skipped bytes.
+    ///
+    /// This recovers data from dumps corrupted by OCR or line noise. The
+    /// symbols after a skipped byte are shifted, so the result is only right
+    /// if the skipped bytes were inserted, like separators and noise.
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::CLOCKWORK;
+    /// let recovered = CLOCKWORK.decode_recovering("CSQP~YRK1\n");
+    /// assert_eq!(recovered.decoded, b"fooba");
+    /// assert_eq!(recovered.skipped, [4, 9]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn decode_recovering<'a, I>(&self, input: I) -> Recovered
+    where
+        I: IntoInput<'a>,
+    {
+        let input = input.into_input();
+        let mut symbols = Vec::with_capacity(input.len());
+        let mut skipped = Vec::new();
+        for (i, &b) in input.iter().enumerate() {
+            if self.is_valid_symbol(b) {
+                symbols.push(b);
+            } else {
+                skipped.push(i);
+            }
+        }
+        // Only valid symbols are left, so this never fails.
+        let decoded = self.decode(&symbols).unwrap_or_default();
+        Recovered { decoded, skipped }
+    }
+
+    /// Decodes bytes and returns the result as a new [`String`].
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input contains a invalid byte or the decoded bytes
+    /// are not valid UTF-8.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), clockwork_base32::Error> {
+    /// use clockwork_base32::CLOCKWORK;
+    /// assert_eq!(CLOCKWORK.decode_to_string("91JPRV3F5GG7EVVJDHJ22")?, "Hello, world!");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn decode_to_string<'a, I>(&self, input: I) -> Result<String>
+    where
+        I: IntoInput<'a>,
+    {
+        let decoded = self.decode(input)?;
+        String::from_utf8(decoded).map_err(|_| Error::InvalidUtf8)
+    }
+
+    /// Decodes bytes and returns the result as a new [`String`], replacing
+    /// invalid UTF-8 sequences with `U+FFFD REPLACEMENT CHARACTER` like
+    /// [`String::from_utf8_lossy`].
+    ///
+    /// This never fails on binary payloads, for logging and debugging output.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input contains a invalid byte.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), clockwork_base32::Error> {
+    /// use clockwork_base32::CLOCKWORK;
+    /// assert_eq!(CLOCKWORK.decode_to_string_lossy("CSQPY")?, "foo");
+    /// assert_eq!(CLOCKWORK.decode_to_string_lossy("CSQPZZG")?, "foo\u{fffd}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn decode_to_string_lossy<'a, I>(&self, input: I) -> Result<String>
+    where
+        I: IntoInput<'a>,
+    {
+        let decoded = self.decode(input)?;
+        Ok(match String::from_utf8(decoded) {
+            Ok(s) => s,
+            Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+        })
+    }
+
+    /// Decodes bytes into `dest` and returns the number of bytes written.
+    ///
+    /// This method is available without the `alloc` feature.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input contains a invalid byte or `dest` is shorter
+    /// than [`decoded_len`](Self::decoded_len) of the input length.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), clockwork_base32::Error> {
+    /// use clockwork_base32::CLOCKWORK;
+    /// let mut dest = [0; 16];
+    /// let len = CLOCKWORK.decode_to_slice(b"91JPRV3F5GG7EVVJDHJ22", &mut dest)?;
+    /// assert_eq!(&dest[..len], b"Hello, world!");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn decode_to_slice<'a, I>(&self, input: I, dest: &mut [u8]) -> Result<usize>
+    where
+        I: IntoInput<'a>,
+    {
+        let input = input.into_input();
+        let len = capacity_hint_for_decode(input.len());
+        let dest = dest.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        decode_slice(&mut SliceSink { buf: dest, len: 0 }, input)?;
+        Ok(len)
+    }
+
+    /// Encodes bytes with a length marker and filler up to the length given by
+    /// `padding`, so payloads of different lengths give outputs of the same
+    /// length.
+    ///
+    /// See the [`pad`](crate::pad) module for the format.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the payload doesn't fit in a fixed `padding`, or the
+    /// padded frame is too large to allocate.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), clockwork_base32::Error> {
+    /// use clockwork_base32::pad::Padding;
+    /// use clockwork_base32::CLOCKWORK;
+    /// let encoded = CLOCKWORK.encode_padded(b"foo", &Padding::fixed(16))?;
+    /// assert_eq!(encoded, "000000V6DXQG0000");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn encode_padded<'a, I>(&self, input: I, padding: &Padding) -> Result<String>
+    where
+        I: IntoInput<'a>,
+    {
+        let input = input.into_input();
+        let frame_len = padding
+            .frame_len(input.len())
+            .ok_or(Error::BufferTooSmall)?;
+        let marker = u32::try_from(input.len()).map_err(|_| Error::BufferTooSmall)?;
+        let mut frame = Vec::new();
+        frame
+            .try_reserve_exact(frame_len)
+            .map_err(|_| Error::BufferTooSmall)?;
+        frame.extend_from_slice(&marker.to_be_bytes());
+        frame.extend_from_slice(input);
+        let filler_start = frame.len();
+        frame.resize(frame_len, 0);
+        padding.fill(frame.get_mut(filler_start..).unwrap_or_default());
+        Ok(self.encode(&frame))
+    }
+
+    /// Decodes the output of [`encode_padded`](Self::encode_padded) and returns
+    /// the payload.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input contains a invalid byte, or the decoded
+    /// bytes are shorter than the length marker or the length it tells.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), clockwork_base32::Error> {
+    /// use clockwork_base32::CLOCKWORK;
+    /// assert_eq!(CLOCKWORK.decode_padded("000000V6DXQG0000")?, b"foo");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn decode_padded<'a, I>(&self, input: I) -> Result<Vec<u8>>
+    where
+        I: IntoInput<'a>,
+    {
+        let mut frame = self.decode(input)?;
+        let mut marker = [0; MARKER_LEN];
+        let actual = frame.len();
+        let header = frame.get(..MARKER_LEN).ok_or(Error::LengthMismatch {
+            expected: MARKER_LEN,
+            actual,
+        })?;
+        marker.copy_from_slice(header);
+        let len = u32::from_be_bytes(marker);
+        let expected = usize::try_from(len).unwrap_or(usize::MAX);
+        let actual = actual - MARKER_LEN;
+        if expected > actual {
+            return Err(Error::LengthMismatch { expected, actual });
+        }
+        frame.truncate(MARKER_LEN + expected);
+        frame.drain(..MARKER_LEN);
+        Ok(frame)
+    }
+
+    /// Checks that every byte of the input is a valid symbol for decoding.
+    ///
+    /// Lowercase letters and the aliases `I`, `L` and `O` are accepted, as the
+    /// decode methods do.
+    ///
+    /// # Errors
+    /// Returns [`Err`] with the position of the first invalid symbol.
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::{Error, CLOCKWORK};
+    /// assert!(CLOCKWORK.validate("csqpyrk1").is_ok());
+    /// let err = CLOCKWORK.validate("CSQPU").unwrap_err();
+    /// assert!(matches!(err.error(), Error::InvalidSymbol { byte: b'U', .. }));
+    /// assert_eq!(err.position(), 4);
+    /// ```
+    pub fn validate<'a, I>(&self, input: I) -> core::result::Result<(), ParseError>
+    where
+        I: IntoInput<'a>,
+    {
+        find_invalid_symbol(input.into_input())
+    }
+
+    /// Checks that the input is the canonical encoding of its decoded bytes,
+    /// which is what the encode methods return.
+    ///
+    /// # Errors
+    /// Returns [`Err`] with the position of the first problem if the input
+    /// contains an invalid, lowercase or aliased symbol, its length is not
+    /// produced by encoding or the padding bits of the last symbol are not zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::{Error, CLOCKWORK};
+    /// assert!(CLOCKWORK.validate_canonical("CSQPYRK1").is_ok());
+    /// let err = CLOCKWORK.validate_canonical("csqpyrk1").unwrap_err();
+    /// assert!(matches!(err.error(), Error::NonCanonical));
+    /// assert_eq!(err.position(), 0);
+    /// ```
+    pub fn validate_canonical<'a, I>(&self, input: I) -> core::result::Result<(), ParseError>
+    where
+        I: IntoInput<'a>,
+    {
+        check_canonical(input.into_input())
+    }
+
+    /// Measures the decoded length and the properties of the input in one pass,
+    /// to decide how to decode it and how much to allocate.
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::CLOCKWORK;
+    /// let m = CLOCKWORK.measure("csqp-yrkI");
+    /// assert_eq!(m.decoded_len, 5);
+    /// assert!(!m.is_valid);
+    /// assert!(!m.is_canonical);
+    /// assert!(m.uses_aliases);
+    /// assert!(m.has_separators);
+    /// ```
+    pub fn measure<'a, I>(&self, input: I) -> Measurement
+    where
+        I: IntoInput<'a>,
+    {
+        Measurement::new(input.into_input())
+    }
+
+    /// Returns whether `b` is a valid symbol for decoding.
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::CLOCKWORK;
+    /// assert!(CLOCKWORK.is_valid_symbol(b'o'));
+    /// assert!(!CLOCKWORK.is_valid_symbol(b'U'));
+    /// ```
+    pub fn is_valid_symbol(&self, b: u8) -> bool {
+        is_symbol_value(decode_symbol(b))
+    }
+
+    /// Returns the length of the encoded result of `input_byte_len` bytes.
+    ///
+    /// This saturates at [`usize::MAX`] on overflow, like
+    /// [`capacity_hint_for_encode`](crate::capacity_hint_for_encode).
+    pub fn encoded_len(&self, input_byte_len: usize) -> usize {
+        capacity_hint_for_encode(input_byte_len)
+    }
+
+    /// Returns the length of the decoded result of `input_byte_len` symbols.
+    pub fn decoded_len(&self, input_byte_len: usize) -> usize {
+        capacity_hint_for_decode(input_byte_len)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clockwork() {
+        let input: Vec<u8> = (0..40).map(|i| (i * 71) as u8).collect();
+        for len in 0..input.len() {
+            let input = &input[..len];
+            let encoded = CLOCKWORK.encode(input);
+            assert_eq!(encoded.len(), CLOCKWORK.encoded_len(len));
+            assert_eq!(encoded, crate::encode_to_string(input));
+            assert!(CLOCKWORK.validate_canonical(&encoded).is_ok());
+            assert_eq!(CLOCKWORK.decode(&encoded).unwrap(), input);
+            assert_eq!(CLOCKWORK.decoded_len(encoded.len()), len);
+
+            let mut buf = [0; 64];
+            let n = CLOCKWORK.encode_to_slice(input, &mut buf).unwrap();
+            assert_eq!(&buf[..n], encoded.as_bytes());
+            let n = CLOCKWORK.decode_to_slice(&encoded, &mut buf).unwrap();
+            assert_eq!(&buf[..n], input);
+        }
+
+        let mut short = [0; 4];
+        assert!(matches!(
+            CLOCKWORK.encode_to_slice(b"foo", &mut short),
+            Err(Error::BufferTooSmall)
+        ));
+        assert!(matches!(
+            CLOCKWORK.decode_to_string("ZZ"),
+            Err(Error::InvalidUtf8)
+        ));
+        assert_eq!(CLOCKWORK.decode_to_string_lossy("ZZ").unwrap(), "\u{fffd}");
+        assert_eq!(CLOCKWORK.decode_exact("", 0).unwrap(), b"");
+        assert_eq!(CLOCKWORK.decode_exact("91JPRV3F", 5).unwrap(), b"Hello");
+        assert_eq!(
+            CLOCKWORK.decode_exact("91JPRV3F", 6),
+            Err(Error::LengthMismatch {
+                expected: 6,
+                actual: 5
+            })
+        );
+        // The length is checked first, so the invalid symbol is not reached.
+        assert_eq!(
+            CLOCKWORK.decode_exact("U1JPRV3F", 4),
+            Err(Error::LengthMismatch {
+                expected: 4,
+                actual: 5
+            })
+        );
+        assert_eq!(
+            CLOCKWORK.decode_exact("91JPRV3U", 5),
+            Err(Error::InvalidSymbol {
+                byte: b'U',
+                position: 7
+            })
+        );
+        assert_eq!(
+            CLOCKWORK.decode_to_string_lossy("91JPRV3F").unwrap(),
+            "Hello"
+        );
+        assert_eq!(
+            CLOCKWORK.decode_to_string_lossy("CSQPU"),
+            Err(Error::InvalidSymbol {
+                byte: b'U',
+                position: 4
+            })
+        );
+        let recovered = CLOCKWORK.decode_recovering("91JPRV3F");
+        assert!(recovered.is_complete());
+        assert_eq!(recovered.decoded, b"Hello");
+        let recovered = CLOCKWORK.decode_recovering(" 91JP\u{e9}RV3FU");
+        assert_eq!(recovered.decoded, b"Hello");
+        assert_eq!(recovered.skipped, [0, 5, 6, 11]);
+        let recovered = CLOCKWORK.decode_recovering("UUU");
+        assert!(recovered.decoded.is_empty());
+        assert_eq!(recovered.skipped.len(), 3);
+
+        let err = CLOCKWORK.validate("CSQPYRK1\n").unwrap_err();
+        assert!(matches!(
+            err.error(),
+            Error::InvalidSymbol { byte: b'\n', .. }
+        ));
+        assert_eq!(err.position(), 8);
+        let err = CLOCKWORK.validate_canonical("CSQPYR").unwrap_err();
+        assert!(matches!(err.error(), Error::NonCanonical));
+        assert_eq!(err.position(), 6);
+    }
+}
@@ -0,0 +1,179 @@
+//! A drop-in replacement for the API of the `base32` crate.
+//!
+//! [`encode`] and [`decode`] have the same signatures and results as the
+//! functions of the `base32` crate, so replacing that dependency only needs a
+//! changed import. [`Alphabet::Crockford`], which has the same symbols and
+//! aliases as Clockwork Base32, uses the functions of this crate. The other
+//! alphabets are encoded and decoded one symbol at a time.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::compat as base32;
+//! use clockwork_base32::compat::Alphabet;
+//!
+//! let encoded = base32::encode(Alphabet::Crockford, b"foobar");
+//! assert_eq!(encoded, "CSQPYRK1E8");
+//! assert_eq!(base32::decode(Alphabet::Crockford, "csqpyrkle8").unwrap(), b"foobar");
+//!
+//! let padded = Alphabet::Rfc4648 { padding: true };
+//! assert_eq!(base32::encode(padded, b"foobar"), "MZXW6YTBOI======");
+//! assert_eq!(base32::decode(padded, "MZXW6YTBOI======").unwrap(), b"foobar");
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{decode_to_vec, encode_to_string, rfc4648};
+
+/// The alphabets of the `base32` crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alphabet {
+    /// Crockford's Base32, whose encoding is the same as Clockwork Base32.
+    Crockford,
+    /// The RFC 4648 alphabet.
+    Rfc4648 {
+        /// Whether `=` padding is added to a multiple of 8 symbols.
+        padding: bool,
+    },
+    /// The RFC 4648 alphabet in lowercase.
+    Rfc4648Lower {
+        /// Whether `=` padding is added to a multiple of 8 symbols.
+        padding: bool,
+    },
+    /// The RFC 4648 "base32hex" alphabet.
+    Rfc4648Hex {
+        /// Whether `=` padding is added to a multiple of 8 symbols.
+        padding: bool,
+    },
+    /// The RFC 4648 "base32hex" alphabet in lowercase.
+    Rfc4648HexLower {
+        /// Whether `=` padding is added to a multiple of 8 symbols.
+        padding: bool,
+    },
+    /// The z-base-32 alphabet.
+    Z,
+}
+
+impl Alphabet {
+    // symbols returns the symbols in the order of their values and whether
+    // padding is used.
+    fn symbols(self) -> (&'static [u8; 32], bool) {
+        match self {
+            Alphabet::Crockford => (b"0123456789ABCDEFGHJKMNPQRSTVWXYZ", false),
+            Alphabet::Rfc4648 { padding } => (b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567", padding),
+            Alphabet::Rfc4648Lower { padding } => (b"abcdefghijklmnopqrstuvwxyz234567", padding),
+            Alphabet::Rfc4648Hex { padding } => (b"0123456789ABCDEFGHIJKLMNOPQRSTUV", padding),
+            Alphabet::Rfc4648HexLower { padding } => (b"0123456789abcdefghijklmnopqrstuv", padding),
+            Alphabet::Z => (b"ybndrfg8ejkmcpqxot1uwisza345h769", false),
+        }
+    }
+}
+
+/// Encodes bytes with an alphabet.
+pub fn encode(alphabet: Alphabet, data: &[u8]) -> String {
+    match alphabet {
+        Alphabet::Crockford => encode_to_string(data),
+        _ => {
+            let (symbols, padding) = alphabet.symbols();
+            rfc4648::encode(symbols, padding, data)
+        }
+    }
+}
+
+/// Decodes a string with an alphabet, or returns [`None`] if it contains an
+/// invalid symbol.
+///
+/// Like the `base32` crate, up to 6 trailing `=` are ignored, and with padding
+/// enabled `=` is read as a zero symbol wherever it appears. Trailing bits which
+/// do not form a whole byte are dropped. [`Alphabet::Crockford`] accepts
+/// lowercase symbols and the aliases `I`, `L` and `O`.
+pub fn decode(alphabet: Alphabet, data: &str) -> Option<Vec<u8>> {
+    let data = data.as_bytes();
+    let (symbols, padding) = match alphabet {
+        Alphabet::Crockford => return decode_to_vec(data).ok(),
+        _ => alphabet.symbols(),
+    };
+    let padding_len = data
+        .iter()
+        .rev()
+        .take(6)
+        .take_while(|&&b| b == b'=')
+        .count();
+    let mut output = rfc4648::decode(data.iter().map(
+        |&b| match symbols.iter().position(|&s| s == b) {
+            Some(v) => Ok(v as u16),
+            None if padding && b == b'=' => Ok(0),
+            None => Err(()),
+        },
+    ))
+    .ok()?;
+    output.truncate((data.len() - padding_len) * 5 / 8);
+    Some(output)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    const ALPHABETS: [(Alphabet, ::base32::Alphabet); 10] = [
+        (Alphabet::Crockford, ::base32::Alphabet::Crockford),
+        (
+            Alphabet::Rfc4648 { padding: false },
+            ::base32::Alphabet::Rfc4648 { padding: false },
+        ),
+        (
+            Alphabet::Rfc4648 { padding: true },
+            ::base32::Alphabet::Rfc4648 { padding: true },
+        ),
+        (
+            Alphabet::Rfc4648Lower { padding: false },
+            ::base32::Alphabet::Rfc4648Lower { padding: false },
+        ),
+        (
+            Alphabet::Rfc4648Lower { padding: true },
+            ::base32::Alphabet::Rfc4648Lower { padding: true },
+        ),
+        (
+            Alphabet::Rfc4648Hex { padding: false },
+            ::base32::Alphabet::Rfc4648Hex { padding: false },
+        ),
+        (
+            Alphabet::Rfc4648Hex { padding: true },
+            ::base32::Alphabet::Rfc4648Hex { padding: true },
+        ),
+        (
+            Alphabet::Rfc4648HexLower { padding: false },
+            ::base32::Alphabet::Rfc4648HexLower { padding: false },
+        ),
+        (
+            Alphabet::Rfc4648HexLower { padding: true },
+            ::base32::Alphabet::Rfc4648HexLower { padding: true },
+        ),
+        (Alphabet::Z, ::base32::Alphabet::Z),
+    ];
+
+    #[test]
+    fn test_same_as_base32() {
+        let input: Vec<u8> = (0..=255).rev().collect();
+        for (alphabet, expected) in ALPHABETS {
+            for len in 0..20 {
+                let encoded = encode(alphabet, &input[..len]);
+                assert_eq!(encoded, ::base32::encode(expected, &input[..len]));
+                assert_eq!(decode(alphabet, &encoded).unwrap(), &input[..len]);
+                assert_eq!(
+                    decode(alphabet, &encoded),
+                    ::base32::decode(expected, &encoded)
+                );
+            }
+            for invalid in ["U", "=A", "A=", "AB=====", "ab", "\u{e9}", "ybnd"] {
+                assert_eq!(
+                    decode(alphabet, invalid),
+                    ::base32::decode(expected, invalid),
+                    "{:?} {:?}",
+                    alphabet,
+                    invalid
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! A decoder whose options are fixed at compile time.
+//!
+//! [`Decoder`] takes its options as const generic parameters, so the checks for
+//! disabled options are compiled away and `Decoder<false, false>` runs the same
+//! code as [`crate::Decoder`]. This is for hot paths which can't afford a branch
+//! on an option flag for each symbol.
+//!
+//! * `STRICT`: only the canonical encoding is accepted, so lowercase symbols and
+//!   the aliases `I`, `L` and `O` are rejected with [`Error::NonCanonical`], and
+//!   so are lengths not produced by encoding and nonzero padding bits.
+//! * `IGNORE_WS`: ASCII whitespace anywhere in the input is skipped, for
+//!   wrapped or grouped input.
+//!
+//! # Examples
+//! ```
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32::{config, Error};
+//!
+//! let mut decoder = config::Decoder::<true, true>::new();
+//! let mut output = [0; 16];
+//! let (_, mut len) = decoder.update(b"91JPRV3F\n5GG7EVVJ\nDHJ22\n", &mut output)?;
+//! len += decoder.finish(&mut output[len..])?;
+//! assert_eq!(&output[..len], b"Hello, world!");
+//!
+//! let mut decoder = config::Decoder::<true, false>::new();
+//! assert!(matches!(
+//!     decoder.update(b"91jprv3f", &mut output),
+//!     Err(Error::NonCanonical)
+//! ));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::stream::sealed;
+use crate::{
+    capacity_hint_for_decode, capacity_hint_for_encode, decode_symbol, encode_symbol,
+    find_invalid_symbol, is_symbol_value, Error, Result, Transcode, BLOCK_SYMBOL_LEN, BYTE_BIT_LEN,
+    DECODED_BIT_LEN,
+};
+
+/// A resumable decoder with the options `STRICT` and `IGNORE_WS`.
+///
+/// See the [module documentation](self) for the options. Otherwise it works like
+/// [`crate::Decoder`], and it implements [`Transcode`] to be used with the
+/// streaming adapters.
+#[derive(Debug, Clone, Default)]
+pub struct Decoder<const STRICT: bool, const IGNORE_WS: bool> {
+    inner: crate::Decoder,
+    // The number of consumed symbols in the last partial block and the last
+    // consumed symbol, which are only tracked if `STRICT` is true.
+    partial_len: usize,
+    last: u8,
+    // The number of consumed input bytes including whitespace, for the positions
+    // of errors.
+    position: usize,
+}
+
+impl<const STRICT: bool, const IGNORE_WS: bool> Decoder<STRICT, IGNORE_WS> {
+    /// Creates a new decoder.
+    pub const fn new() -> Self {
+        Self {
+            inner: crate::Decoder::new(),
+            partial_len: 0,
+            last: 0,
+            position: 0,
+        }
+    }
+
+    /// Decodes `input` into `output` and returns the number of consumed input bytes
+    /// and the number of written output bytes.
+    ///
+    /// Output is written in blocks of 5 bytes, so fewer bytes than `input`
+    /// are consumed when `output` is full.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input contains a invalid byte, or a lowercase or
+    /// aliased symbol if `STRICT` is true.
+    pub fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<(usize, usize)> {
+        let mut consumed = 0;
+        let mut written = 0;
+        while consumed < input.len() {
+            let mut rest = input.get(consumed..).unwrap_or_default();
+            if IGNORE_WS {
+                let spaces = rest.iter().take_while(|b| b.is_ascii_whitespace()).count();
+                consumed += spaces;
+                rest = rest.get(spaces..).unwrap_or_default();
+                let len = rest
+                    .iter()
+                    .position(u8::is_ascii_whitespace)
+                    .unwrap_or(rest.len());
+                rest = rest.get(..len).unwrap_or_default();
+                // The inner decoder doesn't see the skipped whitespace, so
+                // invalid symbols are found here to report their positions.
+                let start = self.position + consumed;
+                find_invalid_symbol(rest).map_err(|err| Error::from(err).offset_by(start))?;
+            }
+            let output = output.get_mut(written..).unwrap_or_default();
+            let (n, m) = self.inner.update(rest, output)?;
+            // Only the consumed symbols are checked, so symbols given again
+            // after a full output are not checked twice.
+            if STRICT && n > 0 {
+                let start = self.position + consumed;
+                check_symbols(rest.get(..n).unwrap_or_default())
+                    .map_err(|err| err.offset_by(start))?;
+                self.partial_len = (self.partial_len + n) % BLOCK_SYMBOL_LEN;
+                self.last = rest.get(n - 1).copied().unwrap_or_default();
+            }
+            consumed += n;
+            written += m;
+            if n < rest.len() {
+                break;
+            }
+        }
+        self.position += consumed;
+        Ok((consumed, written))
+    }
+
+    /// Decodes the remaining buffered symbols into `output` and returns the number of
+    /// written bytes, which is at most 4.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the buffered symbols contain a invalid byte, `output`
+    /// is shorter than needed, or if `STRICT` is true, the input length is not
+    /// produced by encoding or the padding bits are not zero.
+    pub fn finish(&mut self, output: &mut [u8]) -> Result<usize> {
+        if STRICT {
+            let decoded_len = capacity_hint_for_decode(self.partial_len);
+            if capacity_hint_for_encode(decoded_len) != self.partial_len {
+                return Err(Error::NonCanonical);
+            }
+            let padding_bits = self.partial_len * DECODED_BIT_LEN - decoded_len * BYTE_BIT_LEN;
+            if decode_symbol(self.last) & ((1 << padding_bits) - 1) != 0 {
+                return Err(Error::NonCanonical);
+            }
+        }
+        let len = self.inner.finish(output)?;
+        self.partial_len = 0;
+        Ok(len)
+    }
+}
+
+impl<const STRICT: bool, const IGNORE_WS: bool> sealed::Sealed for Decoder<STRICT, IGNORE_WS> {}
+impl<const STRICT: bool, const IGNORE_WS: bool> Transcode for Decoder<STRICT, IGNORE_WS> {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<(usize, usize)> {
+        Decoder::update(self, input, output)
+    }
+
+    fn finish(&mut self, output: &mut [u8]) -> Result<usize> {
+        Decoder::finish(self, output)
+    }
+}
+
+// check_symbols returns Err if `input` contains an invalid symbol or a symbol
+// which is not canonical.
+fn check_symbols(input: &[u8]) -> Result<()> {
+    for (position, &byte) in input.iter().enumerate() {
+        let v = decode_symbol(byte);
+        if !is_symbol_value(v) {
+            return Err(Error::InvalidSymbol { byte, position });
+        }
+        if encode_symbol(v) != byte {
+            return Err(Error::NonCanonical);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::encode_to_vec;
+
+    fn decode<const STRICT: bool, const IGNORE_WS: bool>(
+        input: &[u8],
+        chunk_len: usize,
+        output_len: usize,
+    ) -> Result<Vec<u8>> {
+        let mut decoder = Decoder::<STRICT, IGNORE_WS>::new();
+        let mut output = vec![0; output_len];
+        let mut result = Vec::new();
+        for mut chunk in input.chunks(chunk_len) {
+            while !chunk.is_empty() {
+                let (consumed, written) = decoder.update(chunk, &mut output)?;
+                result.extend_from_slice(&output[..written]);
+                chunk = &chunk[consumed..];
+            }
+        }
+        let written = decoder.finish(&mut output)?;
+        result.extend_from_slice(&output[..written]);
+        Ok(result)
+    }
+
+    #[test]
+    fn test_decoder() {
+        let plain: Vec<u8> = (0..=255).collect();
+        for len in [0, 1, 2, 3, 4, 5, 13, 256] {
+            let plain = &plain[..len];
+            let encoded = encode_to_vec(plain);
+            let wrapped: Vec<u8> = encoded
+                .chunks(7)
+                .flat_map(|line| line.iter().chain(b" \r\n\t"))
+                .copied()
+                .collect();
+            for chunk_len in [1, 3, 8, 100] {
+                for output_len in [5, 9, 64] {
+                    let decoded = decode::<false, false>(&encoded, chunk_len, output_len);
+                    assert_eq!(decoded.unwrap(), plain);
+                    let decoded = decode::<true, false>(&encoded, chunk_len, output_len);
+                    assert_eq!(decoded.unwrap(), plain);
+                    let decoded = decode::<false, true>(&wrapped, chunk_len, output_len);
+                    assert_eq!(decoded.unwrap(), plain);
+                    let decoded = decode::<true, true>(&wrapped, chunk_len, output_len);
+                    assert_eq!(decoded.unwrap(), plain);
+                }
+            }
+        }
+
+        assert_eq!(decode::<false, false>(b"csqpyrk1", 3, 8).unwrap(), b"fooba");
+        assert_eq!(
+            decode::<false, true>(b"csq pyr\nk1", 3, 8).unwrap(),
+            b"fooba"
+        );
+        assert!(matches!(
+            decode::<true, false>(b"csqpyrk1", 3, 8),
+            Err(Error::NonCanonical)
+        ));
+        assert!(matches!(
+            decode::<true, true>(b"CSQ PYR\nKI", 3, 8),
+            Err(Error::NonCanonical)
+        ));
+        assert!(matches!(
+            decode::<false, false>(b"CSQ PYRK1", 3, 8),
+            Err(Error::InvalidSymbol { byte: b' ', .. })
+        ));
+        assert!(matches!(
+            decode::<true, true>(b"CSQPU", 3, 8),
+            Err(Error::InvalidSymbol { byte: b'U', .. })
+        ));
+        // The length and the padding bits are checked only if STRICT is true.
+        assert_eq!(decode::<false, false>(b"CSQPYR", 3, 8).unwrap(), b"foo");
+        assert!(matches!(
+            decode::<true, false>(b"CSQPYR", 3, 8),
+            Err(Error::NonCanonical)
+        ));
+        assert_eq!(decode::<false, false>(b"CSQPZ", 3, 8).unwrap(), b"foo");
+        assert!(matches!(
+            decode::<true, false>(b"CSQPZ", 3, 8),
+            Err(Error::NonCanonical)
+        ));
+    }
+
+    #[test]
+    fn test_error_position() {
+        let wrapped = b"CSQPYRK1\r\nCSQPYRK1\r\nCSQ U";
+        for chunk_len in [1, 3, 8, 100] {
+            for output_len in [5, 9, 64] {
+                let err = decode::<false, true>(wrapped, chunk_len, output_len).unwrap_err();
+                assert_eq!(
+                    err,
+                    Error::InvalidSymbol {
+                        byte: b'U',
+                        position: 24
+                    }
+                );
+                let err = decode::<true, true>(wrapped, chunk_len, output_len).unwrap_err();
+                assert_eq!(
+                    err,
+                    Error::InvalidSymbol {
+                        byte: b'U',
+                        position: 24
+                    }
+                );
+                let err = decode::<true, false>(b"CSQPYRK1CSQU", chunk_len, output_len);
+                assert_eq!(
+                    err.unwrap_err(),
+                    Error::InvalidSymbol {
+                        byte: b'U',
+                        position: 11
+                    }
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! Coupon and invite codes with an expiry time, a kind tag and a check symbol.
+//!
+//! A [`Coupon`] is packed into bytes: the expiry time as a 32-bit big-endian Unix
+//! timestamp in seconds, a kind byte and the payload. The bytes are encoded,
+//! followed by a check symbol, and split into groups of 4 symbols joined by
+//! `-`, like `CNAM-5001-EHJP-2V9D-6GS0-G`.
+//!
+//! Parsing ignores the case, hyphens and whitespace, and reports a mistyped
+//! code, the wrong kind or an expired coupon as a [`CouponError`]. The check
+//! symbol detects any single wrong symbol and any swap of adjacent symbols.
+//! The codes are not signed, so don't use them as proof that you issued them.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::coupon::{Coupon, CouponError};
+//!
+//! const INVITE: u8 = 1;
+//! let now = 1_700_000_000;
+//! let coupon = Coupon::new(now + 86_400, INVITE, b"team-42".to_vec());
+//! let code = coupon.encode();
+//! assert_eq!(code, "CNAM-5001-EHJP-2V9D-6GS0-G");
+//!
+//! let parsed = Coupon::parse(&code.to_lowercase(), INVITE, now).unwrap();
+//! assert_eq!(parsed, coupon);
+//! assert!(matches!(
+//!     Coupon::parse(&code, INVITE, now + 86_401),
+//!     Err(CouponError::Expired { .. })
+//! ));
+//! assert!(matches!(
+//!     Coupon::parse("CNAM-5001-EHJP-2V9D-6GS0-H", INVITE, now),
+//!     Err(CouponError::BadChecksum)
+//! ));
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    capacity_hint_for_decode, capacity_hint_for_encode, decode_symbol, decode_to_vec,
+    encode_symbol, encode_to_vec, is_symbol_value, ParseError,
+};
+
+/// The number of symbols in each group of a code.
+pub const GROUP_LEN: usize = 4;
+
+/// The separator between groups of a code.
+pub const SEPARATOR: char = '-';
+
+// The number of bytes before the payload: the expiry time and the kind.
+const HEADER_LEN: usize = 5;
+
+/// A coupon or invite code.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Coupon {
+    /// The Unix timestamp in seconds when the coupon expires.
+    pub expires_at: u32,
+    /// The kind of the coupon, checked when parsing.
+    pub kind: u8,
+    /// The application data, like a campaign or invitation ID.
+    pub payload: Vec<u8>,
+}
+
+impl Coupon {
+    /// Creates a coupon.
+    pub fn new(expires_at: u32, kind: u8, payload: Vec<u8>) -> Self {
+        Self {
+            expires_at,
+            kind,
+            payload,
+        }
+    }
+
+    /// Returns the code of the coupon, with the check symbol and in groups.
+    pub fn encode(&self) -> String {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.payload.len());
+        bytes.extend_from_slice(&self.expires_at.to_be_bytes());
+        bytes.push(self.kind);
+        bytes.extend_from_slice(&self.payload);
+        let mut symbols = encode_to_vec(&bytes);
+        symbols.push(encode_symbol(check_value(&symbols)));
+
+        let mut code = String::with_capacity(symbols.len() + symbols.len() / GROUP_LEN);
+        for (i, &b) in symbols.iter().enumerate() {
+            if i > 0 && i % GROUP_LEN == 0 {
+                code.push(SEPARATOR);
+            }
+            code.push(char::from(b));
+        }
+        code
+    }
+
+    /// Decodes a code without checking the kind and the expiry time.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the code contains an invalid symbol, is too short or
+    /// has a wrong check symbol.
+    pub fn decode(code: &str) -> Result<Self, CouponError> {
+        let mut symbols = Vec::with_capacity(code.len());
+        for (i, b) in code.bytes().enumerate() {
+            if b == SEPARATOR as u8 || b.is_ascii_whitespace() {
+                continue;
+            }
+            let v = decode_symbol(b);
+            if !is_symbol_value(v) {
+                let err = ParseError::invalid_symbol(b, i);
+                return Err(CouponError::InvalidSymbol(err));
+            }
+            symbols.push(encode_symbol(v));
+        }
+        let check = symbols.pop().ok_or(CouponError::TooShort)?;
+        let decoded_len = capacity_hint_for_decode(symbols.len());
+        if decoded_len < HEADER_LEN || capacity_hint_for_encode(decoded_len) != symbols.len() {
+            return Err(CouponError::TooShort);
+        }
+        if encode_symbol(check_value(&symbols)) != check {
+            return Err(CouponError::BadChecksum);
+        }
+        // The symbols are valid, so this never fails.
+        let bytes = decode_to_vec(&symbols).unwrap_or_default();
+        let (header, payload) = bytes.split_at(HEADER_LEN.min(bytes.len()));
+        let mut expires_at = [0; 4];
+        expires_at.copy_from_slice(header.get(..4).unwrap_or(&[0; 4]));
+        Ok(Self {
+            expires_at: u32::from_be_bytes(expires_at),
+            kind: header.get(4).copied().unwrap_or_default(),
+            payload: payload.to_vec(),
+        })
+    }
+
+    /// Decodes a code and checks that the coupon is of `kind` and not expired
+    /// at `now`, a Unix timestamp in seconds.
+    ///
+    /// A coupon expires after the second of its expiry time.
+    ///
+    /// # Errors
+    /// Returns [`Err`] like [`decode`](Self::decode), or if the kind is wrong
+    /// or the coupon is expired.
+    pub fn parse(code: &str, kind: u8, now: u32) -> Result<Self, CouponError> {
+        let coupon = Self::decode(code)?;
+        if coupon.kind != kind {
+            return Err(CouponError::WrongKind {
+                expected: kind,
+                actual: coupon.kind,
+            });
+        }
+        if now > coupon.expires_at {
+            return Err(CouponError::Expired {
+                expires_at: coupon.expires_at,
+            });
+        }
+        Ok(coupon)
+    }
+}
+
+/// The error type for parsing coupon codes.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum CouponError {
+    /// The code contains a byte which is not a symbol, a hyphen or whitespace.
+    InvalidSymbol(ParseError),
+    /// The code has too few symbols or a length no coupon is encoded to.
+    TooShort,
+    /// The check symbol doesn't match, so the code is mistyped.
+    BadChecksum,
+    /// The coupon is of another kind.
+    WrongKind {
+        /// The kind given to [`Coupon::parse`].
+        expected: u8,
+        /// The kind of the coupon.
+        actual: u8,
+    },
+    /// The coupon is expired.
+    Expired {
+        /// The Unix timestamp in seconds when the coupon expired.
+        expires_at: u32,
+    },
+}
+
+impl fmt::Display for CouponError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CouponError::InvalidSymbol(err) => err.fmt(f),
+            CouponError::TooShort => f.write_str("code is too short or has a wrong length"),
+            CouponError::BadChecksum => f.write_str("code is mistyped, the check symbol differs"),
+            CouponError::WrongKind { expected, actual } => write!(
+                f,
+                "coupon is of kind {}, but kind {} is expected",
+                actual, expected
+            ),
+            CouponError::Expired { expires_at } => {
+                write!(f, "coupon expired at Unix time {}", expires_at)
+            }
+        }
+    }
+}
+
+impl core::error::Error for CouponError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            CouponError::InvalidSymbol(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+// check_value returns the check value of canonical `symbols`, so that the
+// polynomial of the values of the symbols followed by the check value has a
+// root at x, a primitive element of GF(32). A wrong symbol, or a swap of two
+// different adjacent symbols including the check symbol, always changes it.
+fn check_value(symbols: &[u8]) -> u8 {
+    symbols.iter().fold(0, |acc, &b| {
+        // Multiply by x modulo the primitive polynomial x^5 + x^2 + 1.
+        let shifted = (acc ^ decode_symbol(b)) << 1;
+        if shifted & 0x20 != 0 {
+            shifted ^ 0x25
+        } else {
+            shifted
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ENCODE_SYMBOLS;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn test_round_trip() {
+        for len in 0..20 {
+            let coupon = Coupon::new(0x1234_5678, 7, vec![0xa5; len]);
+            let code = coupon.encode();
+            assert!(code.split(SEPARATOR).all(|group| group.len() <= GROUP_LEN));
+            assert_eq!(Coupon::decode(&code).unwrap(), coupon);
+            let spaced = code.replace(SEPARATOR, " ");
+            assert_eq!(Coupon::parse(&spaced, 7, 0x1234_5678).unwrap(), coupon);
+        }
+    }
+
+    #[test]
+    fn test_check_symbol() {
+        let code: Vec<u8> = Coupon::new(1, 2, b"payload".to_vec())
+            .encode()
+            .bytes()
+            .filter(|&b| b != SEPARATOR as u8)
+            .collect();
+        for i in 0..code.len() {
+            for &symbol in ENCODE_SYMBOLS.iter().filter(|&&s| s != code[i]) {
+                let mut typo = code.clone();
+                typo[i] = symbol;
+                let typo = String::from_utf8(typo).unwrap();
+                assert!(Coupon::decode(&typo).is_err(), "{}", typo);
+            }
+            if i + 1 < code.len() && code[i] != code[i + 1] {
+                let mut swapped = code.clone();
+                swapped.swap(i, i + 1);
+                let swapped = String::from_utf8(swapped).unwrap();
+                assert!(Coupon::decode(&swapped).is_err(), "{}", swapped);
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let code = Coupon::new(100, 1, b"x".to_vec()).encode();
+        assert!(matches!(
+            Coupon::parse(&code, 2, 0),
+            Err(CouponError::WrongKind {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        assert!(Coupon::parse(&code, 1, 100).is_ok());
+        let err = Coupon::parse(&code, 1, 101).unwrap_err();
+        assert!(matches!(err, CouponError::Expired { expires_at: 100 }));
+        assert_eq!(err.to_string(), "coupon expired at Unix time 100");
+
+        for short in ["", "0", "0000-0000", "0000-0000-00"] {
+            assert!(matches!(Coupon::decode(short), Err(CouponError::TooShort)));
+        }
+        let err = Coupon::decode("0000_0000").unwrap_err();
+        assert_eq!(err.to_string(), "invalid symbol '_' at position 4");
+    }
+}
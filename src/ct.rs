@@ -0,0 +1,360 @@
+//! Encoding and decoding in constant time with respect to the data.
+//!
+//! The functions in this module take the same time for any bytes and symbols
+//! of the same length, for handling secrets like API keys and OTP seeds where
+//! timing side channels matter. Symbols are mapped with arithmetic masks
+//! instead of table lookups and branches, and decoding checks every symbol
+//! before reporting an error instead of stopping at the first invalid one. So
+//! the error does not tell which symbol is invalid.
+//!
+//! Only the lengths of the input and output are visible through timing. These
+//! functions are slower than the table based ones, and the compiler does not
+//! guarantee to keep code branch-free, so check the generated code on targets
+//! where this matters.
+//!
+//! With the `subtle` feature enabled, [`ct_eq_decoded`] and [`ct_eq_bytes`]
+//! compare encoded tokens by their decoded bytes.
+//!
+//! With the `zeroize` feature enabled, the scratch buffers holding input and
+//! output blocks are wiped after use, and `dest` is wiped when decoding fails.
+//!
+//! # Examples
+//! ```
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32::{ct, Error};
+//!
+//! let mut encoded = [0; 21];
+//! let len = ct::encode_to_slice(b"Hello, world!", &mut encoded)?;
+//! assert_eq!(&encoded[..len], b"91JPRV3F5GG7EVVJDHJ22");
+//!
+//! let mut decoded = [0; 13];
+//! let len = ct::decode_to_slice(b"91jprv3f5gg7evvjdhj22", &mut decoded)?;
+//! assert_eq!(&decoded[..len], b"Hello, world!");
+//!
+//! let err = ct::decode_to_slice(b"91JPRV3U", &mut decoded).unwrap_err();
+//! assert!(matches!(err, Error::InvalidSymbols));
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "subtle")]
+use subtle::{Choice, ConstantTimeEq};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+use crate::{
+    capacity_hint_for_decode, capacity_hint_for_encode, copy_prefix, Error, Result, BLOCK_BYTE_LEN,
+    BLOCK_SYMBOL_LEN, BYTE_BIT_LEN, DECODED_BIT_LEN,
+};
+
+/// Encodes bytes into `dest` in constant time and returns the number of bytes
+/// written.
+///
+/// # Errors
+/// Returns [`Err`] if `dest` is shorter than [`capacity_hint_for_encode`] of
+/// the input length.
+pub fn encode_to_slice(input: &[u8], dest: &mut [u8]) -> Result<usize> {
+    let len = capacity_hint_for_encode(input.len());
+    let dest = dest.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+    for (chunk, out) in input
+        .chunks(BLOCK_BYTE_LEN)
+        .zip(dest.chunks_mut(BLOCK_SYMBOL_LEN))
+    {
+        let mut block = [0; BLOCK_BYTE_LEN];
+        copy_prefix(&mut block, chunk);
+        let mut bits = 0u64;
+        for &b in &block {
+            bits = (bits << BYTE_BIT_LEN) | u64::from(b);
+        }
+        for (i, o) in out.iter_mut().enumerate() {
+            let shift = (BLOCK_SYMBOL_LEN - 1 - i) * DECODED_BIT_LEN;
+            *o = encode_symbol((bits >> shift) as u8 & 0x1f);
+        }
+        #[cfg(feature = "zeroize")]
+        {
+            block.zeroize();
+            bits.zeroize();
+        }
+    }
+    Ok(len)
+}
+
+/// Decodes symbols into `dest` in constant time and returns the number of bytes
+/// written, accepting lowercase symbols and the aliases `I`, `L` and `O`.
+///
+/// # Errors
+/// Returns [`Error::InvalidSymbols`] if the input contains an invalid symbol,
+/// or [`Error::BufferTooSmall`] if `dest` is shorter than
+/// [`capacity_hint_for_decode`] of the input length. `dest` may be partially
+/// written on error.
+pub fn decode_to_slice(input: &[u8], dest: &mut [u8]) -> Result<usize> {
+    let len = capacity_hint_for_decode(input.len());
+    let dest = dest.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+    let mut valid = 0xff;
+    for (chunk, out) in input.chunks(BLOCK_SYMBOL_LEN).zip(
+        dest.chunks_mut(BLOCK_BYTE_LEN)
+            .chain(core::iter::repeat_with(|| &mut [][..])),
+    ) {
+        valid &= decode_block(chunk, out);
+    }
+    if valid != 0xff {
+        #[cfg(feature = "zeroize")]
+        dest.zeroize();
+        return Err(Error::InvalidSymbols);
+    }
+    Ok(len)
+}
+
+/// Encodes bytes to a new [`Vec<u8>`] in constant time.
+#[cfg(feature = "alloc")]
+pub fn encode_to_vec(input: &[u8]) -> Vec<u8> {
+    let mut dest = alloc::vec![0; capacity_hint_for_encode(input.len())];
+    // The destination has the needed length, so this never fails.
+    encode_to_slice(input, &mut dest).unwrap_or_default();
+    dest
+}
+
+/// Decodes symbols to a new [`Vec<u8>`] in constant time.
+///
+/// # Errors
+/// Returns [`Error::InvalidSymbols`] if the input contains an invalid symbol.
+#[cfg(feature = "alloc")]
+pub fn decode_to_vec(input: &[u8]) -> Result<Vec<u8>> {
+    let mut dest = alloc::vec![0; capacity_hint_for_decode(input.len())];
+    decode_to_slice(input, &mut dest)?;
+    Ok(dest)
+}
+
+/// Compares the bytes which two inputs decode to in constant time, without
+/// allocating.
+///
+/// Lowercase symbols, aliases and padding bits are ignored like in decoding, so
+/// `"CSQPYRK1E8"` equals `"csqpyrkie8"`. The result is false if either input
+/// contains an invalid symbol. Only the lengths of the inputs are visible
+/// through timing.
+///
+/// # Examples
+/// ```
+/// use clockwork_base32::ct;
+///
+/// let stored = b"CSQPYRK1E8";
+/// assert!(bool::from(ct::ct_eq_decoded(b"csqpyrkie8", stored)));
+/// assert!(!bool::from(ct::ct_eq_decoded(b"CSQPYRK1F8", stored)));
+/// assert!(!bool::from(ct::ct_eq_decoded(b"CSQPYRK1EU", stored)));
+/// ```
+#[cfg(feature = "subtle")]
+pub fn ct_eq_decoded(a: &[u8], b: &[u8]) -> Choice {
+    if capacity_hint_for_decode(a.len()) != capacity_hint_for_decode(b.len()) {
+        return Choice::from(0);
+    }
+    let mut eq = Choice::from(1);
+    for (x, y) in a.chunks(BLOCK_SYMBOL_LEN).zip(b.chunks(BLOCK_SYMBOL_LEN)) {
+        let mut block = [0; BLOCK_BYTE_LEN];
+        let out = block
+            .get_mut(..capacity_hint_for_decode(x.len()))
+            .unwrap_or_default();
+        let valid = decode_block(x, out);
+        eq &= ct_eq_block(y, out, valid);
+        #[cfg(feature = "zeroize")]
+        block.zeroize();
+    }
+    eq
+}
+
+/// Compares the bytes which an input decodes to with `expected` in constant
+/// time, without allocating.
+///
+/// This is for verifying a presented token against the stored bytes. The
+/// result is false if the input contains an invalid symbol. Only the lengths of
+/// the input and `expected` are visible through timing.
+///
+/// # Examples
+/// ```
+/// use clockwork_base32::ct;
+///
+/// assert!(bool::from(ct::ct_eq_bytes(b"csqpyrk1e8", b"foobar")));
+/// assert!(!bool::from(ct::ct_eq_bytes(b"CSQPYRK1E8", b"foobaz")));
+/// ```
+#[cfg(feature = "subtle")]
+pub fn ct_eq_bytes(input: &[u8], expected: &[u8]) -> Choice {
+    if capacity_hint_for_decode(input.len()) != expected.len() {
+        return Choice::from(0);
+    }
+    let mut eq = Choice::from(1);
+    for (chunk, bytes) in input
+        .chunks(BLOCK_SYMBOL_LEN)
+        .zip(expected.chunks(BLOCK_BYTE_LEN))
+    {
+        eq &= ct_eq_block(chunk, bytes, 0xff);
+    }
+    eq
+}
+
+// ct_eq_block decodes a block of up to 8 symbols and compares it with `expected`,
+// which has the decoded length. The result is false unless `valid` is 0xff.
+#[cfg(feature = "subtle")]
+fn ct_eq_block(chunk: &[u8], expected: &[u8], valid: u8) -> Choice {
+    let mut block = [0; BLOCK_BYTE_LEN];
+    let out = block.get_mut(..expected.len()).unwrap_or_default();
+    let valid = valid & decode_block(chunk, out);
+    let eq = out.ct_eq(expected) & Choice::from(valid & 1);
+    #[cfg(feature = "zeroize")]
+    block.zeroize();
+    eq
+}
+
+// decode_block decodes a block of up to 8 symbols into `out`, which has the
+// decoded length, and returns a mask which is 0xff if all symbols are valid, or
+// 0 otherwise.
+fn decode_block(chunk: &[u8], out: &mut [u8]) -> u8 {
+    let mut block = [b'0'; BLOCK_SYMBOL_LEN];
+    copy_prefix(&mut block, chunk);
+    let mut valid = 0xff;
+    let mut bits = 0u64;
+    for &b in &block {
+        let (v, mask) = decode_symbol(b);
+        valid &= mask;
+        bits = (bits << DECODED_BIT_LEN) | u64::from(v);
+    }
+    for (i, o) in out.iter_mut().enumerate() {
+        *o = (bits >> ((BLOCK_BYTE_LEN - 1 - i) * BYTE_BIT_LEN)) as u8;
+    }
+    #[cfg(feature = "zeroize")]
+    {
+        block.zeroize();
+        bits.zeroize();
+    }
+    valid
+}
+
+// mask_in_range returns 0xff if `lo <= x <= hi`, or 0 otherwise, without branches.
+#[inline]
+fn mask_in_range(x: u8, lo: u8, hi: u8) -> u8 {
+    let below = u16::from(x).wrapping_sub(u16::from(lo));
+    let above = u16::from(hi).wrapping_sub(u16::from(x));
+    // Either difference wraps around and sets the upper byte when out of range.
+    !(((below | above) >> 8) as u8)
+}
+
+// encode_symbol returns the symbol for a value `v` below 32, skipping the
+// letters I, L, O and U after the digits.
+#[inline]
+fn encode_symbol(v: u8) -> u8 {
+    b'0' + v
+        + (mask_in_range(v, 10, 31) & (b'A' - b'0' - 10))
+        + (mask_in_range(v, 18, 31) & 1)
+        + (mask_in_range(v, 20, 31) & 1)
+        + (mask_in_range(v, 22, 31) & 1)
+        + (mask_in_range(v, 27, 31) & 1)
+}
+
+// decode_symbol returns the value of a symbol and a mask which is 0xff if it is
+// valid, or 0 otherwise.
+#[inline]
+fn decode_symbol(b: u8) -> (u8, u8) {
+    // Setting bit 5 maps uppercase letters to lowercase, and keeps digits as is.
+    let l = b | 0x20;
+    let ranges = [
+        (mask_in_range(b, b'0', b'9'), b.wrapping_sub(b'0')),
+        (mask_in_range(l, b'o', b'o'), 0),
+        (
+            mask_in_range(l, b'i', b'i') | mask_in_range(l, b'l', b'l'),
+            1,
+        ),
+        (mask_in_range(l, b'a', b'h'), l.wrapping_sub(b'a' - 10)),
+        (mask_in_range(l, b'j', b'k'), l.wrapping_sub(b'j' - 18)),
+        (mask_in_range(l, b'm', b'n'), l.wrapping_sub(b'm' - 20)),
+        (mask_in_range(l, b'p', b't'), l.wrapping_sub(b'p' - 22)),
+        (mask_in_range(l, b'v', b'z'), l.wrapping_sub(b'v' - 27)),
+    ];
+    ranges.iter().fold((0, 0), |(v, valid), &(mask, value)| {
+        (v | (mask & value), valid | mask)
+    })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbols() {
+        for v in 0..32 {
+            assert_eq!(encode_symbol(v), crate::encode_symbol(v));
+        }
+        for b in 0..=255u8 {
+            let (v, valid) = decode_symbol(b);
+            let expected = crate::decode_symbol(b);
+            assert_eq!(valid == 0xff, crate::is_symbol_value(expected), "{}", b);
+            assert!(valid == 0 || valid == 0xff);
+            if valid == 0xff {
+                assert_eq!(v, expected, "{}", b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let input: Vec<u8> = (0..=255).rev().collect();
+        for len in 0..40 {
+            let encoded = encode_to_vec(&input[..len]);
+            assert_eq!(encoded, crate::encode_to_vec(&input[..len]));
+            assert_eq!(decode_to_vec(&encoded).unwrap(), &input[..len]);
+        }
+        let lenient = b"91jprv3f5gg7evvjdhj2";
+        assert_eq!(
+            decode_to_vec(lenient).unwrap(),
+            crate::decode_to_vec(lenient).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            decode_to_vec(b"U1JPRV3F5G"),
+            Err(Error::InvalidSymbols)
+        ));
+        assert!(matches!(
+            decode_to_vec(b"91JPRV3F5-"),
+            Err(Error::InvalidSymbols)
+        ));
+        let mut dest = [0xff; 5];
+        assert!(decode_to_slice(b"91JPRV3U", &mut dest).is_err());
+        if cfg!(feature = "zeroize") {
+            assert_eq!(dest, [0; 5]);
+        }
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn test_ct_eq() {
+        let input: Vec<u8> = (0..=255).rev().collect();
+        for len in 0..20 {
+            let bytes = &input[..len];
+            let encoded = crate::encode_to_vec(bytes);
+            let lower = encoded.to_ascii_lowercase();
+            assert!(bool::from(ct_eq_decoded(&encoded, &lower)));
+            assert!(bool::from(ct_eq_bytes(&lower, bytes)));
+            if len > 0 {
+                let other = crate::encode_to_vec(&input[1..=len]);
+                assert!(!bool::from(ct_eq_decoded(&encoded, &other)));
+                assert!(!bool::from(ct_eq_bytes(&encoded, &input[1..=len])));
+            }
+            let longer = crate::encode_to_vec(&input[..=len]);
+            assert!(!bool::from(ct_eq_decoded(&encoded, &longer)));
+            assert!(!bool::from(ct_eq_bytes(&encoded, &input[..=len])));
+        }
+        // Invalid symbols never compare equal, even to themselves.
+        assert!(!bool::from(ct_eq_decoded(b"CSQPYRK1EU", b"CSQPYRK1EU")));
+        assert!(!bool::from(ct_eq_bytes(b"UU", &[0xd6])));
+        assert!(matches!(
+            encode_to_slice(b"Hello", &mut [0; 7]),
+            Err(Error::BufferTooSmall)
+        ));
+        assert!(matches!(
+            decode_to_slice(b"CSQPYRK1", &mut [0; 4]),
+            Err(Error::BufferTooSmall)
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+//! An adapter with the API of the `data-encoding` crate.
+//!
+//! [`CLOCKWORK`] has the methods of [`data_encoding::Encoding`] which code
+//! abstracted over `data-encoding` calls, like `encode`, `decode`,
+//! `encode_mut` and `decode_len`, and returns its error types. So replacing a
+//! constant like `data_encoding::BASE32` with it switches call sites to the
+//! functions of this crate without rewriting them.
+//!
+//! For code which needs an actual [`data_encoding::Encoding`] value, like a
+//! struct field, [`ClockworkEncoding::to_encoding`] builds one with the same
+//! alphabet and aliases. It uses the kernels of `data-encoding`, and unlike this
+//! crate it rejects inputs of lengths which no bytes encode to.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::data_encoding::CLOCKWORK as BASE32;
+//!
+//! let encoded = BASE32.encode(b"Hello, world!");
+//! assert_eq!(encoded, "91JPRV3F5GG7EVVJDHJ22");
+//! assert_eq!(BASE32.decode(b"91jprv3f5gg7evvjdhj22").unwrap(), b"Hello, world!");
+//!
+//! let err = BASE32.decode(b"91JPU").unwrap_err();
+//! assert_eq!(err.kind, data_encoding::DecodeKind::Symbol);
+//! assert_eq!(err.position, 4);
+//!
+//! let encoding = BASE32.to_encoding();
+//! assert_eq!(encoding.encode(b"Hello, world!"), encoded);
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use ::data_encoding::{BitOrder, DecodeError, DecodeKind, DecodePartial, Encoding, Specification};
+
+use crate::{
+    append_encoded_to_string, capacity_hint_for_decode, capacity_hint_for_encode, decode_symbol,
+    decode_to_slice, decode_to_vec, encode_to_slice, encode_to_string, is_symbol_value,
+    BLOCK_BYTE_LEN, BLOCK_SYMBOL_LEN,
+};
+
+/// The Clockwork Base32 encoding with the methods of [`Encoding`].
+pub const CLOCKWORK: ClockworkEncoding = ClockworkEncoding;
+
+/// The type of [`CLOCKWORK`].
+///
+/// Decoding accepts lowercase symbols, the aliases `I`, `L` and `O`, and inputs
+/// of any length like the other decoding functions of this crate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClockworkEncoding;
+
+impl ClockworkEncoding {
+    /// Returns the encoded length of an input of length `len`.
+    pub fn encode_len(&self, len: usize) -> usize {
+        capacity_hint_for_encode(len)
+    }
+
+    /// Encodes `input` into `output`.
+    ///
+    /// Unlike [`Encoding::encode_mut`], which panics, nothing is written if
+    /// `output` is not [`encode_len`](Self::encode_len) bytes long.
+    pub fn encode_mut(&self, input: &[u8], output: &mut [u8]) {
+        if output.len() == self.encode_len(input.len()) {
+            // The output has the needed length, so this never fails.
+            encode_to_slice(input, output).unwrap_or_default();
+        }
+    }
+
+    /// Appends the encoded result of `input` to `output`.
+    pub fn encode_append(&self, input: &[u8], output: &mut String) {
+        append_encoded_to_string(output, input.iter());
+    }
+
+    /// Encodes `input` to a new [`String`].
+    pub fn encode(&self, input: &[u8]) -> String {
+        encode_to_string(input)
+    }
+
+    /// Returns the decoded length of an input of length `len`.
+    ///
+    /// # Errors
+    /// This never fails, since inputs of any length are decoded. The [`Result`]
+    /// is for compatibility with [`Encoding::decode_len`].
+    pub fn decode_len(&self, len: usize) -> Result<usize, DecodeError> {
+        Ok(capacity_hint_for_decode(len))
+    }
+
+    /// Decodes `input` into `output` and returns the decoded length.
+    ///
+    /// # Errors
+    /// Returns [`DecodeKind::Symbol`] at the position of the first invalid
+    /// symbol, after decoding the whole blocks before it. Unlike
+    /// [`Encoding::decode_mut`], which panics, this returns
+    /// [`DecodeKind::Length`] if `output` is not
+    /// [`decode_len`](Self::decode_len) bytes long.
+    pub fn decode_mut(&self, input: &[u8], output: &mut [u8]) -> Result<usize, DecodePartial> {
+        if output.len() != capacity_hint_for_decode(input.len()) {
+            return Err(DecodePartial {
+                read: 0,
+                written: 0,
+                error: DecodeError {
+                    position: 0,
+                    kind: DecodeKind::Length,
+                },
+            });
+        }
+        match input
+            .iter()
+            .position(|&b| !is_symbol_value(decode_symbol(b)))
+        {
+            None => {
+                // The symbols and the output length are checked, so this never fails.
+                Ok(decode_to_slice(input, output).unwrap_or_default())
+            }
+            Some(position) => {
+                let read = position / BLOCK_SYMBOL_LEN * BLOCK_SYMBOL_LEN;
+                let valid = input.get(..read).unwrap_or_default();
+                let written = decode_to_slice(valid, output).unwrap_or_default();
+                debug_assert_eq!(written, read / BLOCK_SYMBOL_LEN * BLOCK_BYTE_LEN);
+                Err(DecodePartial {
+                    read,
+                    written,
+                    error: symbol_error(position),
+                })
+            }
+        }
+    }
+
+    /// Decodes `input` to a new [`Vec<u8>`].
+    ///
+    /// # Errors
+    /// Returns [`DecodeKind::Symbol`] at the position of the first invalid
+    /// symbol.
+    pub fn decode(&self, input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        decode_to_vec(input).map_err(|_| {
+            let position = input
+                .iter()
+                .position(|&b| !is_symbol_value(decode_symbol(b)));
+            symbol_error(position.unwrap_or_default())
+        })
+    }
+
+    /// Returns whether decoding accepts only the output of encoding, which is
+    /// false because of the aliases and lowercase symbols.
+    pub fn is_canonical(&self) -> bool {
+        false
+    }
+
+    /// Returns the [`Specification`] of the encoding.
+    pub fn specification(&self) -> Specification {
+        let mut spec = Specification::new();
+        spec.symbols.push_str("0123456789ABCDEFGHJKMNPQRSTVWXYZ");
+        spec.bit_order = BitOrder::MostSignificantFirst;
+        spec.check_trailing_bits = false;
+        spec.translate.from.push_str("abcdefghjkmnpqrstvwxyzIiLlOo");
+        spec.translate.to.push_str("ABCDEFGHJKMNPQRSTVWXYZ111100");
+        spec
+    }
+
+    /// Builds an [`Encoding`] of `data-encoding` from the
+    /// [`specification`](Self::specification).
+    pub fn to_encoding(&self) -> Encoding {
+        // The specification is valid, which the tests check.
+        self.specification()
+            .encoding()
+            .unwrap_or_else(|_| Encoding::clone(&::data_encoding::BASE32))
+    }
+}
+
+impl From<ClockworkEncoding> for Encoding {
+    fn from(encoding: ClockworkEncoding) -> Self {
+        encoding.to_encoding()
+    }
+}
+
+fn symbol_error(position: usize) -> DecodeError {
+    DecodeError {
+        position,
+        kind: DecodeKind::Symbol,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_encoding() {
+        let encoding = CLOCKWORK.specification().encoding().unwrap();
+        let input: Vec<u8> = (0..=255).rev().collect();
+        for len in 0..40 {
+            let input = &input[..len];
+            let encoded = CLOCKWORK.encode(input);
+            assert_eq!(encoding.encode(input), encoded);
+            assert_eq!(CLOCKWORK.encode_len(len), encoding.encode_len(len));
+            let lower = encoded.to_ascii_lowercase().replace('1', "l");
+            assert_eq!(encoding.decode(lower.as_bytes()).unwrap(), input);
+            assert_eq!(CLOCKWORK.decode(lower.as_bytes()).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_decode_mut() {
+        let input = b"91JPRV3F5GG7EVUJDHJ22";
+        let mut output = [0; 13];
+        let err = CLOCKWORK.decode_mut(input, &mut output).unwrap_err();
+        assert_eq!((err.read, err.written), (8, 5));
+        assert_eq!(err.error, symbol_error(14));
+        assert_eq!(&output[..5], b"Hello");
+
+        let err = CLOCKWORK.decode_mut(input, &mut output[..12]).unwrap_err();
+        assert_eq!(err.error.kind, DecodeKind::Length);
+
+        let len = CLOCKWORK.decode_mut(b"91JPRV3F", &mut output[..5]).unwrap();
+        assert_eq!(&output[..len], b"Hello");
+    }
+}
@@ -0,0 +1,58 @@
+//! Diesel conversions storing the wrapper types in Text columns.
+
+use alloc::string::String;
+use core::convert::TryFrom;
+use std::io::Write;
+
+use ::diesel::backend::Backend;
+use ::diesel::deserialize::{self, FromSql};
+use ::diesel::query_builder::bind_collector::RawBytesBindCollector;
+use ::diesel::serialize::{self, IsNull, Output, ToSql};
+use ::diesel::sql_types::Text;
+
+use crate::{Base32Bytes, Base32String};
+
+impl<DB> ToSql<Text, DB> for Base32String
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        self.as_str().to_sql(out)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for Base32String
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let s = String::from_sql(bytes)?;
+        Ok(Self::try_from(s)?)
+    }
+}
+
+// The encoded text is not held by `Base32Bytes`, so it is written to the output
+// directly. This works for the backends collecting binds as raw bytes, that is
+// PostgreSQL and MySQL. Bind `Base32Bytes::encode()` for SQLite.
+impl<DB> ToSql<Text, DB> for Base32Bytes
+where
+    for<'c> DB: Backend<BindCollector<'c> = RawBytesBindCollector<DB>>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        write!(out, "{}", self)?;
+        Ok(IsNull::No)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for Base32Bytes
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let s = String::from_sql(bytes)?;
+        Ok(Self::decode(&s)?)
+    }
+}
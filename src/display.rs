@@ -0,0 +1,108 @@
+use core::fmt::{self, Write};
+
+use crate::{
+    capacity_hint_for_encode, encode_to_slice, IntoInput, BLOCK_BYTE_LEN, BLOCK_SYMBOL_LEN,
+};
+
+/// Returns an adapter which displays the input encoded, without allocating.
+///
+/// The adapter honors the width, fill and alignment of the format spec like
+/// strings do, and the precision truncates the encoded text to that many
+/// symbols, so short prefixes of IDs can be printed in logs.
+///
+/// # Examples
+/// ```
+/// use clockwork_base32 as base32;
+/// let hash = b"Hello, world!";
+/// assert_eq!(format!("{}", base32::display(hash)), "91JPRV3F5GG7EVVJDHJ22");
+/// assert_eq!(format!("{:.8}", base32::display(hash)), "91JPRV3F");
+/// assert_eq!(format!("[{:>10.5}]", base32::display(hash)), "[     91JPR]");
+/// assert_eq!(format!("[{:*^9}]", base32::display(b"foo")), "[**CSQPY**]");
+/// ```
+pub fn display<'a, I>(input: I) -> DisplayEncoded<'a>
+where
+    I: IntoInput<'a>,
+{
+    DisplayEncoded {
+        input: input.into_input(),
+    }
+}
+
+/// An adapter displaying bytes encoded, returned by [`display`].
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayEncoded<'a> {
+    input: &'a [u8],
+}
+
+impl DisplayEncoded<'_> {
+    // write_symbols writes the first `len` encoded symbols.
+    fn write_symbols(&self, f: &mut fmt::Formatter<'_>, len: usize) -> fmt::Result {
+        let mut symbols = [0; BLOCK_SYMBOL_LEN];
+        let mut remaining = len;
+        for block in self.input.chunks(BLOCK_BYTE_LEN) {
+            if remaining == 0 {
+                break;
+            }
+            let n = encode_to_slice(block, &mut symbols).map_err(|_| fmt::Error)?;
+            let n = n.min(remaining);
+            let block_symbols = symbols.get(..n).unwrap_or_default();
+            // The symbols are ASCII, so this never fails.
+            f.write_str(core::str::from_utf8(block_symbols).unwrap_or_default())?;
+            remaining -= n;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DisplayEncoded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let encoded_len = capacity_hint_for_encode(self.input.len());
+        let len = f.precision().map_or(encoded_len, |p| p.min(encoded_len));
+        let padding = f.width().map_or(0, |width| width.saturating_sub(len));
+        let (before, after) = match f.align() {
+            None | Some(fmt::Alignment::Left) => (0, padding),
+            Some(fmt::Alignment::Right) => (padding, 0),
+            Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        };
+        let fill = f.fill();
+        for _ in 0..before {
+            f.write_char(fill)?;
+        }
+        self.write_symbols(f, len)?;
+        for _ in 0..after {
+            f.write_char(fill)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn test_display() {
+        let input: alloc::vec::Vec<u8> = (0..40).map(|i| (i * 37) as u8).collect();
+        for len in 0..input.len() {
+            let encoded = crate::encode_to_string(&input[..len]);
+            assert_eq!(format!("{}", display(&input[..len])), encoded);
+            for precision in [0, 1, 7, 8, 9, 100] {
+                let expected = &encoded[..precision.min(encoded.len())];
+                assert_eq!(
+                    format!("{:.*}", precision, display(&input[..len])),
+                    expected
+                );
+                assert_eq!(
+                    format!("{:>12.*}", precision, display(&input[..len])),
+                    format!("{:>12}", expected)
+                );
+                assert_eq!(
+                    format!("{:-^13.*}", precision, display(&input[..len])),
+                    format!("{:-^13}", expected)
+                );
+            }
+        }
+        assert_eq!(format!("{:6}|", display(b"foo")), "CSQPY |");
+    }
+}
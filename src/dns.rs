@@ -0,0 +1,156 @@
+//! Embedding encoded data in DNS names and TXT records.
+//!
+//! A DNS label is limited to 63 bytes, so longer encoded data is split into
+//! labels joined with `.`, like `<label>.<label>.example.com`. Clockwork Base32
+//! fits DNS well: the symbols are letters and digits only, and decoding ignores
+//! the case, which resolvers and caches may change.
+//!
+//! # Examples
+//! ```
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32::dns;
+//!
+//! let data = [0xab; 50];
+//! let labels = dns::encode_to_labels(&data);
+//! assert_eq!(labels.split('.').map(str::len).collect::<Vec<_>>(), [63, 17]);
+//!
+//! let name = format!("{}.telemetry.example.com", labels.to_ascii_lowercase());
+//! let encoded = name.strip_suffix(".telemetry.example.com").unwrap();
+//! assert_eq!(dns::decode_labels(encoded)?, data);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A character string of a TXT record is limited to 255 bytes, so longer data
+//! like keys and proofs is published as several strings of one record.
+//!
+//! ```
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32::dns;
+//!
+//! let key = [0x5a; 200];
+//! let strings = dns::encode_to_txt_strings(&key);
+//! assert_eq!(strings.iter().map(String::len).collect::<Vec<_>>(), [255, 65]);
+//!
+//! // Zone file tools may show the strings with spaces and line breaks between them.
+//! let record = strings.join(" \n  ");
+//! assert_eq!(dns::decode_txt_strings(record.split('\n'))?, key);
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{decode_to_vec, encode_to_string, Result};
+
+/// The maximum length of a DNS label in bytes.
+pub const MAX_LABEL_LEN: usize = 63;
+
+/// The maximum length of a character string in a TXT record in bytes.
+pub const MAX_TXT_STRING_LEN: usize = 255;
+
+/// Encodes bytes and splits the result into labels of up to
+/// [`MAX_LABEL_LEN`] symbols joined with `.`.
+///
+/// An empty input gives an empty string, which is not a valid label.
+pub fn encode_to_labels(input: &[u8]) -> String {
+    join_chunks(&encode_to_string(input), MAX_LABEL_LEN, ".")
+}
+
+/// Decodes labels joined with `.` in order, ignoring the dots.
+///
+/// The labels may have any length, and a trailing dot of a fully qualified
+/// name is accepted.
+///
+/// # Errors
+/// Returns [`Err`] if a label contains an invalid symbol.
+pub fn decode_labels(name: &str) -> Result<Vec<u8>> {
+    let symbols: Vec<u8> = name.bytes().filter(|&b| b != b'.').collect();
+    decode_to_vec(&symbols)
+}
+
+/// Encodes bytes and splits the result into strings of up to
+/// [`MAX_TXT_STRING_LEN`] symbols for a TXT record.
+///
+/// An empty input gives no strings.
+pub fn encode_to_txt_strings(input: &[u8]) -> Vec<String> {
+    let encoded = encode_to_string(input);
+    encoded
+        .as_bytes()
+        .chunks(MAX_TXT_STRING_LEN)
+        // The encoded result is ASCII, so any chunk is valid UTF-8.
+        .map(|chunk| String::from(core::str::from_utf8(chunk).unwrap_or_default()))
+        .collect()
+}
+
+/// Decodes the strings of a TXT record in order, ignoring ASCII whitespace in
+/// and between them.
+///
+/// # Errors
+/// Returns [`Err`] if a string contains an invalid symbol.
+pub fn decode_txt_strings<I>(strings: I) -> Result<Vec<u8>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut symbols = Vec::new();
+    for s in strings {
+        let s = s.as_ref().bytes().filter(|b| !b.is_ascii_whitespace());
+        symbols.extend(s);
+    }
+    decode_to_vec(&symbols)
+}
+
+// join_chunks splits the ASCII string `encoded` into chunks of up to `len` bytes
+// joined with `separator`.
+fn join_chunks(encoded: &str, len: usize, separator: &str) -> String {
+    let count = encoded.len().div_ceil(len);
+    let mut output = String::with_capacity(encoded.len() + count.saturating_sub(1));
+    for (i, chunk) in encoded.as_bytes().chunks(len).enumerate() {
+        if i > 0 {
+            output.push_str(separator);
+        }
+        // The encoded result is ASCII, so any chunk is valid UTF-8.
+        output.push_str(core::str::from_utf8(chunk).unwrap_or_default());
+    }
+    output
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels() {
+        let input: Vec<u8> = (0..=255).collect();
+        for len in [0, 1, 39, 40, 41, 79, 80, 200] {
+            let labels = encode_to_labels(&input[..len]);
+            assert!(labels.split('.').all(|l| l.len() <= MAX_LABEL_LEN));
+            assert_eq!(
+                labels.replace('.', ""),
+                crate::encode_to_string(&input[..len])
+            );
+            assert_eq!(decode_labels(&labels).unwrap(), &input[..len]);
+            let fqdn = format!("{}.", labels.to_ascii_lowercase());
+            assert_eq!(decode_labels(&fqdn).unwrap(), &input[..len]);
+        }
+        assert!(decode_labels("CSQP.U").is_err());
+    }
+
+    #[test]
+    fn test_txt_strings() {
+        let input: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        for len in [0, 1, 159, 160, 161, 319, 320, 1000] {
+            let strings = encode_to_txt_strings(&input[..len]);
+            assert!(strings.iter().all(|s| s.len() <= MAX_TXT_STRING_LEN));
+            assert_eq!(strings.concat(), crate::encode_to_string(&input[..len]));
+            assert_eq!(decode_txt_strings(&strings).unwrap(), &input[..len]);
+        }
+        assert_eq!(
+            decode_txt_strings([" CSQ ", "\tPYRK1E8\r\n"]).unwrap(),
+            b"foobar"
+        );
+        assert!(decode_txt_strings(["CSQP", "U"]).is_err());
+    }
+}
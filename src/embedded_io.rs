@@ -0,0 +1,245 @@
+//! Adapters decoding/encoding data streamed through the [`embedded_io`] traits.
+//!
+//! These work like the adapters in the `io` module of the `std` feature, but
+//! use small fixed size buffers and do not allocate.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::embedded_io::{DecodeReader, EncodeWriter};
+//! use embedded_io::{Read, Write};
+//!
+//! let mut encoded = [0; 32];
+//! let mut writer = EncodeWriter::new(&mut encoded[..]);
+//! writer.write_all(b"Hello, world!").unwrap();
+//! let rest = writer.finish().unwrap().len();
+//! let encoded = &encoded[..encoded.len() - rest];
+//! assert_eq!(encoded, b"91JPRV3F5GG7EVVJDHJ22");
+//!
+//! let mut decoded = [0; 13];
+//! DecodeReader::new(encoded).read_exact(&mut decoded).unwrap();
+//! assert_eq!(&decoded, b"Hello, world!");
+//! ```
+
+use core::fmt;
+
+use ::embedded_io::{ErrorKind, ErrorType, Read, Write};
+
+use crate::stream::{ReadBuffer, MAX_FINISH_LEN};
+use crate::{Decoder, Encoder, Error, Transcode};
+
+// The size of the buffers used by the adapters.
+pub(crate) const BUF_LEN: usize = 64;
+
+/// The error type of the adapters.
+#[derive(Debug)]
+pub enum StreamError<E> {
+    /// The inner reader or writer failed.
+    Io(E),
+    /// Decoding failed.
+    Base32(Error),
+}
+
+impl<E> From<Error> for StreamError<E> {
+    fn from(err: Error) -> Self {
+        StreamError::Base32(err)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for StreamError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(err) => err.fmt(f),
+            StreamError::Base32(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: core::error::Error> core::error::Error for StreamError<E> {}
+
+impl<E: ::embedded_io::Error> ::embedded_io::Error for StreamError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            StreamError::Io(err) => err.kind(),
+            StreamError::Base32(_) => ErrorKind::InvalidData,
+        }
+    }
+}
+
+/// A writer which encodes the written bytes and writes the result to the inner writer.
+pub type EncodeWriter<W> = Writer<Encoder, W>;
+
+/// A writer which decodes the written bytes and writes the result to the inner writer.
+pub type DecodeWriter<W> = Writer<Decoder, W>;
+
+/// A reader which reads bytes from the inner reader and returns the encoded result.
+pub type EncodeReader<R> = Reader<Encoder, R>;
+
+/// A reader which reads bytes from the inner reader and returns the decoded result.
+pub type DecodeReader<R> = Reader<Decoder, R>;
+
+/// A writer which transcodes the written bytes and writes the result to the inner writer.
+///
+/// Use the [`EncodeWriter`] and [`DecodeWriter`] aliases to create one.
+/// The last partial block is written by [`Writer::finish`], which must be called
+/// at the end.
+#[derive(Debug)]
+pub struct Writer<T, W> {
+    transcoder: T,
+    inner: W,
+}
+
+impl<T: Transcode + Default, W: Write> Writer<T, W> {
+    /// Creates a new writer which writes the result to `inner`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            transcoder: T::default(),
+            inner,
+        }
+    }
+}
+
+impl<T: Transcode, W: Write> Writer<T, W> {
+    /// Writes the result for the last partial block and returns the inner writer.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the last partial block is invalid or writing to the inner
+    /// writer fails.
+    pub fn finish(mut self) -> Result<W, StreamError<W::Error>> {
+        let mut output = [0; MAX_FINISH_LEN];
+        let len = self.transcoder.finish(&mut output)?;
+        self.inner
+            .write_all(output.get(..len).unwrap_or_default())
+            .map_err(StreamError::Io)?;
+        self.inner.flush().map_err(StreamError::Io)?;
+        Ok(self.inner)
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<T: Transcode, W: Write> ErrorType for Writer<T, W> {
+    type Error = StreamError<W::Error>;
+}
+
+impl<T: Transcode, W: Write> Write for Writer<T, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let mut output = [0; BUF_LEN];
+        let (consumed, written) = self.transcoder.update(buf, &mut output)?;
+        self.inner
+            .write_all(output.get(..written).unwrap_or_default())
+            .map_err(StreamError::Io)?;
+        Ok(consumed)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().map_err(StreamError::Io)
+    }
+}
+
+/// A reader which reads bytes from the inner reader and returns the transcoded result.
+///
+/// Use the [`EncodeReader`] and [`DecodeReader`] aliases to create one.
+#[derive(Debug)]
+pub struct Reader<T, R> {
+    transcoder: T,
+    inner: R,
+    buf: ReadBuffer<BUF_LEN>,
+}
+
+impl<T: Transcode + Default, R: Read> Reader<T, R> {
+    /// Creates a new reader which reads bytes from `inner`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            transcoder: T::default(),
+            inner,
+            buf: ReadBuffer::new(),
+        }
+    }
+}
+
+impl<T: Transcode, R: Read> Reader<T, R> {
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<T: Transcode, R: Read> ErrorType for Reader<T, R> {
+    type Error = StreamError<R::Error>;
+}
+
+impl<T: Transcode, R: Read> Read for Reader<T, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        loop {
+            if let Some(len) = self.buf.take_output(buf) {
+                return Ok(len);
+            }
+            let len = match self.buf.input_to_fill() {
+                Some(input) => Some(self.inner.read(input).map_err(StreamError::Io)?),
+                None => None,
+            };
+            self.buf.transcode(&mut self.transcoder, len)?;
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::encode_to_vec;
+
+    #[test]
+    fn test_round_trip() {
+        let plain: Vec<u8> = (0..500).map(|i| (i * 7) as u8).collect();
+        let encoded = encode_to_vec(&plain);
+
+        let mut output = vec![0; encoded.len()];
+        let mut writer = EncodeWriter::new(&mut output[..]);
+        for chunk in plain.chunks(7) {
+            writer.write_all(chunk).unwrap();
+        }
+        assert!(writer.finish().unwrap().is_empty());
+        assert_eq!(output, encoded);
+
+        let mut output = vec![0; plain.len()];
+        let mut writer = DecodeWriter::new(&mut output[..]);
+        for chunk in encoded.chunks(11) {
+            writer.write_all(chunk).unwrap();
+        }
+        assert!(writer.finish().unwrap().is_empty());
+        assert_eq!(output, plain);
+
+        let mut output = vec![0; encoded.len()];
+        EncodeReader::new(&plain[..])
+            .read_exact(&mut output)
+            .unwrap();
+        assert_eq!(output, encoded);
+
+        let mut output = vec![0; plain.len()];
+        DecodeReader::new(&encoded[..])
+            .read_exact(&mut output)
+            .unwrap();
+        assert_eq!(output, plain);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut output = [0; 8];
+        let err = DecodeReader::new(&b"CSQPU"[..])
+            .read(&mut output)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            StreamError::Base32(Error::InvalidSymbol { byte: b'U', .. })
+        ));
+        assert_eq!(::embedded_io::Error::kind(&err), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,186 @@
+//! Adapters decoding/encoding data streamed through the [`embedded_io_async`] traits.
+//!
+//! These are the async counterparts of the adapters in the
+//! [`embedded_io`](crate::embedded_io) module and share its error type.
+
+use ::embedded_io_async::{ErrorType, Read, Write};
+
+use crate::embedded_io::{StreamError, BUF_LEN};
+use crate::stream::{ReadBuffer, MAX_FINISH_LEN};
+use crate::{Decoder, Encoder, Transcode};
+
+/// A writer which encodes the written bytes and writes the result to the inner writer.
+pub type EncodeWriter<W> = Writer<Encoder, W>;
+
+/// A writer which decodes the written bytes and writes the result to the inner writer.
+pub type DecodeWriter<W> = Writer<Decoder, W>;
+
+/// A reader which reads bytes from the inner reader and returns the encoded result.
+pub type EncodeReader<R> = Reader<Encoder, R>;
+
+/// A reader which reads bytes from the inner reader and returns the decoded result.
+pub type DecodeReader<R> = Reader<Decoder, R>;
+
+/// A writer which transcodes the written bytes and writes the result to the inner writer.
+///
+/// Use the [`EncodeWriter`] and [`DecodeWriter`] aliases to create one.
+/// The last partial block is written by [`Writer::finish`], which must be called
+/// at the end.
+#[derive(Debug)]
+pub struct Writer<T, W> {
+    transcoder: T,
+    inner: W,
+}
+
+impl<T: Transcode + Default, W: Write> Writer<T, W> {
+    /// Creates a new writer which writes the result to `inner`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            transcoder: T::default(),
+            inner,
+        }
+    }
+}
+
+impl<T: Transcode, W: Write> Writer<T, W> {
+    /// Writes the result for the last partial block and returns the inner writer.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the last partial block is invalid or writing to the inner
+    /// writer fails.
+    pub async fn finish(mut self) -> Result<W, StreamError<W::Error>> {
+        let mut output = [0; MAX_FINISH_LEN];
+        let len = self.transcoder.finish(&mut output)?;
+        self.inner
+            .write_all(output.get(..len).unwrap_or_default())
+            .await
+            .map_err(StreamError::Io)?;
+        self.inner.flush().await.map_err(StreamError::Io)?;
+        Ok(self.inner)
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<T: Transcode, W: Write> ErrorType for Writer<T, W> {
+    type Error = StreamError<W::Error>;
+}
+
+impl<T: Transcode, W: Write> Write for Writer<T, W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let mut output = [0; BUF_LEN];
+        let (consumed, written) = self.transcoder.update(buf, &mut output)?;
+        self.inner
+            .write_all(output.get(..written).unwrap_or_default())
+            .await
+            .map_err(StreamError::Io)?;
+        Ok(consumed)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await.map_err(StreamError::Io)
+    }
+}
+
+/// A reader which reads bytes from the inner reader and returns the transcoded result.
+///
+/// Use the [`EncodeReader`] and [`DecodeReader`] aliases to create one.
+#[derive(Debug)]
+pub struct Reader<T, R> {
+    transcoder: T,
+    inner: R,
+    buf: ReadBuffer<BUF_LEN>,
+}
+
+impl<T: Transcode + Default, R: Read> Reader<T, R> {
+    /// Creates a new reader which reads bytes from `inner`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            transcoder: T::default(),
+            inner,
+            buf: ReadBuffer::new(),
+        }
+    }
+}
+
+impl<T: Transcode, R: Read> Reader<T, R> {
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<T: Transcode, R: Read> ErrorType for Reader<T, R> {
+    type Error = StreamError<R::Error>;
+}
+
+impl<T: Transcode, R: Read> Read for Reader<T, R> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        loop {
+            if let Some(len) = self.buf.take_output(buf) {
+                return Ok(len);
+            }
+            let len = match self.buf.input_to_fill() {
+                Some(input) => Some(self.inner.read(input).await.map_err(StreamError::Io)?),
+                None => None,
+            };
+            self.buf.transcode(&mut self.transcoder, len)?;
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::encode_to_vec;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    // The adapters over slices never wait, so polling once is enough.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        match pin!(future).poll(&mut cx) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future is pending"),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let plain: Vec<u8> = (0..500).map(|i| (i * 7) as u8).collect();
+        let encoded = encode_to_vec(&plain);
+
+        block_on(async {
+            let mut output = vec![0; encoded.len()];
+            let mut writer = EncodeWriter::new(&mut output[..]);
+            for chunk in plain.chunks(7) {
+                writer.write_all(chunk).await.unwrap();
+            }
+            assert!(writer.finish().await.unwrap().is_empty());
+            assert_eq!(output, encoded);
+
+            let mut output = vec![0; plain.len()];
+            let mut reader = DecodeReader::new(&encoded[..]);
+            reader.read_exact(&mut output).await.unwrap();
+            assert_eq!(output, plain);
+        });
+    }
+}
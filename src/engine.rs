@@ -0,0 +1,520 @@
+//! Engines encoding with custom alphabets.
+//!
+//! An [`Engine`] holds 32 symbols and the options for encoding and decoding
+//! with them. Its constructor and options are `const fn`s, so an engine is
+//! built at compile time and stored in a `static`. The [`new_engine!`] macro
+//! does this and turns an invalid alphabet into a compile error.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "alloc")]
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! clockwork_base32::new_engine! {
+//!     /// The RFC 4648 "base32hex" alphabet with padding.
+//!     static BASE32HEX = b"0123456789ABCDEFGHIJKLMNOPQRSTUV", padding, case_insensitive;
+//! }
+//!
+//! assert_eq!(BASE32HEX.encode_to_string(b"foobar"), "CPNMUOJ1E8======");
+//! assert_eq!(BASE32HEX.decode_to_vec(b"cpnmuoj1e8======")?, b"foobar");
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "alloc"))]
+//! # fn main() {}
+//! ```
+//!
+//! An alphabet with a repeated symbol does not compile:
+//! ```compile_fail
+//! clockwork_base32::new_engine! {
+//!     static INVALID = b"0023456789ABCDEFGHIJKLMNOPQRSTUV";
+//! }
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    capacity_hint_for_decode, capacity_hint_for_encode, copy_prefix, Error, Result, BLOCK_BYTE_LEN,
+    BLOCK_SYMBOL_LEN, BYTE_BIT_LEN, DECODED_BIT_LEN,
+};
+
+/// The padding symbol, which is never a symbol of an alphabet.
+const PADDING: u8 = b'=';
+
+/// How decoding treats trailing `=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodePadding {
+    /// Any number of trailing `=` are ignored without checking.
+    #[default]
+    Ignore,
+    /// Both padded and unpadded input are accepted, but if there are trailing
+    /// `=`, the input must be padded to a multiple of 8 symbols with the number
+    /// of `=` encoding gives for its length.
+    Either,
+}
+
+/// An alphabet of 32 symbols with options for encoding and decoding.
+///
+/// Bytes are encoded with the most significant bits first like the other
+/// functions of this crate, and the last symbol is padded with zero bits.
+/// Decoding drops trailing bits which do not form a whole byte.
+#[derive(Clone, Copy)]
+pub struct Engine {
+    symbols: [u8; 32],
+    // values has the value of each byte as a symbol, or -1 if it is invalid.
+    values: [i8; 256],
+    padding: bool,
+    decode_padding: DecodePadding,
+}
+
+// The const fns index arrays directly since `get` is not available in const
+// contexts. The indices are `u8` values or in the range of the loops.
+#[allow(clippy::indexing_slicing)]
+impl Engine {
+    /// Creates an engine with the symbols in the order of their values.
+    ///
+    /// Returns [`None`] unless the symbols are 32 distinct printable ASCII bytes
+    /// other than `=`.
+    pub const fn new(symbols: &[u8; 32]) -> Option<Self> {
+        let mut values = [-1; 256];
+        let mut i = 0;
+        while i < symbols.len() {
+            let b = symbols[i];
+            if !b.is_ascii_graphic() || b == PADDING || values[b as usize] >= 0 {
+                return None;
+            }
+            values[b as usize] = i as i8;
+            i += 1;
+        }
+        Some(Self {
+            symbols: *symbols,
+            values,
+            padding: false,
+            decode_padding: DecodePadding::Ignore,
+        })
+    }
+
+    /// Sets whether encoding pads the result with `=` to a multiple of 8
+    /// symbols. Decoding accepts trailing `=` either way, as set by
+    /// [`with_decode_padding`](Self::with_decode_padding).
+    pub const fn with_padding(mut self, padding: bool) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets how decoding treats trailing `=`, which is
+    /// [`DecodePadding::Ignore`] by default.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "alloc")]
+    /// # {
+    /// use clockwork_base32::engine::DecodePadding;
+    /// clockwork_base32::new_engine! {
+    ///     static ENGINE = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ", decode_padding(DecodePadding::Either);
+    /// }
+    /// assert_eq!(ENGINE.decode_to_vec(b"CSQPY===").unwrap(), b"foo");
+    /// assert_eq!(ENGINE.decode_to_vec(b"CSQPY").unwrap(), b"foo");
+    /// assert!(ENGINE.decode_to_vec(b"CSQPY=").is_err());
+    /// # }
+    /// ```
+    pub const fn with_decode_padding(mut self, decode_padding: DecodePadding) -> Self {
+        self.decode_padding = decode_padding;
+        self
+    }
+
+    /// Makes decoding accept letters in the other case of the symbols and the
+    /// aliases added so far, unless that case is already accepted.
+    pub const fn with_case_insensitive(mut self) -> Self {
+        let mut b = 0;
+        while b < self.values.len() {
+            let value = self.values[b];
+            let other = if (b as u8).is_ascii_uppercase() {
+                (b as u8).to_ascii_lowercase()
+            } else {
+                (b as u8).to_ascii_uppercase()
+            };
+            if value >= 0 && self.values[other as usize] < 0 {
+                self.values[other as usize] = value;
+            }
+            b += 1;
+        }
+        self
+    }
+
+    /// Makes decoding accept `alias` as `symbol`, like `I` for `1` in Clockwork
+    /// Base32.
+    ///
+    /// Returns [`None`] if `symbol` is not a symbol, or if `alias` is already
+    /// accepted or is not a printable ASCII byte other than `=`.
+    pub const fn with_alias(mut self, alias: u8, symbol: u8) -> Option<Self> {
+        let value = self.values[symbol as usize];
+        if value < 0
+            || self.symbols[value as usize] != symbol
+            || !alias.is_ascii_graphic()
+            || alias == PADDING
+            || self.values[alias as usize] >= 0
+        {
+            return None;
+        }
+        self.values[alias as usize] = value;
+        Some(self)
+    }
+}
+
+impl Engine {
+    /// Returns the symbols in the order of their values.
+    pub fn symbols(&self) -> &[u8; 32] {
+        &self.symbols
+    }
+
+    /// Returns whether encoding pads the result with `=`.
+    pub fn padding(&self) -> bool {
+        self.padding
+    }
+
+    /// Returns the encoded length of `input_byte_len` bytes, including padding.
+    ///
+    /// Like [`capacity_hint_for_encode`], this returns [`usize::MAX`] if the
+    /// length doesn't fit in [`usize`].
+    pub fn encoded_len(&self, input_byte_len: usize) -> usize {
+        if self.padding {
+            input_byte_len
+                .div_ceil(BLOCK_BYTE_LEN)
+                .saturating_mul(BLOCK_SYMBOL_LEN)
+        } else {
+            capacity_hint_for_encode(input_byte_len)
+        }
+    }
+
+    /// Returns the maximum decoded length of `input_byte_len` symbols.
+    pub fn decoded_len(&self, input_byte_len: usize) -> usize {
+        capacity_hint_for_decode(input_byte_len)
+    }
+
+    /// Encodes bytes into `dest` and returns the number of bytes written.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if `dest` is shorter than [`encoded_len`](Self::encoded_len).
+    pub fn encode_to_slice(&self, input: &[u8], dest: &mut [u8]) -> Result<usize> {
+        let len = self.encoded_len(input.len());
+        let dest = dest.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        let mut outputs = dest.chunks_mut(BLOCK_SYMBOL_LEN);
+        for (chunk, out) in input.chunks(BLOCK_BYTE_LEN).zip(outputs.by_ref()) {
+            let mut block = [0; BLOCK_BYTE_LEN];
+            copy_prefix(&mut block, chunk);
+            let bits = block
+                .iter()
+                .fold(0u64, |acc, &b| (acc << BYTE_BIT_LEN) | u64::from(b));
+            let symbol_len = capacity_hint_for_encode(chunk.len());
+            for (i, o) in out.iter_mut().enumerate() {
+                let shift = (BLOCK_SYMBOL_LEN - 1 - i) * DECODED_BIT_LEN;
+                *o = if i < symbol_len {
+                    self.encode_symbol((bits >> shift) as u8)
+                } else {
+                    PADDING
+                };
+            }
+        }
+        Ok(len)
+    }
+
+    /// Decodes symbols into `dest` and returns the number of bytes written.
+    ///
+    /// Trailing `=` are accepted as set by
+    /// [`with_decode_padding`](Self::with_decode_padding).
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input contains an invalid symbol or padding, or if
+    /// `dest` is shorter than [`decoded_len`](Self::decoded_len) of the input
+    /// without padding.
+    pub fn decode_to_slice(&self, input: &[u8], dest: &mut [u8]) -> Result<usize> {
+        let input = self.strip_padding(input)?;
+        let len = capacity_hint_for_decode(input.len());
+        let dest = dest.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        // A last chunk of a symbol decodes to no bytes, but is still checked.
+        let outputs = dest
+            .chunks_mut(BLOCK_BYTE_LEN)
+            .chain(core::iter::repeat_with(|| &mut [][..]));
+        let chunks = input.chunks(BLOCK_SYMBOL_LEN).enumerate();
+        for ((n, chunk), out) in chunks.zip(outputs) {
+            let mut bits = 0u64;
+            for i in 0..BLOCK_SYMBOL_LEN {
+                let v = match chunk.get(i) {
+                    Some(&byte) => {
+                        let position = n * BLOCK_SYMBOL_LEN + i;
+                        let err = Error::InvalidSymbol { byte, position };
+                        self.decode_symbol(byte).ok_or(err)?
+                    }
+                    None => 0,
+                };
+                bits = (bits << DECODED_BIT_LEN) | u64::from(v);
+            }
+            for (i, o) in out.iter_mut().enumerate() {
+                *o = (bits >> ((BLOCK_BYTE_LEN - 1 - i) * BYTE_BIT_LEN)) as u8;
+            }
+        }
+        Ok(len)
+    }
+
+    /// Encodes bytes to a new [`String`].
+    #[cfg(feature = "alloc")]
+    pub fn encode_to_string(&self, input: &[u8]) -> String {
+        let mut dest = alloc::vec![0; self.encoded_len(input.len())];
+        // The destination has the needed length, so this never fails.
+        self.encode_to_slice(input, &mut dest).unwrap_or_default();
+        // The symbols are ASCII, so this never fails.
+        String::from_utf8(dest).unwrap_or_default()
+    }
+
+    /// Decodes symbols to a new [`Vec<u8>`].
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input contains an invalid symbol.
+    #[cfg(feature = "alloc")]
+    pub fn decode_to_vec(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let mut dest = alloc::vec![0; self.decoded_len(input.len())];
+        let len = self.decode_to_slice(input, &mut dest)?;
+        dest.truncate(len);
+        Ok(dest)
+    }
+
+    /// Returns whether `b` is accepted as a symbol when decoding.
+    pub fn is_valid_symbol(&self, b: u8) -> bool {
+        self.decode_symbol(b).is_some()
+    }
+
+    // encode_symbol returns the symbol for the lower 5 bits of `v`.
+    fn encode_symbol(&self, v: u8) -> u8 {
+        // The index is always in range, so the fallback is never used.
+        self.symbols
+            .get(usize::from(v & 0x1f))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    // decode_symbol returns the value of the symbol `b`, or None if it is invalid.
+    fn decode_symbol(&self, b: u8) -> Option<u8> {
+        let v = self.values.get(usize::from(b)).copied().unwrap_or(-1);
+        u8::try_from(v).ok()
+    }
+
+    // strip_padding returns the input without trailing `=`, or Err if the
+    // padding is invalid for the decode padding option.
+    fn strip_padding<'a>(&self, input: &'a [u8]) -> Result<&'a [u8]> {
+        let padding_len = input.iter().rev().take_while(|&&b| b == PADDING).count();
+        let len = input.len() - padding_len;
+        if self.decode_padding == DecodePadding::Either && padding_len > 0 {
+            let expected_len = len.div_ceil(BLOCK_SYMBOL_LEN) * BLOCK_SYMBOL_LEN;
+            // Whole blocks and lengths not produced by encoding are never padded.
+            let encodable = capacity_hint_for_encode(capacity_hint_for_decode(len)) == len;
+            if !encodable || len + padding_len != expected_len {
+                return Err(Error::InvalidSymbol {
+                    byte: PADDING,
+                    position: len,
+                });
+            }
+        }
+        Ok(input.get(..len).unwrap_or_default())
+    }
+}
+
+impl fmt::Debug for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Engine")
+            .field(
+                "symbols",
+                &core::str::from_utf8(&self.symbols).unwrap_or_default(),
+            )
+            .field("padding", &self.padding)
+            .field("decode_padding", &self.decode_padding)
+            .finish()
+    }
+}
+
+/// Defines a `static` [`Engine`] from an alphabet and options, failing to
+/// compile if the alphabet or an option is invalid.
+///
+/// The options are `padding`, `decode_padding(MODE)`, `case_insensitive` and
+/// `alias(ALIAS => SYMBOL)`,
+/// which call the `with_` methods of [`Engine`] in order. Put `case_insensitive`
+/// after the aliases to accept them in both cases.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// clockwork_base32::new_engine! {
+///     pub static CROCKFORD = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+///         alias(b'O' => b'0'), alias(b'I' => b'1'), alias(b'L' => b'1'), case_insensitive;
+/// }
+///
+/// assert_eq!(CROCKFORD.encode_to_string(b"foobar"), "CSQPYRK1E8");
+/// assert_eq!(CROCKFORD.decode_to_vec(b"csqpyrkle8")?, b"foobar");
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "alloc"))]
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! new_engine {
+    (@options $engine:expr;) => {
+        $engine
+    };
+    (@options $engine:expr; padding $(, $($rest:tt)*)?) => {
+        $crate::new_engine!(@options $engine.with_padding(true); $($($rest)*)?)
+    };
+    (@options $engine:expr; decode_padding($mode:expr) $(, $($rest:tt)*)?) => {
+        $crate::new_engine!(@options $engine.with_decode_padding($mode); $($($rest)*)?)
+    };
+    (@options $engine:expr; case_insensitive $(, $($rest:tt)*)?) => {
+        $crate::new_engine!(@options $engine.with_case_insensitive(); $($($rest)*)?)
+    };
+    (@options $engine:expr; alias($alias:expr => $symbol:expr) $(, $($rest:tt)*)?) => {
+        $crate::new_engine!(@options match $engine.with_alias($alias, $symbol) {
+            ::core::option::Option::Some(engine) => engine,
+            ::core::option::Option::None => ::core::panic!(::core::concat!(
+                "invalid alias ",
+                ::core::stringify!($alias),
+            )),
+        }; $($($rest)*)?)
+    };
+    ($(#[$attr:meta])* $vis:vis static $name:ident = $symbols:expr
+        $(, $option:ident $(($($args:tt)*))?)* $(,)?;) => {
+        $(#[$attr])*
+        $vis static $name: $crate::engine::Engine = $crate::new_engine!(@options
+            match $crate::engine::Engine::new($symbols) {
+                ::core::option::Option::Some(engine) => engine,
+                ::core::option::Option::None => ::core::panic!(
+                    "an alphabet must have 32 distinct printable ASCII symbols other than '='"
+                ),
+            }; $($option $(($($args)*))?),*);
+    };
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    crate::new_engine! {
+        static CROCKFORD = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+            alias(b'O' => b'0'), alias(b'I' => b'1'), alias(b'L' => b'1'), case_insensitive;
+    }
+
+    #[test]
+    fn test_same_as_crate() {
+        let input: Vec<u8> = (0..=255).rev().collect();
+        for len in 0..40 {
+            let encoded = CROCKFORD.encode_to_string(&input[..len]);
+            assert_eq!(encoded, crate::encode_to_string(&input[..len]));
+            assert_eq!(
+                CROCKFORD.decode_to_vec(encoded.as_bytes()).unwrap(),
+                &input[..len]
+            );
+        }
+        for b in 0..=255 {
+            assert_eq!(
+                CROCKFORD.is_valid_symbol(b),
+                crate::is_valid_symbol(b),
+                "{}",
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn test_padding() {
+        let engine = CROCKFORD.with_padding(true);
+        for (input, expected) in [
+            ("", ""),
+            ("f", "CR======"),
+            ("fo", "CSQG===="),
+            ("foo", "CSQPY==="),
+            ("foob", "CSQPYRG="),
+            ("fooba", "CSQPYRK1"),
+        ] {
+            assert_eq!(engine.encode_to_string(input.as_bytes()), expected);
+            assert_eq!(engine.encoded_len(input.len()), expected.len());
+            assert_eq!(
+                engine.decode_to_vec(expected.as_bytes()).unwrap(),
+                input.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_padding() {
+        let engine = CROCKFORD.with_decode_padding(DecodePadding::Either);
+        for (input, expected) in [
+            ("CR======", "f"),
+            ("CR", "f"),
+            ("CSQPYRG=", "foob"),
+            ("CSQPYRG", "foob"),
+            ("CSQPYRK1", "fooba"),
+            ("CSQPYRK1CR======", "foobaf"),
+            ("", ""),
+        ] {
+            assert_eq!(
+                engine.decode_to_vec(input.as_bytes()).unwrap(),
+                expected.as_bytes(),
+                "{}",
+                input
+            );
+        }
+        for input in [
+            "CR=",
+            "CR=======",
+            "CSQPYRK1========",
+            "CSQ=====",
+            "========",
+        ] {
+            let err = engine.decode_to_vec(input.as_bytes()).unwrap_err();
+            assert!(
+                matches!(err, Error::InvalidSymbol { byte: b'=', .. }),
+                "{}",
+                input
+            );
+            assert!(
+                CROCKFORD.decode_to_vec(input.as_bytes()).is_ok(),
+                "{}",
+                input
+            );
+        }
+        assert!(matches!(
+            engine.decode_to_vec(b"CR==CR=="),
+            Err(Error::InvalidSymbol { byte: b'=', .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut symbols = *CROCKFORD.symbols();
+        assert!(Engine::new(&symbols).is_some());
+        symbols[0] = b'1';
+        assert!(Engine::new(&symbols).is_none());
+        symbols[0] = b'=';
+        assert!(Engine::new(&symbols).is_none());
+        symbols[0] = b' ';
+        assert!(Engine::new(&symbols).is_none());
+
+        assert!(CROCKFORD.with_alias(b'U', b'V').is_some());
+        assert!(CROCKFORD.with_alias(b'I', b'1').is_none());
+        assert!(CROCKFORD.with_alias(b'u', b'U').is_none());
+        assert!(CROCKFORD.with_alias(b'=', b'0').is_none());
+
+        for input in ["CSQPU", "CSQPYRK1U"] {
+            let err = CROCKFORD.decode_to_vec(input.as_bytes()).unwrap_err();
+            assert!(
+                matches!(err, Error::InvalidSymbol { byte: b'U', .. }),
+                "{}",
+                input
+            );
+        }
+        assert!(matches!(
+            CROCKFORD.encode_to_slice(b"foo", &mut [0; 4]),
+            Err(Error::BufferTooSmall)
+        ));
+    }
+}
@@ -0,0 +1,470 @@
+use core::fmt;
+
+/// The error type for decoding.
+///
+/// Errors hold only their kind, the offending byte and its position, or the
+/// lengths; [`Display`](fmt::Display) formats the message on demand. So creating
+/// and formatting them never allocates. They work without the `alloc` feature
+/// and cost nothing more than a return value in hot validation loops.
+///
+/// With the `std` feature enabled, this can be converted into a [`std::io::Error`],
+/// so the `?` operator works in functions returning [`std::io::Result`].
+///
+/// With the `defmt` feature enabled, this implements `defmt::Format` so decode
+/// failures can be logged from embedded targets.
+///
+/// Errors can be compared, so tests and retry logic can match on the
+/// [`kind`](Self::kind), the position and the offending byte instead of the
+/// [`Display`](fmt::Display) output.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # {
+/// use clockwork_base32 as base32;
+/// use base32::{Error, ErrorKind};
+///
+/// let err = base32::decode_to_vec(b"CSQPU").unwrap_err();
+/// assert_eq!(err, Error::InvalidSymbol { byte: b'U', position: 4 });
+/// assert_eq!(err.kind(), ErrorKind::InvalidSymbol);
+/// assert_eq!(err.position(), Some(4));
+/// assert_eq!(err.byte(), Some(b'U'));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum Error {
+    /// The input contains a byte which is not a valid symbol.
+    InvalidSymbol {
+        /// The offending byte.
+        byte: u8,
+        /// The byte position of the offending byte in the input.
+        position: usize,
+    },
+    /// The decoded bytes are not valid UTF-8.
+    InvalidUtf8,
+    /// The destination buffer is too small for the result.
+    BufferTooSmall,
+    /// The input is not the canonical encoding of any bytes: it contains lowercase or
+    /// aliased symbols, its length cannot be produced by encoding, or the unused bits
+    /// of the last symbol are not zero.
+    NonCanonical,
+    /// The decoded length does not match the expected length.
+    LengthMismatch {
+        /// The expected length in bytes.
+        expected: usize,
+        /// The decoded length in bytes.
+        actual: usize,
+    },
+    /// The input contains invalid symbols, reported by [`ct`](crate::ct)
+    /// functions without telling which ones so their values do not leak.
+    InvalidSymbols,
+    /// A streaming adapter read or wrote more bytes than its limit.
+    LimitExceeded {
+        /// The limit in bytes.
+        limit: usize,
+    },
+    /// The decoded value is out of the range of the target type, like zero for
+    /// a `NonZero` integer.
+    OutOfRange,
+}
+
+/// The kind of an [`Error`], without its details.
+///
+/// Each variant matches the [`Error`] variant of the same name, so the kind can
+/// be compared or used as a map key where the position or the byte do not
+/// matter, like in retry logic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// See [`Error::InvalidSymbol`].
+    InvalidSymbol,
+    /// See [`Error::InvalidUtf8`].
+    InvalidUtf8,
+    /// See [`Error::BufferTooSmall`].
+    BufferTooSmall,
+    /// See [`Error::NonCanonical`].
+    NonCanonical,
+    /// See [`Error::LengthMismatch`].
+    LengthMismatch,
+    /// See [`Error::InvalidSymbols`].
+    InvalidSymbols,
+    /// See [`Error::LimitExceeded`].
+    LimitExceeded,
+    /// See [`Error::OutOfRange`].
+    OutOfRange,
+}
+
+impl Error {
+    /// Returns the kind of the error.
+    pub const fn kind(&self) -> ErrorKind {
+        match self {
+            Error::InvalidSymbol { .. } => ErrorKind::InvalidSymbol,
+            Error::InvalidUtf8 => ErrorKind::InvalidUtf8,
+            Error::BufferTooSmall => ErrorKind::BufferTooSmall,
+            Error::NonCanonical => ErrorKind::NonCanonical,
+            Error::LengthMismatch { .. } => ErrorKind::LengthMismatch,
+            Error::InvalidSymbols => ErrorKind::InvalidSymbols,
+            Error::LimitExceeded { .. } => ErrorKind::LimitExceeded,
+            Error::OutOfRange => ErrorKind::OutOfRange,
+        }
+    }
+
+    /// Returns a stable numeric code of the error kind.
+    ///
+    /// The codes never change between versions, so they can be stored or passed
+    /// to other languages, like the C API does. New kinds get new codes.
+    ///
+    /// | Error              | Code |
+    /// |--------------------|------|
+    /// | `InvalidSymbol`    | 1    |
+    /// | `InvalidUtf8`      | 2    |
+    /// | `BufferTooSmall`   | 3    |
+    /// | `NonCanonical`     | 4    |
+    /// | `LengthMismatch`   | 5    |
+    /// | `InvalidSymbols`   | 6    |
+    /// | `LimitExceeded`    | 7    |
+    /// | `OutOfRange`       | 8    |
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "alloc")]
+    /// # {
+    /// use clockwork_base32 as base32;
+    /// let err = base32::decode_to_vec(b"CSQPU").unwrap_err();
+    /// assert_eq!(err.code(), 1);
+    /// # }
+    /// ```
+    pub const fn code(&self) -> i32 {
+        match self {
+            Error::InvalidSymbol { .. } => 1,
+            Error::InvalidUtf8 => 2,
+            Error::BufferTooSmall => 3,
+            Error::NonCanonical => 4,
+            Error::LengthMismatch { .. } => 5,
+            Error::InvalidSymbols => 6,
+            Error::LimitExceeded { .. } => 7,
+            Error::OutOfRange => 8,
+        }
+    }
+
+    /// Returns the offending byte of an [`Error::InvalidSymbol`], or [`None`]
+    /// for the other kinds.
+    pub const fn byte(&self) -> Option<u8> {
+        match self {
+            Error::InvalidSymbol { byte, .. } => Some(*byte),
+            _ => None,
+        }
+    }
+
+    /// Returns the byte position of the offending byte of an
+    /// [`Error::InvalidSymbol`], or [`None`] for the other kinds.
+    pub const fn position(&self) -> Option<usize> {
+        match self {
+            Error::InvalidSymbol { position, .. } => Some(*position),
+            _ => None,
+        }
+    }
+
+    // offset_by returns the error with `offset` added to its position, for an
+    // error found in a chunk starting at `offset` in the whole input.
+    pub(crate) fn offset_by(self, offset: usize) -> Self {
+        match self {
+            Error::InvalidSymbol { byte, position } => Error::InvalidSymbol {
+                byte,
+                position: position.saturating_add(offset),
+            },
+            err => err,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidSymbol { byte, .. } => {
+                write!(f, "invalid symbol value {}", *byte as char)
+            }
+            Error::InvalidUtf8 => f.write_str("decoded bytes are not valid UTF-8"),
+            Error::BufferTooSmall => f.write_str("destination buffer is too small"),
+            Error::NonCanonical => f.write_str("input is not a canonical encoding"),
+            Error::InvalidSymbols => f.write_str("input contains invalid symbols"),
+            Error::LimitExceeded { limit } => write!(f, "limit of {} bytes exceeded", limit),
+            Error::OutOfRange => f.write_str("decoded value is out of range"),
+            Error::LengthMismatch { expected, actual } => write!(
+                f,
+                "decoded length {} does not match the expected length {}",
+                actual, expected
+            ),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    fn from(err: Error) -> Self {
+        let kind = match err {
+            Error::InvalidSymbol { .. }
+            | Error::BufferTooSmall
+            | Error::NonCanonical
+            | Error::LengthMismatch { .. }
+            | Error::InvalidSymbols
+            | Error::LimitExceeded { .. } => std::io::ErrorKind::InvalidInput,
+            Error::InvalidUtf8 | Error::OutOfRange => std::io::ErrorKind::InvalidData,
+        };
+        std::io::Error::new(kind, err)
+    }
+}
+
+/// The error type for parsing [`Base32String`](crate::Base32String) and
+/// [`Base32Bytes`](crate::Base32Bytes) from strings.
+///
+/// In addition to the [`Error`], this holds the position of the problem and its
+/// [`Display`](fmt::Display) output suggests how to fix the input, so it can be
+/// shown to users as is, for example as a command line argument error. Like
+/// [`Error`], it never allocates: the message is formatted from the kind, the
+/// position and the offending byte on demand. With the `defmt` feature enabled,
+/// it implements `defmt::Format` like [`Error`].
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # {
+/// use clockwork_base32::{Base32String, Error};
+///
+/// let err = "CSQPU".parse::<Base32String>().unwrap_err();
+/// assert_eq!(err.error(), &Error::InvalidSymbol { byte: b'U', position: 4 });
+/// assert_eq!(err.position(), 4);
+/// assert_eq!(err.byte(), Some(b'U'));
+/// assert_eq!(
+///     err.to_string(),
+///     "invalid symbol 'U' at position 4, 'U' is excluded from the alphabet, did you mean 'V'?"
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ParseError {
+    error: Error,
+    position: usize,
+    expected: Option<u8>,
+}
+
+impl ParseError {
+    pub(crate) fn new(error: Error, position: usize, expected: Option<u8>) -> Self {
+        Self {
+            error,
+            position,
+            expected,
+        }
+    }
+
+    pub(crate) fn invalid_symbol(byte: u8, position: usize) -> Self {
+        Self::new(Error::InvalidSymbol { byte, position }, position, None)
+    }
+
+    /// Returns the underlying error, which tells the kind of the problem.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Returns the kind of the underlying error.
+    pub fn kind(&self) -> ErrorKind {
+        self.error.kind()
+    }
+
+    /// Returns the byte position of the problem in the input.
+    ///
+    /// For an invalid length, this is the length of the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the offending byte at [`position`](Self::position) for an
+    /// invalid symbol, or [`None`] for the other kinds.
+    pub fn byte(&self) -> Option<u8> {
+        self.error.byte()
+    }
+
+    /// Returns the canonical symbol expected at [`position`](Self::position)
+    /// for a non-canonical symbol, like `'A'` for `'a'`, or [`None`] otherwise.
+    pub fn expected(&self) -> Option<u8> {
+        self.expected
+    }
+
+    /// Returns an adapter displaying the line of `input` around the problem
+    /// with a caret below it, for showing the error in a terminal.
+    ///
+    /// `input` is the input which was parsed, so the position points into it.
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::CLOCKWORK;
+    ///
+    /// let input = "CSQ-PY";
+    /// let err = CLOCKWORK.validate(input).unwrap_err();
+    /// assert_eq!(
+    ///     format!("error: {}\n{}", err, err.snippet(input.as_bytes())),
+    ///     "error: invalid symbol '-' at position 3, remove hyphens\n    CSQ-PY\n       ^"
+    /// );
+    /// ```
+    pub fn snippet<'a>(&self, input: &'a [u8]) -> crate::Snippet<'a> {
+        crate::Snippet::new(input, self.position)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let position = self.position;
+        match (&self.error, self.expected) {
+            (Error::InvalidSymbol { byte: b, .. }, _) => {
+                write!(
+                    f,
+                    "invalid symbol {:?} at position {}",
+                    *b as char, position
+                )?;
+                match b {
+                    b'U' | b'u' => write!(
+                        f,
+                        ", {:?} is excluded from the alphabet, did you mean 'V'?",
+                        *b as char
+                    ),
+                    b'-' => f.write_str(", remove hyphens"),
+                    b'=' => f.write_str(", padding is not used, remove it"),
+                    b if b.is_ascii_whitespace() => f.write_str(", remove whitespace"),
+                    _ => Ok(()),
+                }
+            }
+            (Error::NonCanonical, Some(expected)) => write!(
+                f,
+                "non-canonical symbol at position {}, expected {:?}",
+                position, expected as char
+            ),
+            (Error::NonCanonical, None) => write!(
+                f,
+                "invalid length {}, no bytes are encoded to this length; \
+                 is a symbol missing or extra?",
+                position
+            ),
+            (Error::LengthMismatch { expected, actual }, _) => write!(
+                f,
+                "{} symbols decode to {} bytes, but {} bytes are expected",
+                position, actual, expected
+            ),
+            (error, _) => write!(f, "{} at position {}", error, position),
+        }
+    }
+}
+
+impl core::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        err.error
+    }
+}
+
+/// A specialized [`Result`](core::result::Result) type for decoding.
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    // A fixed-size buffer to format errors without `alloc`.
+    struct Buf {
+        bytes: [u8; 128],
+        len: usize,
+    }
+
+    impl Buf {
+        fn format(args: impl fmt::Display) -> Self {
+            let mut buf = Self {
+                bytes: [0; 128],
+                len: 0,
+            };
+            write!(buf, "{}", args).unwrap();
+            buf
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+        }
+    }
+
+    impl Write for Buf {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let dest = self
+                .bytes
+                .get_mut(self.len..self.len + s.len())
+                .ok_or(fmt::Error)?;
+            dest.copy_from_slice(s.as_bytes());
+            self.len += s.len();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_eq() {
+        let err = crate::CLOCKWORK.validate("CSQ-PY").unwrap_err();
+        assert_eq!(err.clone(), ParseError::invalid_symbol(b'-', 3));
+        assert_ne!(err, ParseError::invalid_symbol(b'-', 2));
+        assert_eq!(err.byte(), Some(b'-'));
+        assert_eq!(err.expected(), None);
+
+        let err = crate::CLOCKWORK.validate_canonical("CSQpY").unwrap_err();
+        assert_eq!(err.error(), &Error::NonCanonical);
+        assert_eq!(
+            (err.position(), err.byte(), err.expected()),
+            (3, None, Some(b'P'))
+        );
+        assert_ne!(
+            Error::LimitExceeded { limit: 1 },
+            Error::LimitExceeded { limit: 2 }
+        );
+        assert_eq!(Error::OutOfRange.byte(), None);
+    }
+
+    #[test]
+    fn test_kind_and_position() {
+        let err = crate::CLOCKWORK.validate("CSQ-PY").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidSymbol);
+        assert_eq!(err.error().position(), Some(3));
+
+        let err = crate::CLOCKWORK.validate_canonical("CSQpY").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NonCanonical);
+        assert_eq!(err.error().position(), None);
+
+        let err = Error::LengthMismatch {
+            expected: 5,
+            actual: 4,
+        };
+        assert_eq!(err.kind(), ErrorKind::LengthMismatch);
+        assert_eq!(err.position(), None);
+        assert_eq!(Error::OutOfRange.kind(), ErrorKind::OutOfRange);
+    }
+
+    #[test]
+    fn test_display_without_alloc() {
+        let err = crate::CLOCKWORK.validate("CSQ-PY").unwrap_err();
+        assert_eq!(
+            Buf::format(&err).as_str(),
+            "invalid symbol '-' at position 3, remove hyphens"
+        );
+        assert_eq!(Buf::format(err.error()).as_str(), "invalid symbol value -");
+        let err = ParseError::new(Error::InvalidUtf8, 2, None);
+        assert_eq!(
+            Buf::format(&err).as_str(),
+            "decoded bytes are not valid UTF-8 at position 2"
+        );
+    }
+}
@@ -0,0 +1,729 @@
+//! A C API for encoding and decoding buffers.
+//!
+//! The functions are exported with the `cwb32_` prefix and declared in
+//! `include/clockwork_base32.h`, which is generated from this module by
+//! [cbindgen](https://github.com/mozilla/cbindgen) with `cbindgen.toml`.
+//! A plain `cargo build` only builds the Rust library, so build a shared or
+//! static library for C with:
+//!
+//! ```sh
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! cargo rustc --release --features ffi --crate-type staticlib
+//! ```
+//!
+//! and link it like `interop/c/roundtrip.c` is in CI.
+//!
+//! # Ownership
+//! The caller owns every buffer, which only has to be valid during the call.
+//! Use [`cwb32_encoded_len`] and [`cwb32_decoded_len`] to size output buffers.
+//! A pointer may be `NULL` when its length is zero.
+//!
+//! The only memory allocated by the library is the state of the streaming
+//! encoder and decoder, which must be released with [`cwb32_encoder_free`] and
+//! [`cwb32_decoder_free`].
+//!
+//! # Errors
+//! The functions return `CWB32_OK` on success or a `CWB32_ERROR_*` code on
+//! failure. The codes other than [`CWB32_ERROR_NULL_POINTER`] are the stable
+//! codes of [`Error::code`](crate::Error::code). The details of the last failure
+//! on the calling thread are returned by [`cwb32_last_error_code`],
+//! [`cwb32_last_error_offset`] and [`cwb32_last_error_message`].
+//!
+//! # Examples
+//! ```c
+//! #include "clockwork_base32.h"
+//!
+//! const char *input = "Hello, world!";
+//! size_t input_len = strlen(input);
+//! char encoded[32];
+//! size_t encoded_len;
+//! if (cwb32_encode((const uint8_t *)input, input_len, (uint8_t *)encoded,
+//!                  sizeof(encoded), &encoded_len) != CWB32_OK) {
+//!     char message[256];
+//!     cwb32_last_error_message(message, sizeof(message));
+//!     fprintf(stderr, "error %d: %s\n", cwb32_last_error_code(), message);
+//! }
+//! ```
+//!
+//! Data can also be transcoded in chunks, like with [`Encoder`] and [`Decoder`]:
+//!
+//! ```c
+//! Cwb32Encoder *encoder = cwb32_encoder_new();
+//! uint8_t chunk[4096], output[1024];
+//! size_t consumed, written;
+//! ssize_t n;
+//! while ((n = read(fd, chunk, sizeof(chunk))) > 0) {
+//!     for (size_t pos = 0; pos < n; pos += consumed) {
+//!         cwb32_encoder_update(encoder, chunk + pos, n - pos, output, sizeof(output),
+//!                              &consumed, &written);
+//!         fwrite(output, 1, written, stdout);
+//!     }
+//! }
+//! cwb32_encoder_finish(encoder, output, sizeof(output), &written);
+//! fwrite(output, 1, written, stdout);
+//! cwb32_encoder_free(encoder);
+//! ```
+
+use alloc::boxed::Box;
+use core::cell::RefCell;
+use core::ffi::{c_char, c_int};
+use core::fmt::Write;
+use core::slice;
+
+use crate::{
+    capacity_hint_for_decode, capacity_hint_for_encode, decode_to_slice, encode_to_slice,
+    find_invalid_symbol, Decoder, Encoder, Error, ParseError, Transcode,
+};
+
+// The error codes are literals for cbindgen, and equal to Error::code.
+
+/// Returned by the functions on success.
+pub const CWB32_OK: c_int = 0;
+
+/// A pointer argument is `NULL` when it must not be.
+pub const CWB32_ERROR_NULL_POINTER: c_int = -1;
+
+/// The input contains a byte which is not a valid symbol.
+pub const CWB32_ERROR_INVALID_SYMBOL: c_int = 1;
+
+/// The decoded bytes are not valid UTF-8.
+pub const CWB32_ERROR_INVALID_UTF8: c_int = 2;
+
+/// The output buffer is too small for the result.
+pub const CWB32_ERROR_BUFFER_TOO_SMALL: c_int = 3;
+
+/// The input is not the canonical encoding of any bytes.
+pub const CWB32_ERROR_NON_CANONICAL: c_int = 4;
+
+/// The decoded length does not match the expected length.
+pub const CWB32_ERROR_LENGTH_MISMATCH: c_int = 5;
+
+/// The input contains invalid symbols, without telling which ones.
+pub const CWB32_ERROR_INVALID_SYMBOLS: c_int = 6;
+
+/// A streaming adapter read or wrote more bytes than its limit.
+pub const CWB32_ERROR_LIMIT_EXCEEDED: c_int = 7;
+
+/// The decoded value is out of the range of the target type.
+pub const CWB32_ERROR_OUT_OF_RANGE: c_int = 8;
+
+// FfiError is a failure of a function, kept as the last error of the thread.
+enum FfiError {
+    NullPointer,
+    Parse(ParseError),
+    Other(Error),
+}
+
+impl FfiError {
+    fn code(&self) -> c_int {
+        match self {
+            FfiError::NullPointer => CWB32_ERROR_NULL_POINTER,
+            FfiError::Parse(err) => err.error().code(),
+            FfiError::Other(err) => err.code(),
+        }
+    }
+
+    fn offset(&self) -> usize {
+        match self {
+            FfiError::Parse(err) => err.position(),
+            _ => 0,
+        }
+    }
+}
+
+impl From<Error> for FfiError {
+    fn from(err: Error) -> Self {
+        FfiError::Other(err)
+    }
+}
+
+impl core::fmt::Display for FfiError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FfiError::NullPointer => f.write_str("a pointer argument is NULL"),
+            FfiError::Parse(err) => err.fmt(f),
+            FfiError::Other(err) => err.fmt(f),
+        }
+    }
+}
+
+std::thread_local! {
+    static LAST_ERROR: RefCell<Option<FfiError>> = const { RefCell::new(None) };
+}
+
+// status keeps the error of `result` as the last error and returns its code.
+fn status(result: Result<(), FfiError>) -> c_int {
+    match result {
+        Ok(()) => CWB32_OK,
+        Err(err) => {
+            let code = err.code();
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(err));
+            code
+        }
+    }
+}
+
+/// Returns the code of the last failure on the calling thread, or `CWB32_OK`
+/// if no function has failed on it.
+#[no_mangle]
+pub extern "C" fn cwb32_last_error_code() -> c_int {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(CWB32_OK, FfiError::code))
+}
+
+/// Returns the byte offset of the problem in the input of the last failure on
+/// the calling thread.
+///
+/// For the streaming decoder, the offset counts from the start of the stream.
+/// Returns 0 if the failure has no position in the input.
+#[no_mangle]
+pub extern "C" fn cwb32_last_error_offset() -> usize {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(0, FfiError::offset))
+}
+
+/// Copies the message of the last failure on the calling thread to the
+/// `buf_cap` bytes buffer at `buf` as a NUL-terminated string, and returns the
+/// length of the whole message without the NUL.
+///
+/// The message is truncated if it does not fit, like `snprintf`. The message is
+/// empty if no function has failed on the thread. `buf` may be `NULL` if
+/// `buf_cap` is 0, to get the needed length.
+///
+/// # Safety
+/// `buf` must be valid for writes of `buf_cap` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cwb32_last_error_message(buf: *mut c_char, buf_cap: usize) -> usize {
+    let mut writer = MessageWriter {
+        buf: output_slice(buf.cast(), buf_cap).unwrap_or_default(),
+        len: 0,
+    };
+    LAST_ERROR.with(|last| {
+        if let Some(err) = &*last.borrow() {
+            write!(writer, "{}", err).unwrap_or_default();
+        }
+    });
+    let len = writer.len;
+    // Leave room for the terminating NUL.
+    let end = len.min(writer.buf.len().saturating_sub(1));
+    if let Some(b) = writer.buf.get_mut(end) {
+        *b = 0;
+    }
+    len
+}
+
+// MessageWriter writes as much of a message as fits in `buf` and counts the
+// length of the whole message.
+struct MessageWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl Write for MessageWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        // Keep the last byte for the terminating NUL.
+        let cap = self.buf.len().saturating_sub(1);
+        if let Some(dest) = self.buf.get_mut(self.len.min(cap)..cap) {
+            let n = dest.len().min(s.len());
+            dest.get_mut(..n)
+                .unwrap_or_default()
+                .copy_from_slice(s.as_bytes().get(..n).unwrap_or_default());
+        }
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+/// Returns the length of the encoded text of `input_len` bytes, or `SIZE_MAX`
+/// if it doesn't fit in `size_t`.
+#[no_mangle]
+pub extern "C" fn cwb32_encoded_len(input_len: usize) -> usize {
+    capacity_hint_for_encode(input_len)
+}
+
+/// Returns the length of the decoded bytes of `input_len` symbols.
+#[no_mangle]
+pub extern "C" fn cwb32_decoded_len(input_len: usize) -> usize {
+    capacity_hint_for_decode(input_len)
+}
+
+/// Encodes `input_len` bytes at `input` into the `output_cap` bytes buffer at
+/// `output`, and stores the length of the encoded text to `*output_len`.
+///
+/// The output is not NUL-terminated. Returns `CWB32_OK` on success,
+/// `CWB32_ERROR_BUFFER_TOO_SMALL` if `output_cap` is less than
+/// `cwb32_encoded_len(input_len)`, or `CWB32_ERROR_NULL_POINTER`.
+///
+/// # Safety
+/// `input` must be valid for reads of `input_len` bytes, `output` must be
+/// valid for writes of `output_cap` bytes, and `output_len` must be valid for
+/// a write. The buffers must not overlap.
+#[no_mangle]
+pub unsafe extern "C" fn cwb32_encode(
+    input: *const u8,
+    input_len: usize,
+    output: *mut u8,
+    output_cap: usize,
+    output_len: *mut usize,
+) -> c_int {
+    status((|| {
+        let input = input_slice(input, input_len)?;
+        let output = output_slice(output, output_cap)?;
+        let output_len = output_len.as_mut().ok_or(FfiError::NullPointer)?;
+        *output_len = encode_to_slice(input, output)?;
+        Ok(())
+    })())
+}
+
+/// Decodes `input_len` symbols at `input` into the `output_cap` bytes buffer at
+/// `output`, and stores the length of the decoded bytes to `*output_len`.
+///
+/// Lowercase symbols and the aliases `I`, `L` and `O` are accepted. Returns
+/// `CWB32_OK` on success, `CWB32_ERROR_INVALID_SYMBOL` if the input contains an
+/// invalid symbol, `CWB32_ERROR_BUFFER_TOO_SMALL` if `output_cap` is less than
+/// `cwb32_decoded_len(input_len)`, or `CWB32_ERROR_NULL_POINTER`.
+///
+/// # Safety
+/// `input` must be valid for reads of `input_len` bytes, `output` must be
+/// valid for writes of `output_cap` bytes, and `output_len` must be valid for
+/// a write. The buffers must not overlap.
+#[no_mangle]
+pub unsafe extern "C" fn cwb32_decode(
+    input: *const u8,
+    input_len: usize,
+    output: *mut u8,
+    output_cap: usize,
+    output_len: *mut usize,
+) -> c_int {
+    status((|| {
+        let input = input_slice(input, input_len)?;
+        let output = output_slice(output, output_cap)?;
+        let output_len = output_len.as_mut().ok_or(FfiError::NullPointer)?;
+        find_invalid_symbol(input).map_err(FfiError::Parse)?;
+        *output_len = decode_to_slice(input, output)?;
+        Ok(())
+    })())
+}
+
+/// The state of a streaming encoder, created by `cwb32_encoder_new`.
+pub struct Cwb32Encoder(Encoder);
+
+/// The state of a streaming decoder, created by `cwb32_decoder_new`.
+pub struct Cwb32Decoder {
+    decoder: Decoder,
+    // The number of symbols consumed so far, for the offsets of errors.
+    offset: usize,
+}
+
+/// Returns a new streaming encoder, which must be released with
+/// `cwb32_encoder_free`.
+#[no_mangle]
+pub extern "C" fn cwb32_encoder_new() -> *mut Cwb32Encoder {
+    Box::into_raw(Box::new(Cwb32Encoder(Encoder::new())))
+}
+
+/// Encodes `input_len` bytes at `input` into the `output_cap` bytes buffer at
+/// `output`, and stores the number of consumed input bytes to `*consumed` and
+/// the number of written output bytes to `*written`.
+///
+/// Bytes not forming a whole block of 5 bytes are kept in the encoder. Fewer
+/// bytes than `input_len` are consumed when `output` is full, so call this again
+/// with the rest of the input. Returns `CWB32_OK` on success, or
+/// `CWB32_ERROR_NULL_POINTER`.
+///
+/// # Safety
+/// `encoder` must be returned by `cwb32_encoder_new` and not be freed yet. The
+/// other pointers must be valid like for `cwb32_encode`.
+#[no_mangle]
+pub unsafe extern "C" fn cwb32_encoder_update(
+    encoder: *mut Cwb32Encoder,
+    input: *const u8,
+    input_len: usize,
+    output: *mut u8,
+    output_cap: usize,
+    consumed: *mut usize,
+    written: *mut usize,
+) -> c_int {
+    status((|| {
+        let encoder = encoder.as_mut().ok_or(FfiError::NullPointer)?;
+        let input = input_slice(input, input_len)?;
+        update(&mut encoder.0, input, output, output_cap, consumed, written)?;
+        Ok(())
+    })())
+}
+
+/// Encodes the bytes kept in the encoder into the `output_cap` bytes buffer at
+/// `output`, and stores the number of written bytes, which is at most 8, to
+/// `*written`.
+///
+/// Returns `CWB32_OK` on success, `CWB32_ERROR_BUFFER_TOO_SMALL` if
+/// `output_cap` is less than needed, or `CWB32_ERROR_NULL_POINTER`. The encoder
+/// can be used again after success.
+///
+/// # Safety
+/// The pointers must be valid like for `cwb32_encoder_update`.
+#[no_mangle]
+pub unsafe extern "C" fn cwb32_encoder_finish(
+    encoder: *mut Cwb32Encoder,
+    output: *mut u8,
+    output_cap: usize,
+    written: *mut usize,
+) -> c_int {
+    status((|| {
+        let encoder = encoder.as_mut().ok_or(FfiError::NullPointer)?;
+        finish(&mut encoder.0, output, output_cap, written)
+    })())
+}
+
+/// Releases an encoder. Does nothing if `encoder` is `NULL`.
+///
+/// # Safety
+/// `encoder` must be `NULL` or returned by `cwb32_encoder_new` and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn cwb32_encoder_free(encoder: *mut Cwb32Encoder) {
+    if !encoder.is_null() {
+        drop(Box::from_raw(encoder));
+    }
+}
+
+/// Returns a new streaming decoder, which must be released with
+/// `cwb32_decoder_free`.
+#[no_mangle]
+pub extern "C" fn cwb32_decoder_new() -> *mut Cwb32Decoder {
+    Box::into_raw(Box::new(Cwb32Decoder {
+        decoder: Decoder::new(),
+        offset: 0,
+    }))
+}
+
+/// Decodes `input_len` symbols at `input` into the `output_cap` bytes buffer at
+/// `output`, and stores the number of consumed input bytes to `*consumed` and
+/// the number of written output bytes to `*written`.
+///
+/// Symbols not forming a whole block of 8 symbols are kept in the decoder.
+/// Fewer symbols than `input_len` are consumed when `output` is full, so call
+/// this again with the rest of the input. Returns `CWB32_OK` on success,
+/// `CWB32_ERROR_INVALID_SYMBOL` if the input contains an invalid symbol, or
+/// `CWB32_ERROR_NULL_POINTER`. Nothing is consumed on failure.
+///
+/// # Safety
+/// `decoder` must be returned by `cwb32_decoder_new` and not be freed yet. The
+/// other pointers must be valid like for `cwb32_decode`.
+#[no_mangle]
+pub unsafe extern "C" fn cwb32_decoder_update(
+    decoder: *mut Cwb32Decoder,
+    input: *const u8,
+    input_len: usize,
+    output: *mut u8,
+    output_cap: usize,
+    consumed: *mut usize,
+    written: *mut usize,
+) -> c_int {
+    status((|| {
+        let decoder = decoder.as_mut().ok_or(FfiError::NullPointer)?;
+        let input = input_slice(input, input_len)?;
+        // Check the symbols here to know their offsets in the stream.
+        find_invalid_symbol(input).map_err(|err| {
+            let position = decoder.offset + err.position();
+            FfiError::Parse(ParseError::new(err.into(), position, None))
+        })?;
+        let len = update(
+            &mut decoder.decoder,
+            input,
+            output,
+            output_cap,
+            consumed,
+            written,
+        )?;
+        decoder.offset += len;
+        Ok(())
+    })())
+}
+
+/// Decodes the symbols kept in the decoder into the `output_cap` bytes buffer at
+/// `output`, and stores the number of written bytes, which is at most 4, to
+/// `*written`.
+///
+/// Returns `CWB32_OK` on success, `CWB32_ERROR_BUFFER_TOO_SMALL` if
+/// `output_cap` is less than needed, or `CWB32_ERROR_NULL_POINTER`. The decoder
+/// can be used again after success.
+///
+/// # Safety
+/// The pointers must be valid like for `cwb32_decoder_update`.
+#[no_mangle]
+pub unsafe extern "C" fn cwb32_decoder_finish(
+    decoder: *mut Cwb32Decoder,
+    output: *mut u8,
+    output_cap: usize,
+    written: *mut usize,
+) -> c_int {
+    status((|| {
+        let decoder = decoder.as_mut().ok_or(FfiError::NullPointer)?;
+        finish(&mut decoder.decoder, output, output_cap, written)?;
+        decoder.offset = 0;
+        Ok(())
+    })())
+}
+
+/// Releases a decoder. Does nothing if `decoder` is `NULL`.
+///
+/// # Safety
+/// `decoder` must be `NULL` or returned by `cwb32_decoder_new` and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn cwb32_decoder_free(decoder: *mut Cwb32Decoder) {
+    if !decoder.is_null() {
+        drop(Box::from_raw(decoder));
+    }
+}
+
+// update calls Transcode::update with the buffers from C, stores the lengths
+// and returns the number of consumed bytes.
+unsafe fn update<T: Transcode>(
+    transcoder: &mut T,
+    input: &[u8],
+    output: *mut u8,
+    output_cap: usize,
+    consumed: *mut usize,
+    written: *mut usize,
+) -> Result<usize, FfiError> {
+    let output = output_slice(output, output_cap)?;
+    let consumed = consumed.as_mut().ok_or(FfiError::NullPointer)?;
+    let written = written.as_mut().ok_or(FfiError::NullPointer)?;
+    let (input_len, output_len) = transcoder.update(input, output)?;
+    *consumed = input_len;
+    *written = output_len;
+    Ok(input_len)
+}
+
+// finish calls Transcode::finish with the buffer from C and stores the length.
+unsafe fn finish<T: Transcode>(
+    transcoder: &mut T,
+    output: *mut u8,
+    output_cap: usize,
+    written: *mut usize,
+) -> Result<(), FfiError> {
+    let output = output_slice(output, output_cap)?;
+    let written = written.as_mut().ok_or(FfiError::NullPointer)?;
+    *written = transcoder.finish(output)?;
+    Ok(())
+}
+
+// input_slice returns the slice for a pointer and a length from C, or an error
+// if the pointer is NULL for a non-empty slice.
+unsafe fn input_slice<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], FfiError> {
+    match len {
+        0 => Ok(&[]),
+        _ if ptr.is_null() => Err(FfiError::NullPointer),
+        _ => Ok(slice::from_raw_parts(ptr, len)),
+    }
+}
+
+// output_slice is the mutable version of input_slice.
+unsafe fn output_slice<'a>(ptr: *mut u8, len: usize) -> Result<&'a mut [u8], FfiError> {
+    match len {
+        0 => Ok(&mut []),
+        _ if ptr.is_null() => Err(FfiError::NullPointer),
+        _ => Ok(slice::from_raw_parts_mut(ptr, len)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::ptr;
+
+    #[test]
+    fn test_encode_decode() {
+        let input = b"Hello, world!";
+        let mut encoded = [0; 32];
+        let mut encoded_len = 0;
+        let status = unsafe {
+            cwb32_encode(
+                input.as_ptr(),
+                input.len(),
+                encoded.as_mut_ptr(),
+                encoded.len(),
+                &mut encoded_len,
+            )
+        };
+        assert_eq!(status, CWB32_OK);
+        assert_eq!(encoded_len, cwb32_encoded_len(input.len()));
+        assert_eq!(&encoded[..encoded_len], b"91JPRV3F5GG7EVVJDHJ22");
+
+        let mut decoded = [0; 16];
+        let mut decoded_len = 0;
+        let status = unsafe {
+            cwb32_decode(
+                encoded.as_ptr(),
+                encoded_len,
+                decoded.as_mut_ptr(),
+                decoded.len(),
+                &mut decoded_len,
+            )
+        };
+        assert_eq!(status, CWB32_OK);
+        assert_eq!(decoded_len, cwb32_decoded_len(encoded_len));
+        assert_eq!(&decoded[..decoded_len], input);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut output = [0; 8];
+        let mut output_len = 0;
+        unsafe {
+            // Empty buffers may be NULL.
+            let status = cwb32_encode(ptr::null(), 0, ptr::null_mut(), 0, &mut output_len);
+            assert_eq!((status, output_len), (CWB32_OK, 0));
+
+            let input = b"CSQPU";
+            let status = cwb32_decode(input.as_ptr(), 5, output.as_mut_ptr(), 8, &mut output_len);
+            assert_eq!(status, CWB32_ERROR_INVALID_SYMBOL);
+            assert_eq!(cwb32_last_error_code(), CWB32_ERROR_INVALID_SYMBOL);
+            assert_eq!(cwb32_last_error_offset(), 4);
+            assert_eq!(
+                last_error_message(),
+                "invalid symbol 'U' at position 4, 'U' is excluded from the alphabet, \
+                 did you mean 'V'?"
+            );
+
+            let status = cwb32_encode(input.as_ptr(), 5, output.as_mut_ptr(), 7, &mut output_len);
+            assert_eq!(status, CWB32_ERROR_BUFFER_TOO_SMALL);
+            assert_eq!(cwb32_last_error_offset(), 0);
+            assert_eq!(last_error_message(), "destination buffer is too small");
+
+            let status = cwb32_encode(ptr::null(), 5, output.as_mut_ptr(), 8, &mut output_len);
+            assert_eq!(status, CWB32_ERROR_NULL_POINTER);
+            let status = cwb32_encode(input.as_ptr(), 5, output.as_mut_ptr(), 8, ptr::null_mut());
+            assert_eq!(status, CWB32_ERROR_NULL_POINTER);
+            assert_eq!(last_error_message(), "a pointer argument is NULL");
+
+            // The message is truncated to the buffer.
+            let mut buf = [0x7f_u8; 8];
+            let len = cwb32_last_error_message(buf.as_mut_ptr().cast(), buf.len());
+            assert_eq!(len, 26);
+            assert_eq!(&buf, b"a point\0");
+            assert_eq!(cwb32_last_error_message(ptr::null_mut(), 0), 26);
+        }
+    }
+
+    #[test]
+    fn test_no_error() {
+        // Each thread has its own last error.
+        std::thread::spawn(|| {
+            assert_eq!(cwb32_last_error_code(), CWB32_OK);
+            assert_eq!(unsafe { last_error_message() }, "");
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(
+            CWB32_ERROR_INVALID_SYMBOL,
+            Error::InvalidSymbol {
+                byte: 0,
+                position: 0
+            }
+            .code()
+        );
+        assert_eq!(CWB32_ERROR_INVALID_UTF8, Error::InvalidUtf8.code());
+        assert_eq!(CWB32_ERROR_BUFFER_TOO_SMALL, Error::BufferTooSmall.code());
+        assert_eq!(CWB32_ERROR_NON_CANONICAL, Error::NonCanonical.code());
+        let err = Error::LengthMismatch {
+            expected: 0,
+            actual: 0,
+        };
+        assert_eq!(CWB32_ERROR_LENGTH_MISMATCH, err.code());
+        assert_eq!(CWB32_ERROR_INVALID_SYMBOLS, Error::InvalidSymbols.code());
+        let err = Error::LimitExceeded { limit: 0 };
+        assert_eq!(CWB32_ERROR_LIMIT_EXCEEDED, err.code());
+        assert_eq!(CWB32_ERROR_OUT_OF_RANGE, Error::OutOfRange.code());
+    }
+
+    unsafe fn last_error_message() -> String {
+        let mut buf = [0_u8; 256];
+        let len = cwb32_last_error_message(buf.as_mut_ptr().cast(), buf.len());
+        String::from_utf8(buf[..len].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_streaming() {
+        let input = b"Hello, world!";
+        let mut encoded = Vec::new();
+        let mut output = [0; 8];
+        let (mut consumed, mut written) = (0, 0);
+        unsafe {
+            let encoder = cwb32_encoder_new();
+            for chunk in input.chunks(3) {
+                let mut pos = 0;
+                while pos < chunk.len() {
+                    let rest = &chunk[pos..];
+                    let status = cwb32_encoder_update(
+                        encoder,
+                        rest.as_ptr(),
+                        rest.len(),
+                        output.as_mut_ptr(),
+                        output.len(),
+                        &mut consumed,
+                        &mut written,
+                    );
+                    assert_eq!(status, CWB32_OK);
+                    encoded.extend_from_slice(&output[..written]);
+                    pos += consumed;
+                }
+            }
+            let status = cwb32_encoder_finish(encoder, output.as_mut_ptr(), 8, &mut written);
+            assert_eq!(status, CWB32_OK);
+            encoded.extend_from_slice(&output[..written]);
+            cwb32_encoder_free(encoder);
+        }
+        assert_eq!(encoded, b"91JPRV3F5GG7EVVJDHJ22");
+
+        let mut decoded = Vec::new();
+        unsafe {
+            let decoder = cwb32_decoder_new();
+            for chunk in encoded.chunks(5) {
+                let status = cwb32_decoder_update(
+                    decoder,
+                    chunk.as_ptr(),
+                    chunk.len(),
+                    output.as_mut_ptr(),
+                    output.len(),
+                    &mut consumed,
+                    &mut written,
+                );
+                assert_eq!((status, consumed), (CWB32_OK, chunk.len()));
+                decoded.extend_from_slice(&output[..written]);
+            }
+            // Too short for the 3 bytes of the last 5 symbols.
+            let status = cwb32_decoder_finish(decoder, output.as_mut_ptr(), 2, &mut written);
+            assert_eq!(status, CWB32_ERROR_BUFFER_TOO_SMALL);
+            let status = cwb32_decoder_finish(decoder, output.as_mut_ptr(), 8, &mut written);
+            assert_eq!(status, CWB32_OK);
+            decoded.extend_from_slice(&output[..written]);
+
+            // The offset of an invalid symbol counts from the start of the stream.
+            for (chunk, expected) in [(&b"CSQ"[..], CWB32_OK), (b"PU", CWB32_ERROR_INVALID_SYMBOL)]
+            {
+                let status = cwb32_decoder_update(
+                    decoder,
+                    chunk.as_ptr(),
+                    chunk.len(),
+                    output.as_mut_ptr(),
+                    output.len(),
+                    &mut consumed,
+                    &mut written,
+                );
+                assert_eq!(status, expected);
+            }
+            assert_eq!(cwb32_last_error_offset(), 4);
+            cwb32_decoder_free(decoder);
+            cwb32_decoder_free(ptr::null_mut());
+            let status =
+                cwb32_decoder_finish(ptr::null_mut(), output.as_mut_ptr(), 8, &mut written);
+            assert_eq!(status, CWB32_ERROR_NULL_POINTER);
+        }
+        assert_eq!(decoded, input);
+    }
+}
@@ -0,0 +1,113 @@
+//! Encoded data as file names.
+//!
+//! Encoded text is safe in file names on all common file systems: the symbols
+//! are letters and digits only, and decoding ignores the case, which
+//! case-insensitive file systems may not preserve. Since `O`, `U`, `I` and `L`
+//! are not symbols, the only reserved device name of Windows an encoded name
+//! can be is `PRN`, which only a name of 3 symbols can be.
+//!
+//! File systems limit the length of a name, so long encoded data like a hash is
+//! split into segments of a maximum length, which are used as the directories
+//! and the file name of a path in order.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "std")]
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32::filename;
+//! use std::path::Path;
+//!
+//! let hash = [0x3c; 32];
+//! let segments = filename::encode_to_segments(&hash, 24);
+//! assert_eq!(segments.iter().map(String::len).collect::<Vec<_>>(), [24, 24, 4]);
+//! assert_eq!(filename::decode_segments(&segments)?, hash);
+//!
+//! let path = filename::encode_to_path(&hash, 24);
+//! let cached = Path::new("/var/cache/app").join(&path);
+//! assert_eq!(filename::decode_path(cached.strip_prefix("/var/cache/app").unwrap())?, hash);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+
+use crate::{decode_to_vec, encode_to_string, Result};
+
+/// Encodes bytes and splits the result into segments of up to `max_len`
+/// symbols in order.
+///
+/// A `max_len` of zero is taken as one. An empty input gives no segments.
+pub fn encode_to_segments(input: &[u8], max_len: usize) -> Vec<String> {
+    let encoded = encode_to_string(input);
+    encoded
+        .as_bytes()
+        .chunks(max_len.max(1))
+        // The encoded result is ASCII, so any chunk is valid UTF-8.
+        .map(|chunk| String::from(core::str::from_utf8(chunk).unwrap_or_default()))
+        .collect()
+}
+
+/// Decodes segments in order.
+///
+/// # Errors
+/// Returns [`Err`] if a segment contains an invalid symbol.
+pub fn decode_segments<I>(segments: I) -> Result<Vec<u8>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut symbols = Vec::new();
+    for segment in segments {
+        symbols.extend_from_slice(segment.as_ref().as_bytes());
+    }
+    decode_to_vec(&symbols)
+}
+
+/// Encodes bytes to a relative path whose components are the segments of up
+/// to `max_len` symbols.
+#[cfg(feature = "std")]
+pub fn encode_to_path(input: &[u8], max_len: usize) -> PathBuf {
+    encode_to_segments(input, max_len).into_iter().collect()
+}
+
+/// Decodes the components of a relative path in order.
+///
+/// # Errors
+/// Returns [`Err`] if a component contains an invalid symbol, including the
+/// root directory and `.` or `..` components.
+#[cfg(feature = "std")]
+pub fn decode_path(path: &Path) -> Result<Vec<u8>> {
+    let mut symbols = Vec::new();
+    for component in path.components() {
+        symbols.extend_from_slice(component.as_os_str().as_encoded_bytes());
+    }
+    decode_to_vec(&symbols)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments() {
+        let input: Vec<u8> = (0..=255).collect();
+        for max_len in [0, 1, 2, 8, 100] {
+            for len in [0, 1, 5, 32, 64] {
+                let segments = encode_to_segments(&input[..len], max_len);
+                assert!(segments.iter().all(|s| s.len() <= max_len.max(1)));
+                assert_eq!(segments.concat(), crate::encode_to_string(&input[..len]));
+                assert_eq!(decode_segments(&segments).unwrap(), &input[..len]);
+                let path = encode_to_path(&input[..len], max_len);
+                assert_eq!(decode_path(&path).unwrap(), &input[..len]);
+            }
+        }
+        assert!(decode_segments(["CSQP", "YRK1", "E8.tmp"]).is_err());
+        assert!(decode_path(Path::new("/CSQP")).is_err());
+        assert!(decode_path(Path::new("CSQP/../YRK1")).is_err());
+    }
+}
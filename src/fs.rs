@@ -0,0 +1,337 @@
+//! Encoding and decoding files with atomic output.
+//!
+//! The functions in this module stream the input file through the adapters in
+//! [`io`](crate::io) into a temporary file next to the destination, and rename it
+//! to the destination only when everything succeeded. So an interrupted or
+//! failed run never leaves a half-written destination, and an existing
+//! destination is replaced at once. [`write_atomic`] gives the same guarantee
+//! to any other output.
+//!
+//! With the `memmap2` feature, [`encode_file_mmap`] and [`decode_file_mmap`]
+//! map the input and the output files to memory and run the block kernels
+//! directly over the mappings, without read and write calls or intermediate
+//! buffers, for transcoding huge files.
+//!
+//! # Examples
+//! ```no_run
+//! # fn main() -> std::io::Result<()> {
+//! use clockwork_base32::fs;
+//!
+//! fs::encode_file("backup.tar", "backup.tar.b32")?;
+//! fs::decode_file("backup.tar.b32", "restored.tar")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::ffi::OsString;
+use std::fs::{self as std_fs, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::io::{DecodeWriter, EncodeWriter};
+#[cfg(feature = "memmap2")]
+use crate::{
+    capacity_hint_for_decode, capacity_hint_for_encode, decode_to_slice, encode_to_slice, Result,
+};
+#[cfg(feature = "memmap2")]
+use core::convert::TryFrom;
+
+// The size of the buffers for reading and writing files.
+const BUF_LEN: usize = 64 * 1024;
+
+/// Encodes the file at `src` and writes the result to `dest` atomically.
+///
+/// # Errors
+/// Returns [`Err`] if reading `src` or writing `dest` fails. `dest` is left as
+/// it was in that case.
+pub fn encode_file<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dest: Q) -> io::Result<()> {
+    transcode_file(src.as_ref(), dest.as_ref(), |input, output| {
+        let mut writer = EncodeWriter::new(output);
+        io::copy(input, &mut writer)?;
+        writer.finish().map(|_| ())
+    })
+}
+
+/// Decodes the file at `src` and writes the result to `dest` atomically.
+///
+/// # Errors
+/// Returns [`Err`] with [`io::ErrorKind::InvalidInput`] if `src` contains an
+/// invalid symbol, or [`Err`] if reading `src` or writing `dest` fails. `dest` is
+/// left as it was in these cases.
+pub fn decode_file<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dest: Q) -> io::Result<()> {
+    transcode_file(src.as_ref(), dest.as_ref(), |input, output| {
+        let mut writer = DecodeWriter::new(output);
+        io::copy(input, &mut writer)?;
+        writer.finish().map(|_| ())
+    })
+}
+
+/// Encodes the file at `src` and writes the result to `dest` atomically through
+/// memory mappings.
+///
+/// # Errors
+/// Returns [`Err`] if reading `src`, writing `dest` or mapping either fails.
+/// `dest` is left as it was in that case.
+///
+/// # Safety
+/// The file at `src` must not be modified or truncated while this runs, for
+/// example by another process. Doing so is undefined behavior.
+#[cfg(feature = "memmap2")]
+pub unsafe fn encode_file_mmap<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dest: Q) -> io::Result<()> {
+    // SAFETY: The caller guarantees that `src` is not modified.
+    unsafe {
+        transcode_file_mmap(
+            src.as_ref(),
+            dest.as_ref(),
+            capacity_hint_for_encode,
+            |input, output| encode_to_slice(input, output),
+        )
+    }
+}
+
+/// Decodes the file at `src` and writes the result to `dest` atomically through
+/// memory mappings.
+///
+/// # Errors
+/// Returns [`Err`] with [`io::ErrorKind::InvalidInput`] if `src` contains an
+/// invalid symbol, or [`Err`] if reading `src`, writing `dest` or mapping
+/// either fails. `dest` is left as it was in these cases.
+///
+/// # Safety
+/// The file at `src` must not be modified or truncated while this runs, for
+/// example by another process. Doing so is undefined behavior.
+#[cfg(feature = "memmap2")]
+pub unsafe fn decode_file_mmap<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dest: Q) -> io::Result<()> {
+    // SAFETY: The caller guarantees that `src` is not modified.
+    unsafe {
+        transcode_file_mmap(
+            src.as_ref(),
+            dest.as_ref(),
+            capacity_hint_for_decode,
+            |input, output| decode_to_slice(input, output),
+        )
+    }
+}
+
+// transcode_file_mmap maps `src` and a temporary file of the output length to
+// memory, runs `transcode` over them, and renames the temporary file to `dest`
+// on success, or removes it on failure.
+//
+// SAFETY: `src` must not be modified while this runs.
+#[cfg(feature = "memmap2")]
+unsafe fn transcode_file_mmap(
+    src: &Path,
+    dest: &Path,
+    output_len: fn(usize) -> usize,
+    transcode: fn(&[u8], &mut [u8]) -> Result<usize>,
+) -> io::Result<()> {
+    use memmap2::{Mmap, MmapMut};
+
+    let input_file = File::open(src)?;
+    let input_len = usize::try_from(input_file.metadata()?.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file too large to map"))?;
+    let output_len = output_len(input_len);
+    let (temp_path, temp) = create_temp(dest)?;
+    let result = (|| {
+        temp.set_len(output_len as u64)?;
+        // Empty files are not mapped, since mapping them fails on some platforms.
+        // An input of one symbol decodes to no bytes, but it is still checked.
+        if input_len > 0 {
+            // SAFETY: The caller guarantees that `src` is not modified.
+            let input = unsafe { Mmap::map(&input_file)? };
+            if output_len > 0 {
+                // SAFETY: The temporary file was just created with a new name,
+                // so nothing else modifies it.
+                let mut output = unsafe { MmapMut::map_mut(&temp)? };
+                transcode(&input, &mut output)?;
+                output.flush()?;
+            } else {
+                transcode(&input, &mut [])?;
+            }
+        }
+        temp.sync_all()?;
+        std_fs::rename(&temp_path, dest)
+    })();
+    if result.is_err() {
+        let _ = std_fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Runs `write` with a buffered writer to a temporary file next to `dest`, and
+/// renames the file to `dest` atomically if `write` succeeds.
+///
+/// This is the building block of [`encode_file`] and [`decode_file`], for
+/// writing other output with the same guarantee.
+///
+/// # Errors
+/// Returns [`Err`] if `write` fails or creating, writing or renaming the
+/// temporary file fails. `dest` is left as it was in these cases.
+///
+/// # Examples
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use clockwork_base32::{fs, io::EncodeWriter};
+/// use std::io::Write;
+///
+/// fs::write_atomic("greeting.b32", |output| {
+///     let mut writer = EncodeWriter::new(output);
+///     writer.write_all(b"Hello, world!")?;
+///     writer.finish().map(|_| ())
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_atomic<P, F>(dest: P, write: F) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let dest = dest.as_ref();
+    let (temp_path, temp) = create_temp(dest)?;
+    let mut output = BufWriter::with_capacity(BUF_LEN, temp);
+    let result = write(&mut output)
+        .and_then(|()| output.into_inner().map_err(io::IntoInnerError::into_error))
+        .and_then(|file| file.sync_all())
+        .and_then(|()| std_fs::rename(&temp_path, dest));
+    if result.is_err() {
+        let _ = std_fs::remove_file(&temp_path);
+    }
+    result
+}
+
+// transcode_file runs `transcode` from `src` to `dest` with `write_atomic`.
+fn transcode_file<F>(src: &Path, dest: &Path, transcode: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BufReader<File>, &mut BufWriter<File>) -> io::Result<()>,
+{
+    let mut input = BufReader::with_capacity(BUF_LEN, File::open(src)?);
+    write_atomic(dest, |output| transcode(&mut input, output))
+}
+
+// create_temp creates a new file in the directory of `dest` with a name which
+// is not used yet.
+fn create_temp(dest: &Path) -> io::Result<(PathBuf, File)> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let file_name = dest
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no file name in the path"))?;
+    loop {
+        let mut temp_name = OsString::from(".");
+        temp_name.push(file_name);
+        temp_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp_path = dest.with_file_name(temp_name);
+        // Readable too, since writable memory mappings need it.
+        match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)
+        {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_to_vec, Error};
+
+    // A new empty directory for a test, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "clockwork_base32-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = std_fs::remove_dir_all(&path);
+            std_fs::create_dir(&path).unwrap();
+            Self(path)
+        }
+
+        fn file_names(&self) -> Vec<String> {
+            let mut names: Vec<String> = std_fs::read_dir(&self.0)
+                .unwrap()
+                .map(|e| e.unwrap().file_name().into_string().unwrap())
+                .collect();
+            names.sort();
+            names
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std_fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_encode_decode_file() {
+        let dir = TempDir::new("fs");
+        let plain: Vec<u8> = (0..200_000).map(|i| (i * 7) as u8).collect();
+        std_fs::write(dir.0.join("plain"), &plain).unwrap();
+
+        encode_file(dir.0.join("plain"), dir.0.join("encoded")).unwrap();
+        assert_eq!(
+            std_fs::read(dir.0.join("encoded")).unwrap(),
+            encode_to_vec(&plain)
+        );
+        decode_file(dir.0.join("encoded"), dir.0.join("decoded")).unwrap();
+        assert_eq!(std_fs::read(dir.0.join("decoded")).unwrap(), plain);
+
+        // A failed run leaves the destination as it was and no temporary files.
+        std_fs::write(dir.0.join("invalid"), b"CSQPU").unwrap();
+        let err = decode_file(dir.0.join("invalid"), dir.0.join("decoded")).unwrap_err();
+        assert!(matches!(
+            err.get_ref().and_then(|e| e.downcast_ref::<Error>()),
+            Some(Error::InvalidSymbol { byte: b'U', .. })
+        ));
+        assert_eq!(std_fs::read(dir.0.join("decoded")).unwrap(), plain);
+        assert!(decode_file(dir.0.join("missing"), dir.0.join("decoded")).is_err());
+        assert_eq!(dir.file_names(), ["decoded", "encoded", "invalid", "plain"]);
+    }
+
+    #[cfg(feature = "memmap2")]
+    #[test]
+    fn test_encode_decode_file_mmap() {
+        let dir = TempDir::new("fs-mmap");
+        for len in [0, 1, 7, 200_000] {
+            let plain: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+            std_fs::write(dir.0.join("plain"), &plain).unwrap();
+            unsafe { encode_file_mmap(dir.0.join("plain"), dir.0.join("encoded")).unwrap() };
+            assert_eq!(
+                std_fs::read(dir.0.join("encoded")).unwrap(),
+                encode_to_vec(&plain)
+            );
+            unsafe { decode_file_mmap(dir.0.join("encoded"), dir.0.join("decoded")).unwrap() };
+            assert_eq!(std_fs::read(dir.0.join("decoded")).unwrap(), plain);
+        }
+
+        // A failed run leaves the destination as it was and no temporary files.
+        let decoded = std_fs::read(dir.0.join("decoded")).unwrap();
+        for invalid in [&b"U"[..], b"CSQPU", b"CSQPYRK1E8U"] {
+            std_fs::write(dir.0.join("invalid"), invalid).unwrap();
+            let err = unsafe { decode_file_mmap(dir.0.join("invalid"), dir.0.join("decoded")) };
+            assert!(matches!(
+                err.unwrap_err()
+                    .get_ref()
+                    .and_then(|e| e.downcast_ref::<Error>()),
+                Some(Error::InvalidSymbol { byte: b'U', .. })
+            ));
+        }
+        assert_eq!(std_fs::read(dir.0.join("decoded")).unwrap(), decoded);
+        assert_eq!(dir.file_names(), ["decoded", "encoded", "invalid", "plain"]);
+    }
+}
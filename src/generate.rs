@@ -0,0 +1,429 @@
+//! Generators of random tokens and [ULID]s in Clockwork Base32.
+//!
+//! The random bytes come from the operating system through `getrandom`.
+//!
+//! [ULID]: https://github.com/ulid/spec
+//!
+//! # Examples
+//! ```
+//! # fn main() -> std::io::Result<()> {
+//! use clockwork_base32::generate;
+//!
+//! // 128 random bits are 16 bytes, encoded to 26 symbols.
+//! let token = generate::token(16)?;
+//! assert_eq!(token.len(), 26);
+//! assert_eq!(token.decoded_len(), 16);
+//!
+//! let symbols = generate::symbols(6)?;
+//! assert_eq!(symbols.len(), 6);
+//!
+//! let ulid = generate::ulid()?;
+//! assert_eq!(ulid.len(), 26);
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(any(feature = "time", feature = "chrono"))]
+use core::convert::TryFrom;
+use std::io;
+use std::string::String;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::vec;
+
+use crate::{
+    capacity_hint_for_decode, decode_symbol, encode_symbol, find_invalid_symbol, is_symbol_value,
+    Base32String, Error, ParseError, DECODED_BIT_LEN,
+};
+
+/// The length of a ULID in symbols.
+pub const ULID_LEN: usize = 26;
+
+/// Returns the encoding of `byte_len` random bytes.
+///
+/// # Errors
+/// Returns [`Err`] if the operating system fails to provide random bytes.
+pub fn token(byte_len: usize) -> io::Result<Base32String> {
+    let mut bytes = vec![0; byte_len];
+    getrandom::fill(&mut bytes)?;
+    Ok(Base32String::encode(&bytes))
+}
+
+/// Returns `len` random symbols, each carrying 5 random bits.
+///
+/// Unlike [`token`], the result can have any length, so it is not always a
+/// canonical encoding of some bytes.
+///
+/// # Errors
+/// Returns [`Err`] if the operating system fails to provide random bytes.
+pub fn symbols(len: usize) -> io::Result<String> {
+    let mut bytes = vec![0; len];
+    getrandom::fill(&mut bytes)?;
+    // 256 is a multiple of 32, so taking the lower bits keeps them uniform.
+    Ok(bytes.iter().map(|&b| encode_symbol(b) as char).collect())
+}
+
+/// The number of attempts of [`token_avoiding`] and [`symbols_avoiding`] before
+/// they give up.
+pub const MAX_ATTEMPTS: usize = 1000;
+
+/// Returns the encoding of `byte_len` random bytes which doesn't contain any
+/// word of `blocklist`, as checked by [`contains_blocked`].
+///
+/// Results containing a word are re-rolled, so the result stays uniformly
+/// random among the allowed ones.
+///
+/// # Errors
+/// Returns [`Err`] if the operating system fails to provide random bytes, or if
+/// no allowed result is found in [`MAX_ATTEMPTS`] attempts, as for a blocklist
+/// containing a single symbol.
+///
+/// # Examples
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use clockwork_base32::generate;
+///
+/// let blocklist = ["BAD", "F00L"];
+/// let voucher = generate::token_avoiding(10, &blocklist)?;
+/// assert!(!generate::contains_blocked(&voucher, &blocklist));
+/// # Ok(())
+/// # }
+/// ```
+pub fn token_avoiding(byte_len: usize, blocklist: &[&str]) -> io::Result<Base32String> {
+    retry_avoiding(blocklist, || token(byte_len))
+}
+
+/// Returns `len` random symbols which don't contain any word of `blocklist`,
+/// as checked by [`contains_blocked`].
+///
+/// # Errors
+/// Returns [`Err`] like [`token_avoiding`].
+pub fn symbols_avoiding(len: usize, blocklist: &[&str]) -> io::Result<String> {
+    retry_avoiding(blocklist, || symbols(len))
+}
+
+/// Returns whether `code` contains any word of `blocklist`, ignoring the case.
+///
+/// The words are compared as they would be decoded, so `O`, `I` and `L` in
+/// them match `0` and `1` in the code, like `F00L` reading as `FOOL`. Empty
+/// words are ignored.
+///
+/// # Examples
+/// ```
+/// use clockwork_base32::generate;
+///
+/// assert!(generate::contains_blocked("X3F001Y", &["fool"]));
+/// assert!(!generate::contains_blocked("X3F0Y", &["fool"]));
+/// ```
+pub fn contains_blocked(code: &str, blocklist: &[&str]) -> bool {
+    let code: Vec<u8> = code.bytes().map(normalize).collect();
+    blocklist.iter().any(|word| {
+        let word: Vec<u8> = word.bytes().map(normalize).collect();
+        !word.is_empty() && code.windows(word.len()).any(|w| w == word.as_slice())
+    })
+}
+
+// normalize returns the canonical symbol `b` decodes to, or `b` in uppercase
+// if it is not a symbol.
+fn normalize(b: u8) -> u8 {
+    let v = decode_symbol(b);
+    if is_symbol_value(v) {
+        encode_symbol(v)
+    } else {
+        b.to_ascii_uppercase()
+    }
+}
+
+fn retry_avoiding<T, F>(blocklist: &[&str], mut generate: F) -> io::Result<T>
+where
+    T: AsRef<str>,
+    F: FnMut() -> io::Result<T>,
+{
+    for _ in 0..MAX_ATTEMPTS {
+        let code = generate()?;
+        if !contains_blocked(code.as_ref(), blocklist) {
+            return Ok(code);
+        }
+    }
+    Err(io::Error::other(
+        "no result avoiding the blocklist was generated",
+    ))
+}
+
+/// Returns a new ULID for the current time with 80 random bits.
+///
+/// # Errors
+/// Returns [`Err`] if the operating system fails to provide random bytes.
+pub fn ulid() -> io::Result<String> {
+    ulid_at(SystemTime::now())
+}
+
+/// Returns a new ULID for `time` with 80 random bits.
+///
+/// `time` is a [`SystemTime`], or with the `time` or `chrono` feature enabled,
+/// a `time::OffsetDateTime` or a `chrono::DateTime<Utc>`.
+///
+/// # Errors
+/// Returns [`Err`] if the operating system fails to provide random bytes.
+pub fn ulid_at<T: UlidTime>(time: T) -> io::Result<String> {
+    let mut random = [0; 10];
+    getrandom::fill(&mut random)?;
+    Ok(ulid_from_parts(time.to_unix_ms(), random))
+}
+
+/// Returns the time of a ULID in milliseconds.
+///
+/// # Errors
+/// Returns [`Err`] if `ulid` is not 26 symbols long, contains an invalid byte,
+/// or starts with a symbol over `7`, which would overflow 128 bits.
+///
+/// # Examples
+/// ```
+/// use clockwork_base32::generate;
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// let time: SystemTime = generate::ulid_time("01ARYZ6S410000000000000000").unwrap();
+/// assert_eq!(time, UNIX_EPOCH + Duration::from_millis(1_469_918_176_385));
+/// ```
+pub fn ulid_time<T: UlidTime>(ulid: &str) -> Result<T, ParseError> {
+    let ulid = ulid.as_bytes();
+    if ulid.len() != ULID_LEN {
+        let error = Error::LengthMismatch {
+            expected: 16,
+            actual: capacity_hint_for_decode(ulid.len()),
+        };
+        return Err(ParseError::new(error, ulid.len(), None));
+    }
+    find_invalid_symbol(ulid)?;
+    let first = ulid.first().copied().unwrap_or_default();
+    if decode_symbol(first) > 7 {
+        return Err(ParseError::invalid_symbol(first, 0));
+    }
+    let timestamp_ms = ulid
+        .get(..ULID_TIME_LEN)
+        .unwrap_or_default()
+        .iter()
+        .fold(0, |acc, &b| {
+            (acc << DECODED_BIT_LEN) | u64::from(decode_symbol(b))
+        });
+    Ok(T::from_unix_ms(timestamp_ms))
+}
+
+// The number of symbols of the 48-bit timestamp at the start of a ULID.
+const ULID_TIME_LEN: usize = 10;
+
+/// A point in time carried by a ULID in milliseconds since the Unix epoch.
+///
+/// This is implemented for [`SystemTime`], and with the `time` or `chrono`
+/// feature enabled, for `time::OffsetDateTime` and `chrono::DateTime<Utc>`.
+pub trait UlidTime: Sized {
+    /// Returns the milliseconds since the Unix epoch, or 0 for times before it.
+    fn to_unix_ms(&self) -> u64;
+
+    /// Returns the time `ms` milliseconds after the Unix epoch, or the latest
+    /// time the type can hold if it is later.
+    fn from_unix_ms(ms: u64) -> Self;
+}
+
+impl UlidTime for SystemTime {
+    fn to_unix_ms(&self) -> u64 {
+        self.duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64)
+    }
+
+    fn from_unix_ms(ms: u64) -> Self {
+        let at = |ms| UNIX_EPOCH.checked_add(Duration::from_millis(ms));
+        if let Some(time) = at(ms) {
+            return time;
+        }
+        // SystemTime has no MAX, so the latest millisecond it can hold is
+        // searched for. A 48-bit timestamp fits on all platforms, so this is
+        // only for larger ones on platforms like Windows.
+        let (mut fits, mut overflows) = (0, ms);
+        while overflows - fits > 1 {
+            let mid = fits + (overflows - fits) / 2;
+            if at(mid).is_some() {
+                fits = mid;
+            } else {
+                overflows = mid;
+            }
+        }
+        at(fits).unwrap_or(UNIX_EPOCH)
+    }
+}
+
+#[cfg(feature = "time")]
+impl UlidTime for time::OffsetDateTime {
+    fn to_unix_ms(&self) -> u64 {
+        u64::try_from(self.unix_timestamp_nanos() / 1_000_000).unwrap_or(0)
+    }
+
+    fn from_unix_ms(ms: u64) -> Self {
+        time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(ms) * 1_000_000)
+            .unwrap_or(time::PrimitiveDateTime::MAX.assume_utc())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl UlidTime for chrono::DateTime<chrono::Utc> {
+    fn to_unix_ms(&self) -> u64 {
+        u64::try_from(self.timestamp_millis()).unwrap_or(0)
+    }
+
+    fn from_unix_ms(ms: u64) -> Self {
+        i64::try_from(ms)
+            .ok()
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC)
+    }
+}
+
+/// Returns the ULID of a Unix timestamp in milliseconds and 80 random bits.
+///
+/// Only the lower 48 bits of `timestamp_ms` are used. A ULID encodes a 128-bit
+/// big-endian number padded with two zero bits at the start, so it differs from
+/// the encoding of the same 16 bytes, which pads at the end.
+///
+/// # Examples
+/// ```
+/// use clockwork_base32::generate;
+///
+/// let ulid = generate::ulid_from_parts(1_469_918_176_385, [0; 10]);
+/// assert_eq!(ulid, "01ARYZ6S410000000000000000");
+/// ```
+pub fn ulid_from_parts(timestamp_ms: u64, random: [u8; 10]) -> String {
+    let mut value = u128::from(timestamp_ms & 0xffff_ffff_ffff) << 80;
+    for (i, &b) in random.iter().enumerate() {
+        value |= u128::from(b) << (72 - 8 * i);
+    }
+    (0..ULID_LEN)
+        .rev()
+        .map(|i| encode_symbol((value >> (DECODED_BIT_LEN * i)) as u8) as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode_to_vec;
+
+    #[test]
+    fn test_token() {
+        assert_eq!(token(0).unwrap(), "");
+        let a = token(16).unwrap();
+        let b = token(16).unwrap();
+        assert_eq!(a.decode().len(), 16);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_symbols() {
+        let s = symbols(7).unwrap();
+        assert_eq!(s.len(), 7);
+        assert!(decode_to_vec(s.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_avoiding() {
+        assert!(contains_blocked("ABC", &["b"]));
+        assert!(!contains_blocked("A1C", &["il", "x"]));
+        assert!(contains_blocked("A11C", &["il"]));
+        assert!(!contains_blocked("ABC", &[""]));
+        assert!(!contains_blocked("AB", &["ABC"]));
+        assert!(contains_blocked("ab-c", &["B-C"]));
+
+        for _ in 0..10 {
+            let s = symbols_avoiding(20, &["0", "1"]).unwrap();
+            assert!(s.bytes().all(|b| b >= b'2'), "{}", s);
+        }
+        let token = token_avoiding(5, &["0"]).unwrap();
+        assert!(!token.contains('0'));
+        let all: Vec<String> = crate::ENCODE_SYMBOLS
+            .iter()
+            .map(|&b| (b as char).to_string())
+            .collect();
+        let all: Vec<&str> = all.iter().map(String::as_str).collect();
+        let err = symbols_avoiding(1, &all).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        assert_eq!(symbols_avoiding(0, &["A"]).unwrap(), "");
+    }
+
+    #[test]
+    fn test_ulid() {
+        assert_eq!(ulid_from_parts(0, [0; 10]), "00000000000000000000000000");
+        assert_eq!(
+            ulid_from_parts(u64::MAX, [0xff; 10]),
+            "7ZZZZZZZZZZZZZZZZZZZZZZZZZ"
+        );
+        assert_eq!(
+            ulid_from_parts(1_469_918_176_385, [0xa5; 10]),
+            "01ARYZ6S41MPJTB9D5MPJTB9D5"
+        );
+        let a = ulid().unwrap();
+        assert_eq!(a.len(), ULID_LEN);
+        assert!(a.as_str() < "8");
+    }
+
+    #[test]
+    fn test_ulid_time() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_469_918_176_385);
+        let ulid = ulid_at(time).unwrap();
+        assert!(ulid.starts_with("01ARYZ6S41"));
+        assert_eq!(ulid_time::<SystemTime>(&ulid).unwrap(), time);
+        assert_eq!(ulid_time::<SystemTime>(&ulid.to_lowercase()).unwrap(), time);
+        let max = ulid_time::<SystemTime>("7ZZZZZZZZZZZZZZZZZZZZZZZZZ").unwrap();
+        assert_eq!(max.to_unix_ms(), 0xffff_ffff_ffff);
+        assert_eq!((UNIX_EPOCH - Duration::from_secs(1)).to_unix_ms(), 0);
+        let latest = SystemTime::from_unix_ms(u64::MAX);
+        assert!(latest > max);
+        assert_eq!(SystemTime::from_unix_ms(latest.to_unix_ms()), latest);
+
+        let err = ulid_time::<SystemTime>("8ZZZZZZZZZZZZZZZZZZZZZZZZZ").unwrap_err();
+        assert!(matches!(
+            err.error(),
+            Error::InvalidSymbol { byte: b'8', .. }
+        ));
+        let err = ulid_time::<SystemTime>("01ARYZ6S41").unwrap_err();
+        assert!(matches!(
+            err.error(),
+            Error::LengthMismatch {
+                expected: 16,
+                actual: 6
+            }
+        ));
+        let err = ulid_time::<SystemTime>("01ARYZ6S41U000000000000000").unwrap_err();
+        assert_eq!(err.position(), 10);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_ulid_time_time() {
+        let time = time::OffsetDateTime::from_unix_timestamp(1_469_918_176).unwrap();
+        let ulid = ulid_at(time).unwrap();
+        assert!(ulid.starts_with("01ARYZ6RR0"));
+        assert_eq!(ulid_time::<time::OffsetDateTime>(&ulid).unwrap(), time);
+        assert_eq!(
+            ulid_time::<time::OffsetDateTime>("7ZZZZZZZZZZZZZZZZZZZZZZZZZ").unwrap(),
+            time::PrimitiveDateTime::MAX.assume_utc()
+        );
+        assert_eq!(
+            time::OffsetDateTime::from_unix_ms(u64::MAX),
+            time::PrimitiveDateTime::MAX.assume_utc()
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_ulid_time_chrono() {
+        let time = chrono::DateTime::from_timestamp_millis(1_469_918_176_385).unwrap();
+        let ulid = ulid_at(time).unwrap();
+        assert!(ulid.starts_with("01ARYZ6S41"));
+        assert_eq!(
+            ulid_time::<chrono::DateTime<chrono::Utc>>(&ulid).unwrap(),
+            time
+        );
+        assert_eq!(
+            chrono::DateTime::<chrono::Utc>::from_unix_ms(u64::MAX),
+            chrono::DateTime::<chrono::Utc>::MAX_UTC
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Helpers for binary tokens in HTTP header values with the `http` crate.
+//!
+//! Encoded text is always a valid header value, so [`header_value`] can't fail.
+//! [`decode_header_value`] and [`validate_header_value`] report the position of
+//! the first problem in a received value, to be returned to the client.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::http::{decode_header_value, header_value, validate_header_value};
+//! use http::{HeaderMap, HeaderValue};
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.insert("x-session-token", header_value(b"Hello, world!"));
+//! let value = &headers["x-session-token"];
+//! assert_eq!(value, "91JPRV3F5GG7EVVJDHJ22");
+//! assert_eq!(decode_header_value(value).unwrap(), b"Hello, world!");
+//!
+//! let value = HeaderValue::from_static("91jprv3f");
+//! assert!(validate_header_value(&value, false).is_ok());
+//! let err = validate_header_value(&value, true).unwrap_err();
+//! assert_eq!(err.position(), 2);
+//! ```
+
+use ::http::HeaderValue;
+use std::vec::Vec;
+
+use crate::{check_input, decode_checked, encode_to_string, ParseError};
+
+/// Returns the encoded `bytes` as a header value.
+pub fn header_value(bytes: &[u8]) -> HeaderValue {
+    // Encoded text consists of visible ASCII only, so this never fails.
+    HeaderValue::from_str(&encode_to_string(bytes)).unwrap_or(HeaderValue::from_static(""))
+}
+
+/// Decodes a header value.
+///
+/// # Errors
+/// Returns [`Err`] with the position of the first invalid byte.
+pub fn decode_header_value(value: &HeaderValue) -> Result<Vec<u8>, ParseError> {
+    decode_checked(value.as_bytes())
+}
+
+/// Checks that a header value can be decoded, and if `strict` is true, that it
+/// is a canonical encoding.
+///
+/// # Errors
+/// Returns [`Err`] with the position of the first problem.
+pub fn validate_header_value(value: &HeaderValue, strict: bool) -> Result<(), ParseError> {
+    check_input(value.as_bytes(), strict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn test_header_value() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in [0, 1, 5, 13, 256] {
+            let value = header_value(&bytes[..len]);
+            assert!(!value.is_sensitive());
+            assert_eq!(decode_header_value(&value).unwrap(), &bytes[..len]);
+            assert!(validate_header_value(&value, true).is_ok());
+        }
+
+        let value = HeaderValue::from_static("CSQP YRK1");
+        let err = decode_header_value(&value).unwrap_err();
+        assert!(matches!(
+            err.error(),
+            Error::InvalidSymbol { byte: b' ', .. }
+        ));
+        assert_eq!(err.position(), 4);
+        let value = HeaderValue::from_bytes(b"CSQP\xff").unwrap();
+        assert!(validate_header_value(&value, false).is_err());
+        let value = HeaderValue::from_static("CSQPZ");
+        assert!(validate_header_value(&value, false).is_ok());
+        assert!(validate_header_value(&value, true).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+//! Hardware addresses as short fixed-width strings, for device IDs printed on
+//! asset tags and provisioning labels.
+//!
+//! A 48-bit MAC address or EUI-48 is encoded to 10 symbols and an EUI-64 to 13
+//! symbols, shorter than the 17 and 23 characters of the usual colon-separated
+//! hex. The encoded results are kept in a [`StackEncoder`], so this works
+//! without the `alloc` feature.
+//!
+//! # Examples
+//! ```
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32::hwaddr;
+//!
+//! let mac = [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e];
+//! let label = hwaddr::encode_eui48(mac);
+//! assert_eq!(label.as_str(), "00D2PF2DBR");
+//! assert_eq!(hwaddr::decode_eui48("00d2pf2dbr")?, mac);
+//!
+//! let eui64 = [0x02, 0x1a, 0x2b, 0xff, 0xfe, 0x3c, 0x4d, 0x5e];
+//! assert_eq!(hwaddr::decode_eui64(&*hwaddr::encode_eui64(eui64))?, eui64);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{capacity_hint_for_decode, decode_to_slice, Error, IntoInput, Result, StackEncoder};
+
+/// The length of an encoded EUI-48 in symbols.
+pub const EUI48_ENCODED_LEN: usize = 10;
+
+/// The length of an encoded EUI-64 in symbols.
+pub const EUI64_ENCODED_LEN: usize = 13;
+
+/// Encodes a MAC address or EUI-48 to 10 symbols.
+pub fn encode_eui48(addr: [u8; 6]) -> StackEncoder<EUI48_ENCODED_LEN> {
+    let mut encoder = StackEncoder::new();
+    // The encoder has the exact length, so this never fails.
+    let _ = encoder.encode(&addr);
+    encoder
+}
+
+/// Encodes an EUI-64 to 13 symbols.
+pub fn encode_eui64(addr: [u8; 8]) -> StackEncoder<EUI64_ENCODED_LEN> {
+    let mut encoder = StackEncoder::new();
+    // The encoder has the exact length, so this never fails.
+    let _ = encoder.encode(&addr);
+    encoder
+}
+
+/// Decodes a MAC address or EUI-48 from 10 symbols.
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte or is not 10 symbols
+/// long.
+pub fn decode_eui48<'a, I>(input: I) -> Result<[u8; 6]>
+where
+    I: IntoInput<'a>,
+{
+    decode_fixed(input.into_input(), EUI48_ENCODED_LEN)
+}
+
+/// Decodes an EUI-64 from 13 symbols.
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte or is not 13 symbols
+/// long.
+pub fn decode_eui64<'a, I>(input: I) -> Result<[u8; 8]>
+where
+    I: IntoInput<'a>,
+{
+    decode_fixed(input.into_input(), EUI64_ENCODED_LEN)
+}
+
+// decode_fixed decodes `input` of exactly `encoded_len` symbols into an array.
+// Longer input decoding to the same number of bytes is rejected as not
+// canonical, so a mistyped extra symbol on a label is not ignored.
+pub(crate) fn decode_fixed<const N: usize>(input: &[u8], encoded_len: usize) -> Result<[u8; N]> {
+    if input.len() != encoded_len {
+        let actual = capacity_hint_for_decode(input.len());
+        if actual == N {
+            return Err(Error::NonCanonical);
+        }
+        return Err(Error::LengthMismatch {
+            expected: N,
+            actual,
+        });
+    }
+    let mut addr = [0; N];
+    decode_to_slice(input, &mut addr)?;
+    Ok(addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hwaddr() {
+        for addr in [[0; 6], [0xff; 6], [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]] {
+            let encoded = encode_eui48(addr);
+            assert_eq!(encoded.len(), EUI48_ENCODED_LEN);
+            assert_eq!(decode_eui48(&*encoded).unwrap(), addr);
+        }
+        for addr in [[0; 8], [0xff; 8], [1, 2, 3, 4, 5, 6, 7, 8]] {
+            let encoded = encode_eui64(addr);
+            assert_eq!(encoded.len(), EUI64_ENCODED_LEN);
+            assert_eq!(decode_eui64(&*encoded).unwrap(), addr);
+        }
+        assert_eq!(encode_eui48([0xff; 6]).as_str(), "ZZZZZZZZZW");
+
+        assert!(matches!(
+            decode_eui48("ZZZZZZZZ"),
+            Err(Error::LengthMismatch {
+                expected: 6,
+                actual: 5
+            })
+        ));
+        assert!(matches!(
+            decode_eui48("ZZZZZZZZZW0"),
+            Err(Error::NonCanonical)
+        ));
+        assert!(matches!(
+            decode_eui64("ZZZZZZZZZZZZU"),
+            Err(Error::InvalidSymbol { byte: b'U', .. })
+        ));
+    }
+}
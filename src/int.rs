@@ -0,0 +1,178 @@
+//! Unsigned integers as fixed-width encoded strings, for numeric IDs.
+//!
+//! An integer is encoded as its big-endian bytes, so all values of a type have
+//! the same length and sort in the same order as strings as they do as
+//! numbers. `NonZero` integers are supported, and decoding zero into them fails
+//! with [`Error::OutOfRange`], so ID types keep their invariant.
+//!
+//! With the `derive` feature, `#[derive(Base32)]` on a newtype over one of
+//! these integers implements `Display` and `FromStr` with these functions.
+//!
+//! # Examples
+//! ```
+//! # fn main() -> Result<(), clockwork_base32::ParseError> {
+//! use clockwork_base32::int;
+//! use clockwork_base32::Error;
+//! use core::num::NonZeroU64;
+//!
+//! let id = NonZeroU64::new(1_234_567_890).unwrap();
+//! let encoded = int::encode_int(id);
+//! assert_eq!(encoded.as_str(), "00000029JR1D4");
+//! assert_eq!(int::decode_int::<NonZeroU64>(&encoded)?, id);
+//! assert_eq!(int::decode_int::<u64>("00000029JR1D4")?, 1_234_567_890);
+//!
+//! let err = int::decode_int::<NonZeroU64>("0000000000000").unwrap_err();
+//! assert!(matches!(err.error(), Error::OutOfRange));
+//! # Ok(())
+//! # }
+//! ```
+
+use core::num::{NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8};
+
+use crate::{
+    capacity_hint_for_decode, find_invalid_symbol, sealed, Error, ParseError, StackEncoder,
+    CLOCKWORK,
+};
+
+/// The length of the longest encoded integer, a `u128`, in symbols.
+pub const MAX_ENCODED_LEN: usize = 26;
+
+/// An unsigned integer type which can be encoded with [`encode_int`] and
+/// decoded with [`decode_int`].
+///
+/// This trait is sealed and implemented for the unsigned integer types and
+/// their `NonZero` counterparts.
+pub trait Integer: Copy + sealed::Sealed {
+    /// The length of the encoded integer in symbols.
+    const ENCODED_LEN: usize;
+
+    #[doc(hidden)]
+    const BYTE_LEN: usize;
+
+    #[doc(hidden)]
+    fn to_u128(self) -> u128;
+
+    #[doc(hidden)]
+    fn from_u128(value: u128) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($ty:ty, $nonzero:ty, $encoded_len:expr;)*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+            impl Integer for $ty {
+                const ENCODED_LEN: usize = $encoded_len;
+                const BYTE_LEN: usize = core::mem::size_of::<$ty>();
+
+                fn to_u128(self) -> u128 {
+                    self as u128
+                }
+
+                fn from_u128(value: u128) -> Option<Self> {
+                    Some(value as $ty)
+                }
+            }
+
+            impl sealed::Sealed for $nonzero {}
+            impl Integer for $nonzero {
+                const ENCODED_LEN: usize = $encoded_len;
+                const BYTE_LEN: usize = core::mem::size_of::<$ty>();
+
+                fn to_u128(self) -> u128 {
+                    self.get() as u128
+                }
+
+                fn from_u128(value: u128) -> Option<Self> {
+                    <$nonzero>::new(value as $ty)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer! {
+    u8, NonZeroU8, 2;
+    u16, NonZeroU16, 4;
+    u32, NonZeroU32, 7;
+    u64, NonZeroU64, 13;
+    u128, NonZeroU128, 26;
+}
+
+/// Encodes an integer to [`Integer::ENCODED_LEN`] symbols.
+pub fn encode_int<T: Integer>(value: T) -> StackEncoder<MAX_ENCODED_LEN> {
+    let bytes = value.to_u128().to_be_bytes();
+    let mut encoder = StackEncoder::new();
+    // The encoder fits the longest integer, so this never fails.
+    let _ = encoder.encode(bytes.get(bytes.len() - T::BYTE_LEN..).unwrap_or_default());
+    encoder
+}
+
+/// Decodes an integer.
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte, doesn't decode to the
+/// size of `T`, or decodes to zero for a `NonZero` integer.
+pub fn decode_int<T: Integer>(s: &str) -> Result<T, ParseError> {
+    find_invalid_symbol(s.as_bytes())?;
+    let len = capacity_hint_for_decode(s.len());
+    if len != T::BYTE_LEN {
+        let error = Error::LengthMismatch {
+            expected: T::BYTE_LEN,
+            actual: len,
+        };
+        return Err(ParseError::new(error, s.len(), None));
+    }
+    let mut bytes = [0; 16];
+    let dest = bytes.get_mut(16 - T::BYTE_LEN..).unwrap_or_default();
+    CLOCKWORK
+        .decode_to_slice(s, dest)
+        .map_err(|err| ParseError::new(err, 0, None))?;
+    T::from_u128(u128::from_be_bytes(bytes))
+        .ok_or_else(|| ParseError::new(Error::OutOfRange, 0, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: Integer + PartialEq + core::fmt::Debug>(values: &[T]) {
+        let mut last = None;
+        for &value in values {
+            let encoded = encode_int(value);
+            assert_eq!(encoded.len(), T::ENCODED_LEN);
+            assert_eq!(decode_int::<T>(&encoded).unwrap(), value);
+            // The values are sorted, and so are their encodings.
+            if let Some(last) = last.replace(encoded.clone()) {
+                assert!(last.as_str() < encoded.as_str());
+            }
+        }
+    }
+
+    #[test]
+    fn test_int() {
+        round_trip(&[0u8, 1, 0x7f, 0xff]);
+        round_trip(&[0u16, 1, 0x100, u16::MAX]);
+        round_trip(&[0u32, 1, 31, 32, u32::MAX]);
+        round_trip(&[0u64, 1, 1 << 40, u64::MAX]);
+        round_trip(&[0u128, 1, 1 << 100, u128::MAX]);
+        round_trip(&[NonZeroU32::MIN, NonZeroU32::MAX]);
+        round_trip(&[NonZeroU64::MIN, NonZeroU64::MAX]);
+        round_trip(&[NonZeroU128::MIN, NonZeroU128::MAX]);
+        assert_eq!(encode_int(u32::MAX).as_str(), "ZZZZZZR");
+
+        assert!(matches!(
+            decode_int::<NonZeroU32>("0000000").unwrap_err().error(),
+            Error::OutOfRange
+        ));
+        let err = decode_int::<u32>("ZZZZZZZZ").unwrap_err();
+        assert!(matches!(
+            err.error(),
+            Error::LengthMismatch {
+                expected: 4,
+                actual: 5
+            }
+        ));
+        let err = decode_int::<u64>("ZZZZZZZZZZZZU").unwrap_err();
+        assert_eq!(err.position(), 12);
+    }
+}
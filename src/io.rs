@@ -0,0 +1,672 @@
+//! Adapters decoding/encoding data streamed through [`std::io::Read`] and
+//! [`std::io::Write`].
+//!
+//! # Examples
+//! ```
+//! # fn main() -> std::io::Result<()> {
+//! use clockwork_base32::io::{DecodeReader, EncodeWriter};
+//! use std::io::{Read, Write};
+//!
+//! let mut writer = EncodeWriter::new(Vec::new());
+//! writer.write_all(b"Hello, ")?;
+//! writer.write_all(b"world!")?;
+//! let encoded = writer.finish()?;
+//! assert_eq!(&encoded, b"91JPRV3F5GG7EVVJDHJ22");
+//!
+//! let mut decoded = String::new();
+//! DecodeReader::new(&encoded[..]).read_to_string(&mut decoded)?;
+//! assert_eq!(&decoded, "Hello, world!");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{self, BufRead, Read, Write};
+use std::mem::ManuallyDrop;
+
+use crate::stream::{ReadBuffer, MAX_FINISH_LEN};
+use crate::{decode_to_vec, Decoder, Encoder, Error, Transcode};
+
+// The size of the buffers used by the adapters.
+const BUF_LEN: usize = 1024;
+
+/// A writer which encodes the written bytes and writes the result to the inner writer.
+pub type EncodeWriter<W> = Writer<Encoder, W>;
+
+/// A writer which decodes the written bytes and writes the result to the inner writer.
+pub type DecodeWriter<W> = Writer<Decoder, W>;
+
+/// A reader which reads bytes from the inner reader and returns the encoded result.
+pub type EncodeReader<R> = Reader<Encoder, R>;
+
+/// A reader which reads bytes from the inner reader and returns the decoded result.
+pub type DecodeReader<R> = Reader<Decoder, R>;
+
+/// A writer which transcodes the written bytes and writes the result to the inner writer.
+///
+/// Use the [`EncodeWriter`] and [`DecodeWriter`] aliases to create one.
+///
+/// The last partial block is written by [`Writer::finish`]. It is also written when
+/// the writer is dropped, but errors are ignored in that case.
+///
+/// The bytes written to the writer and to the inner writer can be limited with
+/// [`Writer::with_input_limit`] and [`Writer::with_output_limit`], for untrusted
+/// uploads.
+#[derive(Debug)]
+pub struct Writer<T: Transcode, W: Write> {
+    transcoder: T,
+    // The inner writer is moved out by `finish`, which sets `finished` so that
+    // `drop` neither writes to it nor drops it then.
+    inner: ManuallyDrop<W>,
+    finished: bool,
+    limits: Limits,
+}
+
+impl<T: Transcode + Default, W: Write> Writer<T, W> {
+    /// Creates a new writer which writes the result to `inner`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            transcoder: T::default(),
+            inner: ManuallyDrop::new(inner),
+            finished: false,
+            limits: Limits::default(),
+        }
+    }
+}
+
+impl<T: Transcode, W: Write> Writer<T, W> {
+    /// Makes writes fail with [`Error::LimitExceeded`] when more than `limit`
+    /// bytes in total would be written to this writer.
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::io::DecodeWriter;
+    /// use std::io::Write;
+    ///
+    /// let mut writer = DecodeWriter::new(Vec::new()).with_input_limit(8);
+    /// assert!(writer.write_all(b"CSQPYRK1").is_ok());
+    /// assert!(writer.write_all(b"91JPRV3F").is_err());
+    /// ```
+    pub fn with_input_limit(mut self, limit: usize) -> Self {
+        self.limits.input.limit = Some(limit);
+        self
+    }
+
+    /// Makes writes fail with [`Error::LimitExceeded`] when more than `limit`
+    /// bytes in total would be written to the inner writer, which never gets
+    /// more than `limit` bytes.
+    pub fn with_output_limit(mut self, limit: usize) -> Self {
+        self.limits.output.limit = Some(limit);
+        self
+    }
+
+    /// Writes the result for the last partial block and returns the inner writer.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the last partial block is invalid or writing to the inner
+    /// writer fails.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_final()?;
+        self.finished = true;
+        // SAFETY: `finished` is set, so `drop` doesn't touch the inner writer
+        // after it is moved out here, and nothing else uses `self` after this.
+        Ok(unsafe { ManuallyDrop::take(&mut self.inner) })
+    }
+
+    /// Returns a reference to the inner writer.
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::io::EncodeWriter;
+    /// use std::io::Write;
+    ///
+    /// let mut writer = EncodeWriter::new(Vec::new());
+    /// writer.write_all(b"fooba").unwrap();
+    /// assert_eq!(writer.get_ref(), b"CSQPYRK1");
+    /// ```
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    fn write_final(&mut self) -> io::Result<()> {
+        let mut output = [0; MAX_FINISH_LEN];
+        let len = self.transcoder.finish(&mut output)?;
+        self.limits.output.add(len)?;
+        self.inner
+            .write_all(output.get(..len).unwrap_or_default())?;
+        self.inner.flush()
+    }
+}
+
+impl<T: Transcode, W: Write> Write for Writer<T, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.limits.input.check(buf.len())?;
+        let mut output = [0; BUF_LEN];
+        let (consumed, written) = self.transcoder.update(buf, &mut output)?;
+        self.limits.input.add(consumed)?;
+        self.limits.output.add(written)?;
+        self.inner
+            .write_all(output.get(..written).unwrap_or_default())?;
+        Ok(consumed)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T: Transcode, W: Write> Drop for Writer<T, W> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.write_final();
+            // SAFETY: The inner writer is not moved out without `finished`, and
+            // is not used after this.
+            unsafe { ManuallyDrop::drop(&mut self.inner) };
+        }
+    }
+}
+
+// Limits holds the optional limits of the bytes going into and out of an
+// adapter, with the numbers of bytes so far.
+#[derive(Debug, Default)]
+struct Limits {
+    input: Limit,
+    output: Limit,
+}
+
+#[derive(Debug, Default)]
+struct Limit {
+    limit: Option<usize>,
+    len: usize,
+}
+
+impl Limit {
+    // check returns Err if `len` more bytes exceed the limit.
+    fn check(&self, len: usize) -> Result<(), Error> {
+        match self.limit {
+            Some(limit) if self.len.saturating_add(len) > limit => {
+                Err(Error::LimitExceeded { limit })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // add counts `len` more bytes, or returns Err if they exceed the limit.
+    fn add(&mut self, len: usize) -> Result<(), Error> {
+        self.check(len)?;
+        self.len += len;
+        Ok(())
+    }
+}
+
+/// A reader which reads bytes from the inner reader and returns the transcoded result.
+///
+/// Use the [`EncodeReader`] and [`DecodeReader`] aliases to create one.
+///
+/// The bytes read from the inner reader and returned by this reader can be
+/// limited with [`Reader::with_input_limit`] and [`Reader::with_output_limit`],
+/// for untrusted uploads.
+#[derive(Debug)]
+pub struct Reader<T: Transcode, R: Read> {
+    transcoder: T,
+    inner: R,
+    buf: ReadBuffer<BUF_LEN>,
+    limits: Limits,
+}
+
+impl<T: Transcode + Default, R: Read> Reader<T, R> {
+    /// Creates a new reader which reads bytes from `inner`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            transcoder: T::default(),
+            inner,
+            buf: ReadBuffer::new(),
+            limits: Limits::default(),
+        }
+    }
+}
+
+impl<T: Transcode, R: Read> Reader<T, R> {
+    /// Makes reads fail with [`Error::LimitExceeded`] when more than `limit`
+    /// bytes in total are read from the inner reader.
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::io::DecodeReader;
+    /// use std::io::Read;
+    ///
+    /// let mut decoded = Vec::new();
+    /// let mut reader = DecodeReader::new(&b"91JPRV3F5GG7EVVJDHJ22"[..]).with_input_limit(16);
+    /// let err = reader.read_to_end(&mut decoded).unwrap_err();
+    /// assert_eq!(err.to_string(), "limit of 16 bytes exceeded");
+    /// ```
+    pub fn with_input_limit(mut self, limit: usize) -> Self {
+        self.limits.input.limit = Some(limit);
+        self
+    }
+
+    /// Makes reads fail with [`Error::LimitExceeded`] when more than `limit`
+    /// bytes in total would be returned.
+    pub fn with_output_limit(mut self, limit: usize) -> Self {
+        self.limits.output.limit = Some(limit);
+        self
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<T: Transcode, R: Read> Read for Reader<T, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if let Some(len) = self.buf.take_output(buf) {
+                self.limits.output.add(len)?;
+                return Ok(len);
+            }
+            let len = match self.buf.input_to_fill() {
+                Some(input) => Some(self.inner.read(input)?),
+                None => None,
+            };
+            self.limits.input.add(len.unwrap_or_default())?;
+            self.buf.transcode(&mut self.transcoder, len)?;
+        }
+    }
+}
+
+/// An iterator which reads lines from the inner reader and decodes each one,
+/// for streaming newline-delimited records without loading the whole input.
+///
+/// Lines end with `\n` or `\r\n`, which are removed before decoding, and an
+/// empty line decodes to no bytes. A line which fails to decode gives an
+/// [`Err`] with [`io::ErrorKind::InvalidInput`], and the iteration continues
+/// with the next line.
+///
+/// # Examples
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use clockwork_base32::io::DecodeLineReader;
+///
+/// let log = "CSQPYRK1\r\n91JPRV3F\nCSQPU\n";
+/// let mut lines = DecodeLineReader::new(log.as_bytes());
+/// assert_eq!(lines.next().transpose()?.as_deref(), Some(&b"fooba"[..]));
+/// assert_eq!(lines.next().transpose()?.as_deref(), Some(&b"Hello"[..]));
+/// assert!(lines.next().unwrap().is_err());
+/// assert!(lines.next().is_none());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DecodeLineReader<R: BufRead> {
+    inner: R,
+    line: Vec<u8>,
+}
+
+impl<R: BufRead> DecodeLineReader<R> {
+    /// Creates a new iterator which reads lines from `inner`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            line: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> Iterator for DecodeLineReader<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<io::Result<Vec<u8>>> {
+        self.line.clear();
+        match self.inner.read_until(b'\n', &mut self.line) {
+            Ok(0) => None,
+            Ok(_) => {
+                let line = self.line.strip_suffix(b"\n").unwrap_or(&self.line);
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                Some(decode_to_vec(line).map_err(io::Error::from))
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// A writer which writes all bytes to two writers, to encode data once for
+/// several destinations.
+///
+/// Each write is written entirely to the first writer and then to the second
+/// one, retrying partial writes. If either fails, the error is returned and the
+/// writers may have received different data, so the tee should be dropped.
+/// Nest tees for more destinations.
+///
+/// # Examples
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use clockwork_base32::io::{EncodeWriter, Tee};
+/// use std::io::Write;
+///
+/// let mut writer = EncodeWriter::new(Tee::new(Vec::new(), Tee::new(Vec::new(), Vec::new())));
+/// writer.write_all(b"Hello, world!")?;
+/// let (a, rest) = writer.finish()?.into_inner();
+/// let (b, c) = rest.into_inner();
+/// assert_eq!(&a, b"91JPRV3F5GG7EVVJDHJ22");
+/// assert_eq!(a, b);
+/// assert_eq!(a, c);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Tee<A: Write, B: Write> {
+    first: A,
+    second: B,
+}
+
+impl<A: Write, B: Write> Tee<A, B> {
+    /// Creates a new writer which writes to `first` and `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns references to the inner writers.
+    pub fn get_ref(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    /// Returns the inner writers.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: Write, B: Write> Write for Tee<A, B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.first.write_all(buf)?;
+        self.second.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Flush the second writer even if the first one fails.
+        let first = self.first.flush();
+        self.second.flush()?;
+        first
+    }
+}
+
+/// A writer which encodes the written bytes to the inner writer and hashes them
+/// with a [`digest::Digest`], for the encoded output and the checksum of the
+/// data in a single pass.
+///
+/// This is available with the `digest` feature.
+///
+/// # Examples
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use clockwork_base32::io::DigestEncodeWriter;
+/// use sha2::{Digest, Sha256};
+/// use std::io::Write;
+///
+/// let mut writer = DigestEncodeWriter::<_, Sha256>::new(Vec::new());
+/// writer.write_all(b"Hello, world!")?;
+/// let (encoded, hash) = writer.finish()?;
+/// assert_eq!(&encoded, b"91JPRV3F5GG7EVVJDHJ22");
+/// assert_eq!(hash, Sha256::digest(b"Hello, world!"));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "digest")]
+#[derive(Debug)]
+pub struct DigestEncodeWriter<W: Write, D: digest::Digest> {
+    writer: EncodeWriter<W>,
+    digest: D,
+}
+
+#[cfg(feature = "digest")]
+impl<W: Write, D: digest::Digest> DigestEncodeWriter<W, D> {
+    /// Creates a new writer which writes the encoded result to `inner`.
+    pub fn new(inner: W) -> Self {
+        Self::with_digest(inner, D::new())
+    }
+
+    /// Creates a new writer which writes the encoded result to `inner` and
+    /// updates `digest`, which may already hold some data.
+    pub fn with_digest(inner: W, digest: D) -> Self {
+        Self {
+            writer: EncodeWriter::new(inner),
+            digest,
+        }
+    }
+
+    /// Writes the result for the last partial block and returns the inner
+    /// writer and the hash of the written bytes.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if writing to the inner writer fails.
+    pub fn finish(self) -> io::Result<(W, digest::Output<D>)> {
+        let inner = self.writer.finish()?;
+        Ok((inner, self.digest.finalize()))
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<W: Write, D: digest::Digest> Write for DigestEncodeWriter<W, D> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.writer.write(buf)?;
+        self.digest.update(buf.get(..len).unwrap_or_default());
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_to_vec;
+
+    // A writer which accepts at most 3 bytes per write.
+    struct ShortWriter(Vec<u8>);
+
+    impl Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let len = buf.len().min(3);
+            self.0.extend_from_slice(&buf[..len]);
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let plain: Vec<u8> = (0..5000).map(|i| (i * 7) as u8).collect();
+        let encoded = encode_to_vec(&plain);
+
+        let mut writer = EncodeWriter::new(ShortWriter(Vec::new()));
+        for chunk in plain.chunks(7) {
+            writer.write_all(chunk).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap().0, encoded);
+
+        let mut writer = DecodeWriter::new(Vec::new());
+        for chunk in encoded.chunks(11) {
+            writer.write_all(chunk).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap(), plain);
+
+        let mut result = Vec::new();
+        EncodeReader::new(&plain[..])
+            .read_to_end(&mut result)
+            .unwrap();
+        assert_eq!(result, encoded);
+
+        let mut result = Vec::new();
+        DecodeReader::new(&encoded[..])
+            .read_to_end(&mut result)
+            .unwrap();
+        assert_eq!(result, plain);
+    }
+
+    #[test]
+    fn test_finish_on_drop() {
+        let mut encoded = Vec::new();
+        {
+            let mut writer = EncodeWriter::new(&mut encoded);
+            writer.write_all(b"f").unwrap();
+        }
+        assert_eq!(&encoded, b"CR");
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut result = Vec::new();
+        let err = DecodeReader::new(&b"CSQPU"[..])
+            .read_to_end(&mut result)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(matches!(
+            err.get_ref().and_then(|e| e.downcast_ref::<Error>()),
+            Some(Error::InvalidSymbol { byte: b'U', .. })
+        ));
+    }
+
+    fn limit_of(err: &io::Error) -> Option<usize> {
+        match err.get_ref().and_then(|e| e.downcast_ref::<Error>()) {
+            Some(Error::LimitExceeded { limit }) => Some(*limit),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let plain: Vec<u8> = (0..=255).collect();
+        let encoded = encode_to_vec(&plain);
+
+        let mut result = Vec::new();
+        let mut reader = DecodeReader::new(&encoded[..]).with_input_limit(encoded.len());
+        reader.read_to_end(&mut result).unwrap();
+        assert_eq!(result, plain);
+        let mut reader = DecodeReader::new(&encoded[..]).with_input_limit(encoded.len() - 1);
+        let err = reader.read_to_end(&mut result).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(limit_of(&err), Some(encoded.len() - 1));
+        let mut reader = EncodeReader::new(&plain[..]).with_output_limit(100);
+        let err = reader.read_to_end(&mut result).unwrap_err();
+        assert_eq!(limit_of(&err), Some(100));
+
+        let mut writer = EncodeWriter::new(Vec::new()).with_output_limit(encoded.len());
+        writer.write_all(&plain).unwrap();
+        assert_eq!(writer.finish().unwrap(), encoded);
+        let mut writer = EncodeWriter::new(Vec::new()).with_output_limit(encoded.len() - 1);
+        writer.write_all(&plain).unwrap();
+        assert_eq!(
+            limit_of(&writer.finish().unwrap_err()),
+            Some(encoded.len() - 1)
+        );
+        let mut writer = DecodeWriter::new(Vec::new()).with_input_limit(10);
+        writer.write_all(b"CSQPY").unwrap();
+        writer.write_all(b"RK1").unwrap();
+        let err = writer.write_all(b"CSQ").unwrap_err();
+        assert_eq!(limit_of(&err), Some(10));
+    }
+
+    #[test]
+    fn test_decode_line_reader() {
+        let plain: Vec<Vec<u8>> = (0..300).map(|len| vec![0xa5; len]).collect();
+        let mut input = Vec::new();
+        for (i, bytes) in plain.iter().enumerate() {
+            input.extend(encode_to_vec(bytes));
+            input.extend_from_slice(if i % 2 == 0 { b"\n" } else { b"\r\n" });
+        }
+        // The last line may have no line break.
+        input.extend_from_slice(b"CSQPY");
+        let lines = DecodeLineReader::new(io::BufReader::with_capacity(16, &input[..]));
+        let decoded: Vec<Vec<u8>> = lines.collect::<io::Result<_>>().unwrap();
+        assert_eq!(decoded.len(), 301);
+        assert_eq!(decoded[..300], plain[..]);
+        assert_eq!(decoded[300], b"foo");
+
+        let mut lines = DecodeLineReader::new(&b"CSQPU\n\n\r\nCSQPY"[..]);
+        let err = lines.next().unwrap().unwrap_err();
+        assert!(matches!(
+            err.get_ref().and_then(|e| e.downcast_ref::<Error>()),
+            Some(Error::InvalidSymbol { byte: b'U', .. })
+        ));
+        assert_eq!(lines.next().unwrap().unwrap(), b"");
+        assert_eq!(lines.next().unwrap().unwrap(), b"");
+        assert_eq!(lines.next().unwrap().unwrap(), b"foo");
+        assert!(lines.next().is_none());
+    }
+
+    // A writer which fails after accepting `0` bytes.
+    struct FailingWriter(usize);
+
+    impl Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.0 == 0 {
+                return Err(io::Error::other("disk full"));
+            }
+            let len = buf.len().min(self.0);
+            self.0 -= len;
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_tee() {
+        let plain: Vec<u8> = (0..5000).map(|i| (i * 7) as u8).collect();
+        let tee = Tee::new(
+            ShortWriter(Vec::new()),
+            Tee::new(Vec::new(), ShortWriter(Vec::new())),
+        );
+        let mut writer = EncodeWriter::new(tee);
+        for chunk in plain.chunks(7) {
+            writer.write_all(chunk).unwrap();
+        }
+        let (a, rest) = writer.finish().unwrap().into_inner();
+        let (b, c) = rest.into_inner();
+        let encoded = encode_to_vec(&plain);
+        assert_eq!(a.0, encoded);
+        assert_eq!(b, encoded);
+        assert_eq!(c.0, encoded);
+
+        let mut writer = EncodeWriter::new(Tee::new(Vec::new(), FailingWriter(100)));
+        let err = writer.write_all(&plain).unwrap_err();
+        assert_eq!(err.to_string(), "disk full");
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn test_digest() {
+        use sha2::{Digest, Sha256};
+        let plain: Vec<u8> = (0..5000).map(|i| (i * 7) as u8).collect();
+        let mut writer = DigestEncodeWriter::<_, Sha256>::new(ShortWriter(Vec::new()));
+        for chunk in plain.chunks(2000) {
+            writer.write_all(chunk).unwrap();
+        }
+        let (inner, hash) = writer.finish().unwrap();
+        assert_eq!(inner.0, encode_to_vec(&plain));
+        assert_eq!(hash, Sha256::digest(&plain));
+    }
+}
@@ -0,0 +1,249 @@
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{
+    capacity_hint_for_decode, ct, Error, IntoInput, Result, BLOCK_BYTE_LEN, BLOCK_SYMBOL_LEN,
+};
+
+/// A key of exactly `N` bytes, like a 16 or 32 bytes symmetric key.
+///
+/// Parsing fails unless the input decodes to exactly `N` bytes, so the length
+/// is checked once at the boundary and guaranteed by the type afterwards.
+/// Parsing and displaying use the constant-time functions in [`ct`], equality
+/// is checked in constant time, and the bytes are not shown by
+/// [`Debug`](fmt::Debug). This works without the `alloc` feature.
+///
+/// With the `zeroize` feature enabled, this implements `Zeroize` and
+/// `ZeroizeOnDrop`, so the bytes are wiped when dropped. With the `subtle`
+/// feature enabled, this implements `ConstantTimeEq`. With the `serde` feature
+/// enabled, this implements `Serialize` and `Deserialize`, as encoded text for
+/// human-readable formats and as bytes otherwise. With the `defmt` feature
+/// enabled, this implements `defmt::Format`, which hides the bytes like
+/// [`Debug`](fmt::Debug).
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32::{Error, Key};
+///
+/// let key: Key<16> = "91JPRV3F5GG7EVVJDHJ22SKFDW".parse()?;
+/// assert_eq!(key.as_bytes(), b"Hello, world!foo");
+/// assert_eq!(key.to_string(), "91JPRV3F5GG7EVVJDHJ22SKFDW");
+/// assert_eq!(format!("{:?}", key), "Key<16>([REDACTED])");
+///
+/// assert!(matches!(
+///     "91JPRV3F".parse::<Key<16>>(),
+///     Err(Error::LengthMismatch { expected: 16, actual: 5 })
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Key<const N: usize>([u8; N]);
+
+impl<const N: usize> Key<N> {
+    /// Creates a key holding `bytes`.
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+
+    /// Decodes the input to a new key in constant time.
+    ///
+    /// The length of the input is not secret, so it is checked first.
+    ///
+    /// # Errors
+    /// Returns [`Error::LengthMismatch`] if the input doesn't decode to `N`
+    /// bytes, or [`Error::InvalidSymbols`] if it contains an invalid symbol.
+    pub fn decode<'a, I>(input: I) -> Result<Self>
+    where
+        I: IntoInput<'a>,
+    {
+        let input = input.into_input();
+        let len = capacity_hint_for_decode(input.len());
+        if len != N {
+            return Err(Error::LengthMismatch {
+                expected: N,
+                actual: len,
+            });
+        }
+        let mut bytes = [0; N];
+        ct::decode_to_slice(input, &mut bytes)?;
+        Ok(Self(bytes))
+    }
+
+    /// Returns the bytes of the key.
+    pub const fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Key<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl<const N: usize> FromStr for Key<N> {
+    type Err = Error;
+
+    /// Decodes a string in constant time.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::decode(s)
+    }
+}
+
+impl<const N: usize> fmt::Display for Key<N> {
+    /// Writes the encoded key, encoding in constant time.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut symbols = [0; BLOCK_SYMBOL_LEN];
+        for block in self.0.chunks(BLOCK_BYTE_LEN) {
+            let len = ct::encode_to_slice(block, &mut symbols).map_err(|_| fmt::Error)?;
+            let symbols = symbols.get(..len).unwrap_or_default();
+            // The symbols are ASCII, so this never fails.
+            f.write_str(core::str::from_utf8(symbols).unwrap_or_default())?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> fmt::Debug for Key<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Key<{}>([REDACTED])", N)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for Key<N> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "Key<{=usize}>([REDACTED])", N);
+    }
+}
+
+impl<const N: usize> PartialEq for Key<N> {
+    /// Compares the bytes in constant time.
+    fn eq(&self, other: &Self) -> bool {
+        let diff = self
+            .0
+            .iter()
+            .zip(other.0.iter())
+            .fold(0, |acc, (a, b)| acc | (a ^ b));
+        core::hint::black_box(diff) == 0
+    }
+}
+
+impl<const N: usize> Eq for Key<N> {}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> ::serde::Serialize for Key<N> {
+    fn serialize<S: ::serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> ::serde::Deserialize<'de> for Key<N> {
+    fn deserialize<D: ::serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        struct KeyVisitor<const N: usize>;
+
+        impl<const N: usize> ::serde::de::Visitor<'_> for KeyVisitor<N> {
+            type Value = Key<N>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "an encoded key of {} bytes", N)
+            }
+
+            fn visit_str<E: ::serde::de::Error>(self, v: &str) -> core::result::Result<Key<N>, E> {
+                Key::decode(v).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: ::serde::de::Error>(
+                self,
+                v: &[u8],
+            ) -> core::result::Result<Key<N>, E> {
+                <[u8; N] as core::convert::TryFrom<&[u8]>>::try_from(v)
+                    .map(Key)
+                    .map_err(|_| E::invalid_length(v.len(), &self))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(KeyVisitor)
+        } else {
+            deserializer.deserialize_bytes(KeyVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl<const N: usize> ::subtle::ConstantTimeEq for Key<N> {
+    fn ct_eq(&self, other: &Self) -> ::subtle::Choice {
+        ::subtle::ConstantTimeEq::ct_eq(self.0.as_slice(), other.0.as_slice())
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const N: usize> ::zeroize::Zeroize for Key<N> {
+    fn zeroize(&mut self) {
+        ::zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const N: usize> Drop for Key<N> {
+    fn drop(&mut self) {
+        ::zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const N: usize> ::zeroize::ZeroizeOnDrop for Key<N> {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::encode_to_string;
+
+    #[test]
+    fn test_key() {
+        let bytes: [u8; 32] = core::array::from_fn(|i| i as u8 * 7);
+        let encoded = encode_to_string(&bytes);
+        let key: Key<32> = encoded.parse().unwrap();
+        assert_eq!(key.as_bytes(), &bytes);
+        assert_eq!(key.to_string(), encoded);
+        assert_eq!(encoded.to_lowercase().parse::<Key<32>>().unwrap(), key);
+        assert!(key != Key::new([0; 32]));
+        assert_eq!(Key::<0>::decode("").unwrap().to_string(), "");
+        assert_eq!(Key::from([0xff; 3]).to_string(), "ZZZZY");
+
+        assert!(matches!(
+            "ZZZZZ".parse::<Key<16>>(),
+            Err(Error::LengthMismatch {
+                expected: 16,
+                actual: 3
+            })
+        ));
+        assert!(matches!(
+            Key::<3>::decode("ZZZZU"),
+            Err(Error::InvalidSymbols)
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let key = Key::new(*b"fooba");
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, r#""CSQPYRK1""#);
+        assert_eq!(serde_json::from_str::<Key<5>>(&json).unwrap(), key);
+        assert!(serde_json::from_str::<Key<4>>(&json).is_err());
+    }
+}
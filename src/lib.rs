@@ -9,22 +9,62 @@
 //! You can encode bytes to a [`String`] with [`encode_to_string`]:
 //!
 //! ```
+//! # #[cfg(feature = "alloc")]
+//! # {
 //! use clockwork_base32 as base32;
 //! let encoded = base32::encode_to_string(b"Hello, world!");
 //! assert_eq!(&encoded, "91JPRV3F5GG7EVVJDHJ22");
+//! # }
 //! ```
 //!
 //! You can decode bytes to a [`String`] with [`decode_to_string`]:
 //!
 //! ```
-//! # fn main() -> std::io::Result<()> {
+//! # #[cfg(feature = "alloc")]
+//! # fn main() -> Result<(), clockwork_base32::Error> {
 //! use clockwork_base32 as base32;
 //! let decoded = base32::decode_to_string(b"91JPRV3F5GG7EVVJDHJ22")?;
 //! assert_eq!(&decoded, "Hello, world!");
 //! # Ok(())
 //! # }
+//! # #[cfg(not(feature = "alloc"))]
+//! # fn main() {}
+//! ```
+//!
+//! The same operations are methods of the [`CLOCKWORK`] static, which is the
+//! primary API going forward. The free functions are thin wrappers around it.
+//!
+//! ```
+//! # #[cfg(feature = "alloc")]
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32::CLOCKWORK;
+//! let encoded = CLOCKWORK.encode(b"Hello, world!");
+//! assert_eq!(CLOCKWORK.decode(&encoded)?, b"Hello, world!");
+//! assert!(CLOCKWORK.validate("CSQPU").is_err());
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "alloc"))]
+//! # fn main() {}
 //! ```
 //!
+//! # Features
+//! The `std` feature is enabled by default. Without it, the crate is `no_std` and
+//! only depends on `alloc`. Without the `alloc` feature as well, only the functions
+//! decoding/encoding into a caller provided buffer are available:
+//! * [`decode_to_slice`]
+//! * [`encode_to_slice`]
+//!
+//! [`StackEncoder`] keeps the encoded result in an internal array and exposes it
+//! as a [`str`].
+//! [`Key`] holds a key of exactly `N` decoded bytes, checked when parsing.
+//! [`display`] formats bytes encoded, truncated to the precision of the format
+//! spec, like `{:.8}` for short ID prefixes in logs.
+//!
+//! # Panics
+//! No function in this crate panics, whatever the input is. Undersized destination
+//! buffers and invalid input are reported as [`Error`] values. This is enforced by
+//! denying the clippy lints for panicking constructs such as indexing, `unwrap`
+//! and `expect` in the library code.
 //!
 //! # High level functions
 //! These functions decode/encode bytes and return a new [`String`] or [`Vec<u8>`] as the result.
@@ -33,6 +73,24 @@
 //! * [`decode_to_vec`]
 //! * [`encode_to_vec`]
 //!
+//! They accept any iterator of bytes, like byte slices, byte arrays, `&Vec<u8>` and
+//! iterator adapters such as `bytes.iter().take(n)`. The bytes are taken in chunks
+//! to a stack buffer and processed with the block based fast path which handles
+//! 5 bytes (8 symbols) at a time. The methods of [`CLOCKWORK`] also accept [`str`]
+//! and [`String`], see [`IntoInput`].
+//!
+//! With the `smallvec` feature enabled, these functions return a [`smallvec::SmallVec`]
+//! which keeps short results inline instead of allocating on the heap.
+//! * `decode_to_smallvec`
+//! * `encode_to_smallvec`
+//!
+//! With the `portable-simd` feature enabled on a nightly compiler, the blocks are
+//! decoded/encoded with the portable SIMD API instead of the scalar code.
+//!
+//! With the `compact-decode` feature enabled, symbols are decoded with range checks
+//! instead of a 256 bytes lookup table. This is slower but makes the binary smaller,
+//! unless the `portable-simd` feature, which uses the table, is enabled too.
+//!
 //! # Low level functions
 //! These functions take a [`String`] or [`Vec<u8>`] argument for the destination
 //! and append the decoded/encoded result to it.
@@ -41,25 +99,498 @@
 //! * [`append_encoded_to_string`]
 //! * [`append_encoded_to_vec`]
 //!
+//! With the `heapless` feature enabled, these functions append the result to
+//! a fixed capacity [`heapless::String`] or [`heapless::Vec`] and return [`Err`]
+//! instead of panicking when the result does not fit.
+//! * `append_decoded_to_heapless_string`
+//! * `append_decoded_to_heapless_vec`
+//! * `append_encoded_to_heapless_string`
+//! * `append_encoded_to_heapless_vec`
+//!
+//! With the `arrayvec` feature enabled, these functions do the same for
+//! [`arrayvec::ArrayString`] and [`arrayvec::ArrayVec`].
+//! * `append_decoded_to_array_string`
+//! * `append_decoded_to_array_vec`
+//! * `append_encoded_to_array_string`
+//! * `append_encoded_to_array_vec`
+//!
 //! These functions can be used to calculate the capacity for the decode/encode result
 //! beforehand.
 //! * [`capacity_hint_for_decode`]
 //! * [`capacity_hint_for_encode`]
+//!
+//! # Streaming
+//! [`Encoder`] and [`Decoder`] are resumable state machines which take input in chunks
+//! and write the result to caller provided buffers.
+//!
+//! They are wrapped by adapters implementing the reader and writer traits:
+//! * `io`: [`std::io::Read`] and [`std::io::Write`], with the `std` feature.
+//! * `embedded_io`: the `embedded-io` traits, with the `embedded-io` feature.
+//! * `embedded_io_async`: the `embedded-io-async` traits, with the `embedded-io-async` feature.
+//!
+//! The `io` readers and writers take optional limits of the bytes going in and out
+//! with `with_input_limit` and `with_output_limit`, failing with
+//! [`Error::LimitExceeded`] beyond them, for untrusted uploads.
+//!
+//! `io::DecodeLineReader` iterates over the decoded lines of a [`std::io::BufRead`],
+//! for newline-delimited records like logs.
+//!
+//! `fs::encode_file` and `fs::decode_file` stream a file through them into a
+//! destination which is replaced atomically, with the `std` feature. With the
+//! `memmap2` feature, `fs::encode_file_mmap` and `fs::decode_file_mmap` do the
+//! same through memory mappings for huge files.
+//!
+//! With the `bbqueue` feature, `ring::RingEncoder` writes the encoded result into
+//! the grants of a ring buffer like a `bbqueue` producer without intermediate copies.
+//!
+//! [`config::Decoder`] takes its options, strict canonical input and skipping
+//! whitespace, as const generic parameters so disabled checks compile away.
+//!
+//! [`chunk::encode_chunks`] yields the encoded result in fixed-size groups of
+//! symbols, like one group per frame of a packet protocol, encoding as it goes.
+//!
+//! # Secrets
+//! [`ct`] has encoding and decoding functions whose timing does not depend on the
+//! data, for API keys and OTP seeds. `otp` converts the RFC 4648 secrets of
+//! `otpauth://` URIs to key bytes and back, with the `alloc` feature.
+//!
+//! [`Clockwork::encode_padded`] pads the payload up to a fixed or bucketed
+//! output length given by [`pad::Padding`], so the length of the output doesn't
+//! tell the length of the payload.
+//!
+//! # Validation
+//! [`Clockwork::validate`] and [`Clockwork::validate_canonical`] check whole
+//! inputs. [`validator::Validator`] checks input given in chunks without
+//! decoding it, reporting every problem with its position, the decoded length
+//! and whether the input is canonical.
+//!
+//! # Custom alphabets
+//! An [`engine::Engine`] encodes and decodes with another alphabet and options
+//! like padding. [`new_engine!`] defines one as a `static`, checking the alphabet
+//! at compile time.
+//!
+//! # Validated strings
+//! [`Base32String`] is an owned string which is always a canonical encoding, for
+//! APIs taking already validated encoded text. [`Base32Bytes`] holds the decoded
+//! bytes instead and is displayed as an encoded string.
+//!
+//! Both implement [`FromStr`](core::str::FromStr) with [`ParseError`], which tells
+//! the position of the problem and how to fix it, so they can be used as argument
+//! types with `clap` as is.
+//!
+//! With the `schemars` feature enabled, both implement `schemars::JsonSchema` as
+//! strings with a pattern constraint.
+//!
+//! With the `sqlx` feature enabled, both implement the `sqlx` `Type`, `Encode` and
+//! `Decode` traits for any database whose TEXT columns map to [`String`], so they can
+//! be bound and fetched as encoded strings.
+//!
+//! With the `diesel` feature enabled, both can be used as Diesel model fields for
+//! Text columns. Binding [`Base32Bytes`] is supported for PostgreSQL and MySQL only;
+//! bind [`Base32Bytes::encode`] on SQLite.
+//!
+//! With the `rusqlite` feature enabled, both implement `rusqlite`'s `ToSql` and
+//! `FromSql` to be stored in TEXT columns.
+//!
+//! # Derive
+//! With the `derive` feature enabled, `#[derive(Base32)]` implements `Display`,
+//! `Debug` and `FromStr` for newtypes over `[u8; N]`, [`Vec<u8>`] or an unsigned
+//! integer like `NonZeroU64`, plus `Serialize` and `Deserialize` if the `serde`
+//! feature is enabled too.
+//!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use clockwork_base32::Base32;
+//!
+//! #[derive(Base32, Clone, PartialEq)]
+//! struct UserId([u8; 5]);
+//!
+//! let id: UserId = "CSQPYRK1".parse().unwrap();
+//! assert_eq!(id.0, *b"fooba");
+//! assert_eq!(id.to_string(), "CSQPYRK1");
+//! assert_eq!(format!("{:?}", id), "UserId(\"CSQPYRK1\")");
+//! assert!("CSQPYR".parse::<UserId>().is_err());
+//! # }
+//! ```
+//!
+//! # Serde
+//! With the `serde` feature enabled, the `serde` module can be used as
+//! `#[serde(with = "clockwork_base32::serde")]` on [`Vec<u8>`] fields to serialize
+//! them as encoded strings in human readable formats and as raw bytes in binary
+//! formats.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "portable-simd", feature(portable_simd))]
+// The public API never panics on any input, see "Panics" in the crate documentation.
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::expect_used,
+        clippy::indexing_slicing,
+        clippy::panic,
+        clippy::todo,
+        clippy::unimplemented,
+        clippy::unreachable,
+        clippy::unwrap_used
+    )
+)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+mod error;
+pub use error::{Error, ErrorKind, ParseError, Result};
+
+#[cfg(feature = "derive")]
+pub use clockwork_base32_derive::Base32;
+
+// The generated code refers to this crate by name, which is only in scope in
+// this crate's own tests with this.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as clockwork_base32;
+
+// Items used by the code generated by the derive macro.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    use core::fmt;
+
+    #[cfg(feature = "serde")]
+    pub use ::serde;
+
+    use crate::int::{encode_int, Integer};
+    use crate::{capacity_hint_for_decode, find_invalid_symbol, Error, ParseError};
+
+    pub fn fmt_encoded(bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::fmt_encoded(bytes, f)
+    }
+
+    pub fn fmt_debug(name: &str, bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(\"", name)?;
+        crate::fmt_encoded(bytes, f)?;
+        f.write_str("\")")
+    }
+
+    pub fn decode_array<const N: usize>(s: &str) -> Result<[u8; N], ParseError> {
+        find_invalid_symbol(s.as_bytes())?;
+        let len = capacity_hint_for_decode(s.len());
+        if len != N {
+            let error = Error::LengthMismatch {
+                expected: N,
+                actual: len,
+            };
+            return Err(ParseError::new(error, s.len(), None));
+        }
+        let mut bytes = [0; N];
+        crate::CLOCKWORK
+            .decode_to_slice(s, &mut bytes)
+            .map_err(|err| ParseError::new(err, 0, None))?;
+        Ok(bytes)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn decode_vec(s: &str) -> Result<Vec<u8>, ParseError> {
+        find_invalid_symbol(s.as_bytes())?;
+        crate::CLOCKWORK
+            .decode(s)
+            .map_err(|err| ParseError::new(err, 0, None))
+    }
+
+    pub fn fmt_int<T: Integer>(value: T, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&encode_int(value))
+    }
+
+    pub fn fmt_debug_int<T: Integer>(
+        name: &str,
+        value: T,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}(\"{}\")", name, &*encode_int(value))
+    }
+
+    pub fn decode_int<T: Integer>(s: &str) -> Result<T, ParseError> {
+        crate::int::decode_int(s)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn serialize_int<T, S>(value: T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Integer,
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&encode_int(value))
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn deserialize_int<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Integer,
+        D: serde::Deserializer<'de>,
+    {
+        let s = <alloc::string::String as serde::Deserialize>::deserialize(deserializer)?;
+        crate::int::decode_int(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+mod clockwork;
+#[cfg(feature = "alloc")]
+pub use clockwork::Recovered;
+pub use clockwork::{Clockwork, CLOCKWORK};
+
+mod stack;
+pub use stack::StackEncoder;
+
+mod key;
+pub use key::Key;
+
+mod display;
+pub use display::{display, DisplayEncoded};
+
+mod url;
+pub use url::UrlSafe;
+
+mod snippet;
+pub use snippet::Snippet;
+
+mod stream;
+pub use stream::{Decoder, Encoder, Transcode};
+
+#[cfg(feature = "alloc")]
+mod bytes;
+#[cfg(feature = "alloc")]
+pub use bytes::Base32Bytes;
+
+#[cfg(feature = "alloc")]
+mod string;
+#[cfg(feature = "alloc")]
+pub use string::Base32String;
+
+pub mod prelude;
+
+#[cfg(feature = "std")]
+pub mod io;
+
+#[cfg(feature = "std")]
+pub mod fs;
+
+#[cfg(feature = "std")]
+pub mod transcode;
+
+#[cfg(feature = "bbqueue")]
+pub mod ring;
+
+pub mod chunk;
+
+#[cfg(feature = "alloc")]
+pub mod pad;
+
+pub mod config;
+
+pub mod validator;
+
+pub mod ct;
+
+pub mod engine;
+
+#[cfg(feature = "alloc")]
+mod rfc4648;
+
+#[cfg(feature = "alloc")]
+pub mod otp;
+
+#[cfg(feature = "alloc")]
+pub mod compat;
+
+#[cfg(feature = "alloc")]
+pub mod dns;
+
+pub mod qr;
+
+pub mod prefix;
 
-use std::io::{Error, ErrorKind, Result};
+pub mod phonetic;
+
+pub mod hwaddr;
+
+pub mod net;
+
+pub mod int;
+
+#[cfg(feature = "alloc")]
+pub mod filename;
+
+#[cfg(feature = "alloc")]
+pub mod batch;
+
+#[cfg(feature = "alloc")]
+pub mod coupon;
+
+#[cfg(feature = "alloc")]
+pub mod token;
+
+#[cfg(feature = "secrecy")]
+mod secret;
+#[cfg(feature = "secrecy")]
+pub use secret::SecretBase32;
+
+#[cfg(feature = "embedded-io")]
+pub mod embedded_io;
+
+#[cfg(feature = "embedded-io-async")]
+pub mod embedded_io_async;
+
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "generate")]
+pub mod generate;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "python")]
+pub mod python;
+
+#[cfg(feature = "napi")]
+pub mod napi;
+
+#[cfg(feature = "proptest")]
+pub mod proptest;
+
+#[cfg(feature = "reference")]
+pub mod reference;
+
+#[cfg(feature = "data-encoding")]
+pub mod data_encoding;
+
+#[cfg(feature = "http")]
+pub mod http;
+
+#[cfg(feature = "axum")]
+pub mod axum;
+
+#[cfg(feature = "sqlx")]
+mod sqlx;
+
+#[cfg(feature = "diesel")]
+mod diesel;
+
+#[cfg(feature = "rusqlite")]
+mod rusqlite;
+
+#[cfg(feature = "portable-simd")]
+mod simd;
+#[cfg(feature = "portable-simd")]
+use simd::{decode_block, encode_block};
 
 const DECODED_BIT_LEN: usize = 5;
 const BYTE_BIT_LEN: usize = 8;
 
+// The size of the stack buffer used to append decoded bytes to a String.
+#[cfg(feature = "alloc")]
+const STRING_CHUNK_LEN: usize = 64;
+
+// A block is the smallest unit where the bytes and the symbols are aligned.
+const BLOCK_BYTE_LEN: usize = 5;
+const BLOCK_SYMBOL_LEN: usize = 8;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Types which can be passed to the methods of [`Clockwork`] as the input.
+///
+/// This trait is sealed and implemented for byte slices, byte arrays, [`Vec<u8>`],
+/// [`str`], [`String`] and [`core::slice::Iter<u8>`]. All of these types hold their
+/// bytes in a contiguous memory, so they are decoded/encoded in place with the fast
+/// path, and positions of problems can be reported.
+///
+/// The free functions at the crate root like [`decode_to_vec`] take any iterator of
+/// bytes instead.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # {
+/// use clockwork_base32::CLOCKWORK;
+/// let input = vec![b'f', b'o', b'o'];
+/// assert_eq!(&CLOCKWORK.encode(&input), "CSQPY");
+/// assert_eq!(&CLOCKWORK.encode(input.iter()), "CSQPY");
+/// assert_eq!(&CLOCKWORK.encode("foo"), "CSQPY");
+/// # }
+/// ```
+pub trait IntoInput<'a>: sealed::Sealed {
+    #[doc(hidden)]
+    fn into_input(self) -> &'a [u8];
+}
+
+impl sealed::Sealed for &[u8] {}
+impl<'a> IntoInput<'a> for &'a [u8] {
+    fn into_input(self) -> &'a [u8] {
+        self
+    }
+}
+
+impl<const N: usize> sealed::Sealed for &[u8; N] {}
+impl<'a, const N: usize> IntoInput<'a> for &'a [u8; N] {
+    fn into_input(self) -> &'a [u8] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl sealed::Sealed for &Vec<u8> {}
+#[cfg(feature = "alloc")]
+impl<'a> IntoInput<'a> for &'a Vec<u8> {
+    fn into_input(self) -> &'a [u8] {
+        self
+    }
+}
+
+impl sealed::Sealed for &str {}
+impl<'a> IntoInput<'a> for &'a str {
+    fn into_input(self) -> &'a [u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl sealed::Sealed for &String {}
+#[cfg(feature = "alloc")]
+impl<'a> IntoInput<'a> for &'a String {
+    fn into_input(self) -> &'a [u8] {
+        self.as_bytes()
+    }
+}
+
+impl sealed::Sealed for core::slice::Iter<'_, u8> {}
+impl<'a> IntoInput<'a> for core::slice::Iter<'a, u8> {
+    fn into_input(self) -> &'a [u8] {
+        self.as_slice()
+    }
+}
+
 /// Decodes bytes and returns the result as a new [`String`].
 ///
 /// # Errors
-/// Returns [`Err`] if the input contains a invalid byte.
+/// Returns [`Err`] if the input contains a invalid byte or the decoded bytes
+/// are not valid UTF-8.
 ///
 /// # Examples
 /// Basic usage:
 /// ```
-/// # fn main() -> std::io::Result<()> {
+/// # fn main() -> Result<(), clockwork_base32::Error> {
 /// use clockwork_base32::decode_to_string;
 /// let decoded = decode_to_string(b"91JPRV3F5GG7EVVJDHJ22")?;
 /// assert_eq!(&decoded, "Hello, world!");
@@ -68,7 +599,7 @@ const BYTE_BIT_LEN: usize = 8;
 /// ```
 /// If your input is a [`str`], you can convert it to bytes using [`str::as_bytes`].
 /// ```
-/// # fn main() -> std::io::Result<()> {
+/// # fn main() -> Result<(), clockwork_base32::Error> {
 /// use clockwork_base32 as base32;
 /// let input = String::from("91JPRV3F5GG7EVVJDHJ22");
 /// let decoded = base32::decode_to_string(input.as_bytes())?;
@@ -76,14 +607,41 @@ const BYTE_BIT_LEN: usize = 8;
 /// # Ok(())
 /// # }
 /// ```
+#[cfg(feature = "alloc")]
 pub fn decode_to_string<'a, I>(input: I) -> Result<String>
 where
     I: IntoIterator<Item = &'a u8>,
 {
-    let it = input.into_iter();
-    let mut dest = String::with_capacity(capacity_hint_for_decode(it.size_hint().0));
-    append_decoded_to_string(&mut dest, it)?;
-    Ok(dest)
+    let decoded = decode_to_vec(input)?;
+    String::from_utf8(decoded).map_err(|_| Error::InvalidUtf8)
+}
+
+/// Decodes bytes and returns the result as a new [`String`], replacing invalid
+/// UTF-8 sequences with `U+FFFD REPLACEMENT CHARACTER`.
+///
+/// See [`Clockwork::decode_to_string_lossy`].
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// assert_eq!(base32::decode_to_string_lossy(b"ZZ")?, "\u{fffd}");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_to_string_lossy<'a, I>(input: I) -> Result<String>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    let decoded = decode_to_vec(input)?;
+    Ok(match String::from_utf8(decoded) {
+        Ok(s) => s,
+        Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+    })
 }
 
 /// Decodes bytes and returns the result as a new [`Vec<u8>`].
@@ -93,20 +651,22 @@ where
 ///
 /// # Examples
 /// ```
-/// # fn main() -> std::io::Result<()> {
+/// # fn main() -> Result<(), clockwork_base32::Error> {
 /// use clockwork_base32 as base32;
 /// let decoded = base32::decode_to_vec(b"91JPRV3F5GG7EVVJDHJ22")?;
 /// assert_eq!(&decoded, b"Hello, world!");
 /// # Ok(())
 /// # }
 /// ```
+#[cfg(feature = "alloc")]
 pub fn decode_to_vec<'a, I>(input: I) -> Result<Vec<u8>>
 where
     I: IntoIterator<Item = &'a u8>,
 {
-    let it = input.into_iter();
-    let mut dest = Vec::with_capacity(capacity_hint_for_decode(it.size_hint().0));
-    append_decoded_to_vec(&mut dest, it)?;
+    let input = input.into_iter();
+    let mut dest = Vec::new();
+    dest.reserve_hint(input.size_hint(), capacity_hint_for_decode);
+    for_each_chunk::<_, ITER_SYMBOL_CHUNK_LEN>(input, |chunk| decode_slice(&mut dest, chunk))?;
     Ok(dest)
 }
 
@@ -126,14 +686,14 @@ where
 /// let encoded = base32::encode_to_string(input.as_bytes());
 /// assert_eq!(&encoded, "91JPRV3F5GG7EVVJDHJ22");
 /// ```
+#[cfg(feature = "alloc")]
 pub fn encode_to_string<'a, I>(input: I) -> String
 where
     I: IntoIterator<Item = &'a u8>,
 {
-    let it = input.into_iter();
-    let mut dest = String::with_capacity(capacity_hint_for_encode(it.size_hint().0));
-    append_encoded_to_string(&mut dest, it);
-    dest
+    let encoded = encode_to_vec(input);
+    // The encoded result consists of ASCII symbols only, so this never fails.
+    String::from_utf8(encoded).unwrap_or_default()
 }
 
 /// Encodes bytes and returns the result as a new [`Vec<u8>`].
@@ -144,17 +704,158 @@ where
 /// let encoded = base32::encode_to_vec(b"Hello, world!");
 /// assert_eq!(&encoded, b"91JPRV3F5GG7EVVJDHJ22");
 /// ```
+#[cfg(feature = "alloc")]
 pub fn encode_to_vec<'a, I>(input: I) -> Vec<u8>
 where
     I: IntoIterator<Item = &'a u8>,
 {
-    let it = input.into_iter();
-    let mut dest = Vec::with_capacity(capacity_hint_for_encode(it.size_hint().0));
-    append_encoded_to_vec(&mut dest, it);
+    let input = input.into_iter();
+    let mut dest = Vec::new();
+    dest.reserve_hint(input.size_hint(), capacity_hint_for_encode);
+    let _ = for_each_chunk::<_, ITER_BYTE_CHUNK_LEN>(input, |chunk| {
+        encode_slice(&mut dest, chunk);
+        Ok(())
+    });
+    dest
+}
+
+/// Decodes bytes and returns the result as a new [`smallvec::SmallVec`].
+///
+/// The result is kept inline without allocating on the heap as long as it fits
+/// in the inline capacity of `A`.
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let decoded = base32::decode_to_smallvec::<[u8; 16], _>(b"91JPRV3F5GG7EVVJDHJ22")?;
+/// assert_eq!(&decoded[..], b"Hello, world!");
+/// assert!(!decoded.spilled());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "smallvec")]
+pub fn decode_to_smallvec<'a, A, I>(input: I) -> Result<smallvec::SmallVec<A>>
+where
+    A: smallvec::Array<Item = u8>,
+    I: IntoIterator<Item = &'a u8>,
+{
+    let input = input.into_iter();
+    let mut dest = smallvec::SmallVec::new();
+    dest.reserve_hint(input.size_hint(), capacity_hint_for_decode);
+    for_each_chunk::<_, ITER_SYMBOL_CHUNK_LEN>(input, |chunk| decode_slice(&mut dest, chunk))?;
+    Ok(dest)
+}
+
+/// Encodes bytes and returns the result as a new [`smallvec::SmallVec`].
+///
+/// The result is kept inline without allocating on the heap as long as it fits
+/// in the inline capacity of `A`.
+///
+/// # Examples
+/// ```
+/// use clockwork_base32 as base32;
+/// let encoded = base32::encode_to_smallvec::<[u8; 32], _>(b"Hello, world!");
+/// assert_eq!(&encoded[..], b"91JPRV3F5GG7EVVJDHJ22");
+/// assert!(!encoded.spilled());
+/// ```
+#[cfg(feature = "smallvec")]
+pub fn encode_to_smallvec<'a, A, I>(input: I) -> smallvec::SmallVec<A>
+where
+    A: smallvec::Array<Item = u8>,
+    I: IntoIterator<Item = &'a u8>,
+{
+    let input = input.into_iter();
+    let mut dest = smallvec::SmallVec::new();
+    dest.reserve_hint(input.size_hint(), capacity_hint_for_encode);
+    let _ = for_each_chunk::<_, ITER_BYTE_CHUNK_LEN>(input, |chunk| {
+        encode_slice(&mut dest, chunk);
+        Ok(())
+    });
     dest
 }
 
+/// Decodes bytes into `dest` and returns the number of bytes written.
+///
+/// This function is available without the `alloc` feature.
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte or `dest` is shorter
+/// than [`capacity_hint_for_decode`] of the input length.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let mut dest = [0; 16];
+/// let len = base32::decode_to_slice(b"91JPRV3F5GG7EVVJDHJ22", &mut dest)?;
+/// assert_eq!(&dest[..len], b"Hello, world!");
+/// # Ok(())
+/// # }
+/// ```
+pub fn decode_to_slice<'a, I>(input: I, dest: &mut [u8]) -> Result<usize>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    let mut len = 0;
+    for_each_chunk::<_, ITER_SYMBOL_CHUNK_LEN>(input.into_iter(), |chunk| {
+        len += CLOCKWORK.decode_to_slice(chunk, dest.get_mut(len..).unwrap_or_default())?;
+        Ok(())
+    })?;
+    Ok(len)
+}
+
+/// Encodes bytes into `dest` and returns the number of bytes written.
+///
+/// This function is available without the `alloc` feature.
+///
+/// # Errors
+/// Returns [`Err`] if `dest` is shorter than [`capacity_hint_for_encode`] of
+/// the input length.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let mut dest = [0; 32];
+/// let len = base32::encode_to_slice(b"Hello, world!", &mut dest)?;
+/// assert_eq!(&dest[..len], b"91JPRV3F5GG7EVVJDHJ22");
+/// # Ok(())
+/// # }
+/// ```
+pub fn encode_to_slice<'a, I>(input: I, dest: &mut [u8]) -> Result<usize>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    let mut len = 0;
+    for_each_chunk::<_, ITER_BYTE_CHUNK_LEN>(input.into_iter(), |chunk| {
+        len += CLOCKWORK.encode_to_slice(chunk, dest.get_mut(len..).unwrap_or_default())?;
+        Ok(())
+    })?;
+    Ok(len)
+}
+
+/// Returns whether `b` is a valid symbol for decoding.
+///
+/// Lowercase letters and the aliases `I`, `L` and `O` are valid symbols.
+/// # Examples
+/// ```
+/// use clockwork_base32 as base32;
+/// assert!(base32::is_valid_symbol(b'A'));
+/// assert!(base32::is_valid_symbol(b'o'));
+/// assert!(!base32::is_valid_symbol(b'U'));
+/// assert!(!base32::is_valid_symbol(b'\n'));
+/// ```
+pub fn is_valid_symbol(b: u8) -> bool {
+    is_symbol_value(decode_symbol(b))
+}
+
 /// Returns a hint for the capacity needed for the decoded result.
+///
+/// This never overflows, since the decoded result is shorter than the input.
 /// # Examples
 /// Basic usage:
 /// ```
@@ -164,7 +865,8 @@ where
 /// ```
 /// You can reserve the needed capacity before decoding:
 /// ```
-/// # fn main() -> std::io::Result<()> {
+/// # #[cfg(feature = "alloc")]
+/// # fn main() -> Result<(), clockwork_base32::Error> {
 /// use clockwork_base32 as base32;
 /// let input = b"91JPRV3F5GG7EVVJDHJ22";
 /// let capacity = base32::capacity_hint_for_encode(input.len());
@@ -173,12 +875,21 @@ where
 /// assert_eq!(&dest, "Hello, world!");
 /// # Ok(())
 /// # }
+/// # #[cfg(not(feature = "alloc"))]
+/// # fn main() {}
 /// ```
 pub fn capacity_hint_for_decode(input_byte_len: usize) -> usize {
-    input_byte_len * DECODED_BIT_LEN / BYTE_BIT_LEN
+    // Whole blocks first, so that the multiplication never overflows.
+    input_byte_len / BLOCK_SYMBOL_LEN * BLOCK_BYTE_LEN
+        + input_byte_len % BLOCK_SYMBOL_LEN * DECODED_BIT_LEN / BYTE_BIT_LEN
 }
 
 /// Returns a hint for the capacity needed for the encoded result.
+///
+/// Returns [`usize::MAX`] if the result doesn't fit in [`usize`], which is only
+/// possible for lengths which no slice has, like lengths of streams. No buffer
+/// is that large, so sizing a buffer with it fails instead of under-allocating.
+/// Use [`checked_capacity_for_encode`] to detect it.
 /// # Examples
 /// Basic usage:
 /// ```
@@ -188,26 +899,51 @@ pub fn capacity_hint_for_decode(input_byte_len: usize) -> usize {
 /// ```
 /// You can reserve the needed capacity before encoding:
 /// ```
+/// # #[cfg(feature = "alloc")]
+/// # {
 /// use clockwork_base32 as base32;
 /// let input = b"Hello, world!";
 /// let capacity = base32::capacity_hint_for_encode(input.len());
 /// let mut dest = String::with_capacity(capacity);
 /// base32::append_encoded_to_string(&mut dest, input.into_iter());
 /// assert_eq!(&dest, "91JPRV3F5GG7EVVJDHJ22");
+/// # }
 /// ```
 pub fn capacity_hint_for_encode(input_byte_len: usize) -> usize {
-    (input_byte_len * BYTE_BIT_LEN + (DECODED_BIT_LEN - 1)) / DECODED_BIT_LEN
+    checked_capacity_for_encode(input_byte_len).unwrap_or(usize::MAX)
+}
+
+/// Returns the capacity needed for the encoded result, or [`None`] if it
+/// doesn't fit in [`usize`].
+/// # Examples
+/// ```
+/// use clockwork_base32 as base32;
+/// assert_eq!(base32::checked_capacity_for_encode(13), Some(21));
+/// assert_eq!(base32::checked_capacity_for_encode(usize::MAX), None);
+/// ```
+pub fn checked_capacity_for_encode(input_byte_len: usize) -> Option<usize> {
+    // Whole blocks first, so that only the final steps may overflow.
+    let rest = (input_byte_len % BLOCK_BYTE_LEN * BYTE_BIT_LEN).div_ceil(DECODED_BIT_LEN);
+    (input_byte_len / BLOCK_BYTE_LEN)
+        .checked_mul(BLOCK_SYMBOL_LEN)?
+        .checked_add(rest)
 }
 
 /// Decodes bytes and append the result to `dest`.
 ///
 /// # Errors
-/// Returns [`Err`] if the input contains a invalid byte.
+/// Returns [`Err`] if the input contains a invalid byte or the decoded bytes
+/// are not valid UTF-8.
+///
+/// The capacity for the result is reserved up front from the lower bound of
+/// [`Iterator::size_hint`] of `input`. For an [`ExactSizeIterator`] like a slice
+/// iterator, exactly the capacity of the result is reserved, and `dest` doesn't
+/// grow while appending.
 ///
 /// # Examples
 /// Basic usage:
 /// ```
-/// # fn main() -> std::io::Result<()> {
+/// # fn main() -> Result<(), clockwork_base32::Error> {
 /// use clockwork_base32 as base32;
 /// let mut dest = String::new();
 /// base32::append_decoded_to_string(&mut dest, b"91JPRV3F5GG7EVVJDHJ22".into_iter())?;
@@ -217,7 +953,7 @@ pub fn capacity_hint_for_encode(input_byte_len: usize) -> usize {
 /// ```
 /// If your input is a [`str`], you can convert it to bytes using [`str::as_bytes`].
 /// ```
-/// # fn main() -> std::io::Result<()> {
+/// # fn main() -> Result<(), clockwork_base32::Error> {
 /// use clockwork_base32 as base32;
 /// let mut dest = String::new();
 /// let input = String::from("91JPRV3F5GG7EVVJDHJ22");
@@ -228,7 +964,7 @@ pub fn capacity_hint_for_encode(input_byte_len: usize) -> usize {
 /// ```
 /// You can reserve the needed capacity before decoding:
 /// ```
-/// # fn main() -> std::io::Result<()> {
+/// # fn main() -> Result<(), clockwork_base32::Error> {
 /// use clockwork_base32 as base32;
 /// let input = b"91JPRV3F5GG7EVVJDHJ22";
 /// let capacity = base32::capacity_hint_for_decode(input.len());
@@ -238,25 +974,63 @@ pub fn capacity_hint_for_encode(input_byte_len: usize) -> usize {
 /// # Ok(())
 /// # }
 /// ```
+#[cfg(feature = "alloc")]
 pub fn append_decoded_to_string<'a, I>(dest: &mut String, input: I) -> Result<()>
 where
     I: Iterator<Item = &'a u8>,
 {
+    dest.reserve_hint(input.size_hint(), capacity_hint_for_decode);
+    let mut buf = [0; STRING_CHUNK_LEN];
+    let mut len = 0;
     for b in DecodeIter::new(input) {
-        dest.push(b? as char);
+        if let Some(slot) = buf.get_mut(len) {
+            *slot = b?;
+            len += 1;
+        }
+        if len == buf.len() {
+            len = push_utf8_chunk(dest, &mut buf, len)?;
+        }
+    }
+    if push_utf8_chunk(dest, &mut buf, len)? > 0 {
+        return Err(Error::InvalidUtf8);
     }
     Ok(())
 }
 
+// Appends the valid UTF-8 prefix of `buf[..len]` to `dest` and moves an incomplete
+// multi-byte sequence at the end to the beginning of `buf`.
+// Returns the length of the moved incomplete sequence.
+#[cfg(feature = "alloc")]
+fn push_utf8_chunk(dest: &mut String, buf: &mut [u8], len: usize) -> Result<usize> {
+    let chunk = buf.get(..len).unwrap_or_default();
+    let s = match core::str::from_utf8(chunk) {
+        Ok(s) => s,
+        Err(e) if e.error_len().is_none() => {
+            let valid = chunk.get(..e.valid_up_to()).unwrap_or_default();
+            core::str::from_utf8(valid).map_err(|_| Error::InvalidUtf8)?
+        }
+        Err(_) => return Err(Error::InvalidUtf8),
+    };
+    let valid_len = s.len();
+    dest.push_str(s);
+    buf.copy_within(valid_len..len, 0);
+    Ok(len - valid_len)
+}
+
 /// Decodes bytes and append the result to `dest`.
 ///
 /// # Errors
 /// Returns [`Err`] if the input contains a invalid byte.
 ///
+/// The capacity for the result is reserved up front from the lower bound of
+/// [`Iterator::size_hint`] of `input`. For an [`ExactSizeIterator`] like a slice
+/// iterator, exactly the capacity of the result is reserved, and `dest` doesn't
+/// grow while appending.
+///
 /// # Examples
 /// Basic usage:
 /// ```
-/// # fn main() -> std::io::Result<()> {
+/// # fn main() -> Result<(), clockwork_base32::Error> {
 /// use clockwork_base32 as base32;
 /// let mut dest = Vec::new();
 /// base32::append_decoded_to_vec(&mut dest, b"91JPRV3F5GG7EVVJDHJ22".into_iter())?;
@@ -266,7 +1040,7 @@ where
 /// ```
 /// You can reserve the needed capacity before decoding:
 /// ```
-/// # fn main() -> std::io::Result<()> {
+/// # fn main() -> Result<(), clockwork_base32::Error> {
 /// use clockwork_base32 as base32;
 /// let input = b"91JPRV3F5GG7EVVJDHJ22";
 /// let mut dest = Vec::with_capacity(input.len());
@@ -275,10 +1049,12 @@ where
 /// # Ok(())
 /// # }
 /// ```
+#[cfg(feature = "alloc")]
 pub fn append_decoded_to_vec<'a, I>(dest: &mut Vec<u8>, input: I) -> Result<()>
 where
     I: Iterator<Item = &'a u8>,
 {
+    dest.reserve_hint(input.size_hint(), capacity_hint_for_decode);
     for b in DecodeIter::new(input) {
         dest.push(b?);
     }
@@ -287,6 +1063,11 @@ where
 
 /// Encodes bytes and append the result to `dest`.
 ///
+/// The capacity for the result is reserved up front from the lower bound of
+/// [`Iterator::size_hint`] of `input`. For an [`ExactSizeIterator`] like a slice
+/// iterator, exactly the capacity of the result is reserved, and `dest` doesn't
+/// grow while appending.
+///
 /// # Examples
 /// Basic usage:
 /// ```
@@ -312,17 +1093,24 @@ where
 /// base32::append_encoded_to_string(&mut dest, input.into_iter());
 /// assert_eq!(&dest, "91JPRV3F5GG7EVVJDHJ22");
 /// ```
+#[cfg(feature = "alloc")]
 pub fn append_encoded_to_string<'a, I>(dest: &mut String, input: I)
 where
     I: Iterator<Item = &'a u8>,
 {
+    dest.reserve_hint(input.size_hint(), capacity_hint_for_encode);
     for b in FiveBitsIter::new(input) {
-        dest.push(ENCODE_SYMBOLS[b as usize] as char);
+        dest.push(encode_symbol(b) as char);
     }
 }
 
 /// Encodes bytes and append the result to `dest`.
 ///
+/// The capacity for the result is reserved up front from the lower bound of
+/// [`Iterator::size_hint`] of `input`. For an [`ExactSizeIterator`] like a slice
+/// iterator, exactly the capacity of the result is reserved, and `dest` doesn't
+/// grow while appending.
+///
 /// # Examples
 /// Basic usage:
 /// ```
@@ -348,18 +1136,526 @@ where
 /// base32::append_encoded_to_vec(&mut dest, input.into_iter());
 /// assert_eq!(&dest, b"91JPRV3F5GG7EVVJDHJ22");
 /// ```
+#[cfg(feature = "alloc")]
 pub fn append_encoded_to_vec<'a, I>(dest: &mut Vec<u8>, input: I)
 where
     I: Iterator<Item = &'a u8>,
 {
+    dest.reserve_hint(input.size_hint(), capacity_hint_for_encode);
     for b in FiveBitsIter::new(input) {
-        dest.push(ENCODE_SYMBOLS[b as usize]);
+        dest.push(encode_symbol(b));
+    }
+}
+
+/// Decodes bytes and append the result to `dest`.
+///
+/// `dest` is left unchanged when the function returns [`Err`].
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte, the decoded bytes
+/// are not valid UTF-8, or the result does not fit in the remaining capacity of `dest`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let mut dest = heapless::String::<16>::new();
+/// base32::append_decoded_to_heapless_string(&mut dest, b"91JPRV3F5GG7EVVJDHJ22")?;
+/// assert_eq!(&dest, "Hello, world!");
+/// assert!(base32::append_decoded_to_heapless_string(&mut dest, b"CSQPYRK1E8").is_err());
+/// assert_eq!(&dest, "Hello, world!");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "heapless")]
+pub fn append_decoded_to_heapless_string<'a, I, const N: usize>(
+    dest: &mut heapless::String<N>,
+    input: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    let mut decoded = heapless::Vec::<u8, N>::new();
+    append_decoded_to_heapless_vec(&mut decoded, input)?;
+    let s = core::str::from_utf8(&decoded).map_err(|_| Error::InvalidUtf8)?;
+    dest.push_str(s).map_err(|_| Error::BufferTooSmall)
+}
+
+/// Decodes bytes and append the result to `dest`.
+///
+/// `dest` is left unchanged when the function returns [`Err`].
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte or the result does not
+/// fit in the remaining capacity of `dest`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let mut dest = heapless::Vec::<u8, 16>::new();
+/// base32::append_decoded_to_heapless_vec(&mut dest, b"91JPRV3F5GG7EVVJDHJ22")?;
+/// assert_eq!(&dest, b"Hello, world!");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "heapless")]
+pub fn append_decoded_to_heapless_vec<'a, I, const N: usize>(
+    dest: &mut heapless::Vec<u8, N>,
+    input: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    append_to_fixed::<_, _, ITER_SYMBOL_CHUNK_LEN>(dest, input.into_iter(), decode_slice)
+}
+
+/// Encodes bytes and append the result to `dest`.
+///
+/// `dest` is left unchanged when the function returns [`Err`].
+///
+/// # Errors
+/// Returns [`Err`] if the result does not fit in the remaining capacity of `dest`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let mut dest = heapless::String::<32>::new();
+/// base32::append_encoded_to_heapless_string(&mut dest, b"Hello, world!")?;
+/// assert_eq!(&dest, "91JPRV3F5GG7EVVJDHJ22");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "heapless")]
+pub fn append_encoded_to_heapless_string<'a, I, const N: usize>(
+    dest: &mut heapless::String<N>,
+    input: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    let mut encoded = heapless::Vec::<u8, N>::new();
+    append_encoded_to_heapless_vec(&mut encoded, input)?;
+    // The encoded result consists of ASCII symbols only.
+    let s = core::str::from_utf8(&encoded).map_err(|_| Error::InvalidUtf8)?;
+    dest.push_str(s).map_err(|_| Error::BufferTooSmall)
+}
+
+/// Encodes bytes and append the result to `dest`.
+///
+/// `dest` is left unchanged when the function returns [`Err`].
+///
+/// # Errors
+/// Returns [`Err`] if the result does not fit in the remaining capacity of `dest`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let mut dest = heapless::Vec::<u8, 32>::new();
+/// base32::append_encoded_to_heapless_vec(&mut dest, b"Hello, world!")?;
+/// assert_eq!(&dest, b"91JPRV3F5GG7EVVJDHJ22");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "heapless")]
+pub fn append_encoded_to_heapless_vec<'a, I, const N: usize>(
+    dest: &mut heapless::Vec<u8, N>,
+    input: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    append_to_fixed::<_, _, ITER_BYTE_CHUNK_LEN>(dest, input.into_iter(), |sink, chunk| {
+        encode_slice(sink, chunk);
+        Ok(())
+    })
+}
+
+/// Decodes bytes and append the result to `dest`.
+///
+/// `dest` is left unchanged when the function returns [`Err`].
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte, the decoded bytes
+/// are not valid UTF-8, or the result does not fit in the remaining capacity of `dest`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let mut dest = arrayvec::ArrayString::<16>::new();
+/// base32::append_decoded_to_array_string(&mut dest, b"91JPRV3F5GG7EVVJDHJ22")?;
+/// assert_eq!(&dest, "Hello, world!");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "arrayvec")]
+pub fn append_decoded_to_array_string<'a, I, const CAP: usize>(
+    dest: &mut arrayvec::ArrayString<CAP>,
+    input: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    let mut decoded = arrayvec::ArrayVec::<u8, CAP>::new();
+    append_decoded_to_array_vec(&mut decoded, input)?;
+    let s = core::str::from_utf8(&decoded).map_err(|_| Error::InvalidUtf8)?;
+    dest.try_push_str(s).map_err(|_| Error::BufferTooSmall)
+}
+
+/// Decodes bytes and append the result to `dest`.
+///
+/// `dest` is left unchanged when the function returns [`Err`].
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte or the result does not
+/// fit in the remaining capacity of `dest`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let mut dest = arrayvec::ArrayVec::<u8, 16>::new();
+/// base32::append_decoded_to_array_vec(&mut dest, b"91JPRV3F5GG7EVVJDHJ22")?;
+/// assert_eq!(&dest[..], b"Hello, world!");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "arrayvec")]
+pub fn append_decoded_to_array_vec<'a, I, const CAP: usize>(
+    dest: &mut arrayvec::ArrayVec<u8, CAP>,
+    input: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    append_to_fixed::<_, _, ITER_SYMBOL_CHUNK_LEN>(dest, input.into_iter(), decode_slice)
+}
+
+/// Encodes bytes and append the result to `dest`.
+///
+/// `dest` is left unchanged when the function returns [`Err`].
+///
+/// # Errors
+/// Returns [`Err`] if the result does not fit in the remaining capacity of `dest`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let mut dest = arrayvec::ArrayString::<32>::new();
+/// base32::append_encoded_to_array_string(&mut dest, b"Hello, world!")?;
+/// assert_eq!(&dest, "91JPRV3F5GG7EVVJDHJ22");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "arrayvec")]
+pub fn append_encoded_to_array_string<'a, I, const CAP: usize>(
+    dest: &mut arrayvec::ArrayString<CAP>,
+    input: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    let mut encoded = arrayvec::ArrayVec::<u8, CAP>::new();
+    append_encoded_to_array_vec(&mut encoded, input)?;
+    // The encoded result consists of ASCII symbols only.
+    let s = core::str::from_utf8(&encoded).map_err(|_| Error::InvalidUtf8)?;
+    dest.try_push_str(s).map_err(|_| Error::BufferTooSmall)
+}
+
+/// Encodes bytes and append the result to `dest`.
+///
+/// `dest` is left unchanged when the function returns [`Err`].
+///
+/// # Errors
+/// Returns [`Err`] if the result does not fit in the remaining capacity of `dest`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32 as base32;
+/// let mut dest = arrayvec::ArrayVec::<u8, 32>::new();
+/// base32::append_encoded_to_array_vec(&mut dest, b"Hello, world!")?;
+/// assert_eq!(&dest[..], b"91JPRV3F5GG7EVVJDHJ22");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "arrayvec")]
+pub fn append_encoded_to_array_vec<'a, I, const CAP: usize>(
+    dest: &mut arrayvec::ArrayVec<u8, CAP>,
+    input: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    append_to_fixed::<_, _, ITER_BYTE_CHUNK_LEN>(dest, input.into_iter(), |sink, chunk| {
+        encode_slice(sink, chunk);
+        Ok(())
+    })
+}
+
+// The number of symbols and bytes taken from an input iterator at a time. They
+// are multiples of the block lengths, so only the last chunk has a partial block.
+const ITER_SYMBOL_CHUNK_LEN: usize = 8 * BLOCK_SYMBOL_LEN;
+const ITER_BYTE_CHUNK_LEN: usize = 8 * BLOCK_BYTE_LEN;
+
+// for_each_chunk copies the bytes of `input` to a stack buffer `N` bytes at a
+// time and calls `f` with each chunk, so iterators are decoded/encoded with the
+// block based fast path too. Only the last chunk can be shorter than `N`.
+// Errors are returned with the offset of the chunk added to their position.
+fn for_each_chunk<'a, I, const N: usize>(
+    mut input: I,
+    mut f: impl FnMut(&[u8]) -> Result<()>,
+) -> Result<()>
+where
+    I: Iterator<Item = &'a u8>,
+{
+    let mut buf = [0; N];
+    let mut offset = 0;
+    loop {
+        let len = buf
+            .iter_mut()
+            .zip(input.by_ref())
+            .map(|(slot, &b)| *slot = b)
+            .count();
+        if len > 0 {
+            f(buf.get(..len).unwrap_or_default()).map_err(|err| err.offset_by(offset))?;
+        }
+        if len < N {
+            return Ok(());
+        }
+        offset += N;
+    }
+}
+
+// ByteSink is a destination the slice based decoder/encoder append bytes to.
+trait ByteSink {
+    fn append_bytes(&mut self, bytes: &[u8]);
+}
+
+#[cfg(feature = "alloc")]
+impl ByteSink for Vec<u8> {
+    fn append_bytes(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+}
+
+// ReserveHint reserves capacity in a destination for the result of transcoding
+// an input iterator with `size_hint`, whose length `capacity` converts to the
+// result length. If the hint is exact, like the one of an `ExactSizeIterator`,
+// exactly that is reserved, so no more than the result is allocated. Otherwise
+// the lower bound is reserved with the usual amortized growth. A failed
+// reservation is ignored, since pushing grows the destination as needed anyway.
+#[cfg(feature = "alloc")]
+trait ReserveHint {
+    fn reserve_hint(&mut self, size_hint: (usize, Option<usize>), capacity: fn(usize) -> usize);
+}
+
+#[cfg(feature = "alloc")]
+impl ReserveHint for Vec<u8> {
+    fn reserve_hint(&mut self, size_hint: (usize, Option<usize>), capacity: fn(usize) -> usize) {
+        let _ = match size_hint {
+            (lower, Some(upper)) if lower == upper => self.try_reserve_exact(capacity(lower)),
+            (lower, _) => self.try_reserve(capacity(lower)),
+        };
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ReserveHint for String {
+    fn reserve_hint(&mut self, size_hint: (usize, Option<usize>), capacity: fn(usize) -> usize) {
+        let _ = match size_hint {
+            (lower, Some(upper)) if lower == upper => self.try_reserve_exact(capacity(lower)),
+            (lower, _) => self.try_reserve(capacity(lower)),
+        };
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array<Item = u8>> ReserveHint for smallvec::SmallVec<A> {
+    fn reserve_hint(&mut self, size_hint: (usize, Option<usize>), capacity: fn(usize) -> usize) {
+        let _ = match size_hint {
+            (lower, Some(upper)) if lower == upper => self.try_reserve_exact(capacity(lower)),
+            (lower, _) => self.try_reserve(capacity(lower)),
+        };
+    }
+}
+
+// SliceSink writes to a slice which the caller has checked to be long enough.
+struct SliceSink<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl ByteSink for SliceSink<'_> {
+    fn append_bytes(&mut self, bytes: &[u8]) {
+        if let Some(buf) = self.buf.get_mut(self.len..) {
+            self.len += copy_prefix(buf, bytes);
+        }
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array<Item = u8>> ByteSink for smallvec::SmallVec<A> {
+    fn append_bytes(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+}
+
+// FixedCapacity is a destination of a fixed capacity, which appending bytes to
+// fails without changing it if they don't fit.
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+trait FixedCapacity {
+    fn len(&self) -> usize;
+    fn try_append(&mut self, bytes: &[u8]) -> bool;
+    fn truncate(&mut self, len: usize);
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> FixedCapacity for heapless::Vec<u8, N> {
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn try_append(&mut self, bytes: &[u8]) -> bool {
+        self.extend_from_slice(bytes).is_ok()
+    }
+
+    fn truncate(&mut self, len: usize) {
+        heapless::Vec::truncate(self, len);
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const CAP: usize> FixedCapacity for arrayvec::ArrayVec<u8, CAP> {
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn try_append(&mut self, bytes: &[u8]) -> bool {
+        self.try_extend_from_slice(bytes).is_ok()
+    }
+
+    fn truncate(&mut self, len: usize) {
+        arrayvec::ArrayVec::truncate(self, len);
+    }
+}
+
+// FixedSink appends to a destination of a fixed capacity and remembers whether
+// some bytes didn't fit.
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+struct FixedSink<'a, D> {
+    dest: &'a mut D,
+    full: bool,
+}
+
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+impl<D: FixedCapacity> ByteSink for FixedSink<'_, D> {
+    fn append_bytes(&mut self, bytes: &[u8]) {
+        self.full = self.full || !self.dest.try_append(bytes);
+    }
+}
+
+// append_to_fixed calls `f` with each chunk of `N` bytes of `input` to append
+// the result to `dest`, which is left unchanged on Err, including
+// Error::BufferTooSmall if the result doesn't fit.
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+fn append_to_fixed<'a, 'd, D, I, const N: usize>(
+    dest: &'d mut D,
+    input: I,
+    mut f: impl FnMut(&mut FixedSink<'d, D>, &[u8]) -> Result<()>,
+) -> Result<()>
+where
+    D: FixedCapacity,
+    I: Iterator<Item = &'a u8>,
+{
+    let start = dest.len();
+    let mut sink = FixedSink { dest, full: false };
+    let result = for_each_chunk::<_, N>(input, |chunk| {
+        f(&mut sink, chunk)?;
+        if sink.full {
+            return Err(Error::BufferTooSmall);
+        }
+        Ok(())
+    });
+    if result.is_err() {
+        sink.dest.truncate(start);
+    }
+    result
+}
+
+fn decode_slice<D: ByteSink>(dest: &mut D, input: &[u8]) -> Result<()> {
+    let mut chunks = input.chunks_exact(BLOCK_SYMBOL_LEN);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        let decoded = decode_block(chunk).map_err(|err| err.offset_by(i * BLOCK_SYMBOL_LEN))?;
+        dest.append_bytes(&decoded);
+    }
+    let rest = chunks.remainder();
+    if !rest.is_empty() {
+        let mut block = [b'0'; BLOCK_SYMBOL_LEN];
+        copy_prefix(&mut block, rest);
+        let offset = input.len() - rest.len();
+        let decoded = decode_block(&block).map_err(|err| err.offset_by(offset))?;
+        let len = rest.len() * DECODED_BIT_LEN / BYTE_BIT_LEN;
+        dest.append_bytes(decoded.get(..len).unwrap_or_default());
     }
+    Ok(())
 }
 
+#[cfg(not(feature = "portable-simd"))]
+fn decode_block(block: &[u8]) -> Result<[u8; BLOCK_BYTE_LEN]> {
+    let mut bits = 0u64;
+    for (position, &byte) in block.iter().enumerate() {
+        let s = decode_symbol(byte);
+        if !is_symbol_value(s) {
+            return Err(Error::InvalidSymbol { byte, position });
+        }
+        bits = (bits << DECODED_BIT_LEN) | s as u64;
+    }
+    let mut output = [0; BLOCK_BYTE_LEN];
+    for (i, o) in output.iter_mut().enumerate() {
+        *o = (bits >> ((BLOCK_BYTE_LEN - 1 - i) * BYTE_BIT_LEN)) as u8;
+    }
+    Ok(output)
+}
+
+fn encode_slice<D: ByteSink>(dest: &mut D, input: &[u8]) {
+    let mut chunks = input.chunks_exact(BLOCK_BYTE_LEN);
+    for chunk in chunks.by_ref() {
+        dest.append_bytes(&encode_block(chunk));
+    }
+    let rest = chunks.remainder();
+    if !rest.is_empty() {
+        let mut block = [0; BLOCK_BYTE_LEN];
+        copy_prefix(&mut block, rest);
+        let encoded = encode_block(&block);
+        let len = capacity_hint_for_encode(rest.len());
+        dest.append_bytes(encoded.get(..len).unwrap_or_default());
+    }
+}
+
+#[cfg(not(feature = "portable-simd"))]
+fn encode_block(block: &[u8]) -> [u8; BLOCK_SYMBOL_LEN] {
+    let bits = block
+        .iter()
+        .fold(0u64, |acc, &b| (acc << BYTE_BIT_LEN) | b as u64);
+    let mut output = [0; BLOCK_SYMBOL_LEN];
+    for (i, o) in output.iter_mut().enumerate() {
+        *o = encode_symbol((bits >> ((BLOCK_SYMBOL_LEN - 1 - i) * DECODED_BIT_LEN)) as u8);
+    }
+    output
+}
+
+#[cfg(feature = "alloc")]
 struct DecodeIter<I> {
     input: I,
 
+    // position is the number of symbols taken from `input`.
+    position: usize,
+
     // bit_count is effective bits count in buffer
     bit_count: usize,
 
@@ -367,16 +1663,19 @@ struct DecodeIter<I> {
     buffer: u8,
 }
 
+#[cfg(feature = "alloc")]
 impl<I> DecodeIter<I> {
     fn new(input: I) -> Self {
         Self {
             input,
+            position: 0,
             bit_count: 0,
             buffer: 0,
         }
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, I> Iterator for DecodeIter<I>
 where
     I: Iterator<Item = &'a u8>,
@@ -384,25 +1683,24 @@ where
     type Item = Result<u8>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(b) = self.input.next() {
-            let s = DECODE_SYMBOLS[*b as usize];
-            if s < 0 {
-                return Some(Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    format!("invalid symbol value {:}", *b as char),
-                )));
+        for &byte in self.input.by_ref() {
+            let position = self.position;
+            self.position += 1;
+            let s = decode_symbol(byte);
+            if !is_symbol_value(s) {
+                return Some(Err(Error::InvalidSymbol { byte, position }));
             }
             if self.bit_count + DECODED_BIT_LEN >= BYTE_BIT_LEN {
                 self.bit_count = self.bit_count + DECODED_BIT_LEN - BYTE_BIT_LEN;
-                let output = self.buffer | ((s as u8) >> self.bit_count);
+                let output = self.buffer | (s >> self.bit_count);
                 self.buffer = if self.bit_count > 0 {
-                    (s as u8) << (BYTE_BIT_LEN - self.bit_count)
+                    s << (BYTE_BIT_LEN - self.bit_count)
                 } else {
                     0
                 };
                 return Some(Ok(output));
             } else {
-                self.buffer |= (s as u8) << (BYTE_BIT_LEN - DECODED_BIT_LEN - self.bit_count);
+                self.buffer |= s << (BYTE_BIT_LEN - DECODED_BIT_LEN - self.bit_count);
                 self.bit_count += DECODED_BIT_LEN;
             }
         }
@@ -410,6 +1708,7 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 struct FiveBitsIter<I> {
     input: I,
 
@@ -420,6 +1719,7 @@ struct FiveBitsIter<I> {
     buffer: u8,
 }
 
+#[cfg(feature = "alloc")]
 impl<I> FiveBitsIter<I> {
     fn new(input: I) -> Self {
         Self {
@@ -430,6 +1730,7 @@ impl<I> FiveBitsIter<I> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, I> Iterator for FiveBitsIter<I>
 where
     I: Iterator<Item = &'a u8>,
@@ -463,41 +1764,197 @@ where
     }
 }
 
-const ENCODE_SYMBOLS: [u8; 32] = [
-    b'0', b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b'A', b'B', b'C', b'D', b'E', b'F',
-    b'G', b'H', b'J', b'K', b'M', b'N', b'P', b'Q', b'R', b'S', b'T', b'V', b'W', b'X', b'Y', b'Z',
-];
+const ENCODE_SYMBOLS: [u8; 32] = *b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
 
-const DECODE_SYMBOLS: [i8; 256] = [
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 0-9 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 10-19 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 20-29 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 30-39 */
-    -1, -1, -1, -1, -1, -1, -1, -1, 0, 1, /* 40-49 */
-    2, 3, 4, 5, 6, 7, 8, 9, 0, -1, /* 50-59 */
-    -1, -1, -1, -1, -1, 10, 11, 12, 13, 14, /* 60-69 */
-    15, 16, 17, 1, 18, 19, 1, 20, 21, 0, /* 70-79 */
-    22, 23, 24, 25, 26, -2, 27, 28, 29, 30, /* 80-89 */
-    31, -1, -1, -1, -1, -1, -1, 10, 11, 12, /* 90-99 */
-    13, 14, 15, 16, 17, 1, 18, 19, 1, 20, /* 100-109 */
-    21, 0, 22, 23, 24, 25, 26, -1, 27, 28, /* 110-119 */
-    29, 30, 31, -1, -1, -1, -1, -1, -1, -1, /* 120-129 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 130-109 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 140-109 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 150-109 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 160-109 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 170-109 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 180-109 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 190-109 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 200-209 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 210-209 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 220-209 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 230-209 */
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, /* 240-209 */
-    -1, -1, -1, -1, -1, -1, /* 250-256 */
+// check_canonical returns Err if `input` contains an invalid symbol or is not
+// the canonical encoding of its decoded bytes, which is the case if it contains
+// lowercase or aliased symbols, its length is not produced by encoding or the
+// padding bits of the last symbol are not zero.
+fn check_canonical(input: &[u8]) -> core::result::Result<(), ParseError> {
+    for (i, &b) in input.iter().enumerate() {
+        let v = decode_symbol(b);
+        if !is_symbol_value(v) {
+            return Err(ParseError::invalid_symbol(b, i));
+        }
+        let canonical = encode_symbol(v);
+        if canonical != b {
+            return Err(ParseError::new(Error::NonCanonical, i, Some(canonical)));
+        }
+    }
+    let decoded_len = capacity_hint_for_decode(input.len());
+    if capacity_hint_for_encode(decoded_len) != input.len() {
+        return Err(ParseError::new(Error::NonCanonical, input.len(), None));
+    }
+    let padding_bits = input.len() * DECODED_BIT_LEN - decoded_len * BYTE_BIT_LEN;
+    match input.last() {
+        Some(&b) if decode_symbol(b) & ((1 << padding_bits) - 1) != 0 => {
+            let canonical = encode_symbol(decode_symbol(b) >> padding_bits << padding_bits);
+            Err(ParseError::new(
+                Error::NonCanonical,
+                input.len() - 1,
+                Some(canonical),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+// find_invalid_symbol returns Err with the position of the first invalid symbol
+// in `input`.
+fn find_invalid_symbol(input: &[u8]) -> core::result::Result<(), ParseError> {
+    match input
+        .iter()
+        .position(|&b| !is_symbol_value(decode_symbol(b)))
+    {
+        Some(i) => {
+            let b = input.get(i).copied().unwrap_or_default();
+            Err(ParseError::invalid_symbol(b, i))
+        }
+        None => Ok(()),
+    }
+}
+
+// check_input returns Err with the position of the first problem in `input`,
+// which must also be a canonical encoding if `strict` is true. It is shared by
+// the bindings for other languages.
+#[cfg(any(
+    feature = "wasm",
+    feature = "python",
+    feature = "napi",
+    feature = "http"
+))]
+fn check_input(input: &[u8], strict: bool) -> core::result::Result<(), ParseError> {
+    if strict {
+        check_canonical(input)
+    } else {
+        find_invalid_symbol(input)
+    }
+}
+
+// decode_checked decodes `input` or returns Err with the position of the first
+// invalid symbol.
+#[cfg(any(
+    feature = "wasm",
+    feature = "python",
+    feature = "napi",
+    feature = "http"
+))]
+fn decode_checked(input: &[u8]) -> core::result::Result<Vec<u8>, ParseError> {
+    find_invalid_symbol(input)?;
+    // The input is validated, so this never fails.
+    Ok(decode_to_vec(input).unwrap_or_default())
+}
+
+// The number of bytes encoded at once by fmt_encoded, a multiple of the block length.
+#[cfg(any(feature = "alloc", feature = "derive"))]
+const FMT_CHUNK_LEN: usize = 8 * BLOCK_BYTE_LEN;
+
+// fmt_encoded writes the encoded result of `bytes` to `f` without allocating.
+#[cfg(any(feature = "alloc", feature = "derive"))]
+fn fmt_encoded(bytes: &[u8], f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut buf = [0; 8 * BLOCK_SYMBOL_LEN];
+    for chunk in bytes.chunks(FMT_CHUNK_LEN) {
+        // The buffer is long enough for a chunk, so these never fail.
+        let len = encode_to_slice(chunk, &mut buf).unwrap_or_default();
+        let encoded = core::str::from_utf8(buf.get(..len).unwrap_or_default());
+        f.write_str(encoded.unwrap_or_default())?;
+    }
+    Ok(())
+}
+
+// encode_symbol returns the symbol for the lower 5 bits of `v`.
+#[inline]
+fn encode_symbol(v: u8) -> u8 {
+    // The index is always in range, so the fallback is never used.
+    ENCODE_SYMBOLS
+        .get((v & 0x1f) as usize)
+        .copied()
+        .unwrap_or(b'0')
+}
+
+// Copies bytes from `src` to the beginning of `dest` as many as both slices have
+// and returns the number of copied bytes. Unlike `copy_from_slice`, this never panics
+// on a length mismatch.
+#[inline]
+fn copy_prefix(dest: &mut [u8], src: &[u8]) -> usize {
+    let len = dest.len().min(src.len());
+    if let (Some(dest), Some(src)) = (dest.get_mut(..len), src.get(..len)) {
+        dest.copy_from_slice(src);
+    }
+    len
+}
+
+// The values DECODE_SYMBOLS and decode_symbol return for bytes which are not
+// valid symbols: `U`, which is excluded from the alphabet, and any other byte.
+// Valid symbols have values below 32, so a single unsigned comparison tells
+// them apart.
+const XU: u8 = 0xfe;
+const XX: u8 = 0xff;
+
+// is_symbol_value returns whether `v` returned by decode_symbol is the value of
+// a valid symbol.
+#[inline]
+fn is_symbol_value(v: u8) -> bool {
+    v < 32
+}
+
+// decode_symbol returns the value of the symbol `b`, or XU or XX if `b` is not
+// a valid symbol.
+#[cfg(not(feature = "compact-decode"))]
+#[inline]
+fn decode_symbol(b: u8) -> u8 {
+    // The table covers all `u8` values, so the fallback is never used.
+    DECODE_SYMBOLS.get(b as usize).copied().unwrap_or(XX)
+}
+
+// This computes the same values as DECODE_SYMBOLS with ranges instead of
+// a 256 bytes table.
+#[cfg(feature = "compact-decode")]
+fn decode_symbol(b: u8) -> u8 {
+    match b {
+        b'0'..=b'9' => b - b'0',
+        b'O' | b'o' => 0,
+        b'I' | b'i' | b'L' | b'l' => 1,
+        b'A'..=b'H' => b - b'A' + 10,
+        b'a'..=b'h' => b - b'a' + 10,
+        b'J' | b'K' => b - b'J' + 18,
+        b'j' | b'k' => b - b'j' + 18,
+        b'M' | b'N' => b - b'M' + 20,
+        b'm' | b'n' => b - b'm' + 20,
+        b'P'..=b'T' => b - b'P' + 22,
+        b'p'..=b't' => b - b'p' + 22,
+        b'V'..=b'Z' => b - b'V' + 27,
+        b'v'..=b'z' => b - b'v' + 27,
+        b'U' => XU,
+        _ => XX,
+    }
+}
+
+#[cfg(any(
+    all(test, feature = "std"),
+    not(feature = "compact-decode"),
+    feature = "portable-simd"
+))]
+const DECODE_SYMBOLS: [u8; 256] = [
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 0-15 */
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 16-31 */
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 32-47 */
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, XX, XX, XX, XX, XX, XX, /* 48-63 */
+    XX, 10, 11, 12, 13, 14, 15, 16, 17, 1, 18, 19, 1, 20, 21, 0, /* 64-79 */
+    22, 23, 24, 25, 26, XU, 27, 28, 29, 30, 31, XX, XX, XX, XX, XX, /* 80-95 */
+    XX, 10, 11, 12, 13, 14, 15, 16, 17, 1, 18, 19, 1, 20, 21, 0, /* 96-111 */
+    22, 23, 24, 25, 26, XX, 27, 28, 29, 30, 31, XX, XX, XX, XX, XX, /* 112-127 */
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 128-143 */
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 144-159 */
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 160-175 */
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 176-191 */
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 192-207 */
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 208-223 */
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 224-239 */
+    XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, XX, /* 240-255 */
 ];
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -569,6 +2026,88 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_append_reserves() {
+        let plain = [0xa5; 1000];
+        let encoded = encode_to_vec(&plain);
+
+        let mut dest = Vec::new();
+        append_encoded_to_vec(&mut dest, plain.iter());
+        assert_eq!(dest.capacity(), encoded.len());
+        let mut dest = String::new();
+        append_encoded_to_string(&mut dest, plain.iter());
+        assert_eq!(dest.capacity(), encoded.len());
+        let mut dest = Vec::new();
+        append_decoded_to_vec(&mut dest, encoded.iter()).unwrap();
+        assert_eq!(dest.capacity(), plain.len());
+
+        let text = encode_to_vec("Hello, world!".repeat(100).as_bytes());
+        let mut dest = String::new();
+        append_decoded_to_string(&mut dest, text.iter()).unwrap();
+        assert_eq!(dest.capacity(), 1300);
+
+        // Exact sizes don't grow `dest` more than needed.
+        let mut dest = vec![b'0'; 1000];
+        append_encoded_to_vec(&mut dest, plain[..10].iter());
+        assert_eq!(dest.capacity(), 1016);
+        let mut dest = "0".repeat(1000);
+        append_decoded_to_string(&mut dest, b"CSQPYRK1".iter()).unwrap();
+        assert_eq!(dest.capacity(), 1005);
+        // Inexact sizes grow it amortized.
+        let mut dest = vec![b'0'; 1000];
+        append_encoded_to_vec(&mut dest, plain[..10].iter().filter(|_| true));
+        assert!(dest.capacity() >= 2000);
+
+        // A lower bound larger than any allocation doesn't fail.
+        struct Overstated<'a>(core::slice::Iter<'a, u8>);
+
+        impl<'a> Iterator for Overstated<'a> {
+            type Item = &'a u8;
+
+            fn next(&mut self) -> Option<&'a u8> {
+                self.0.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (usize::MAX, None)
+            }
+        }
+
+        let mut dest = Vec::new();
+        append_encoded_to_vec(&mut dest, Overstated([0; 5].iter()));
+        assert_eq!(dest, b"00000000");
+        #[cfg(feature = "smallvec")]
+        {
+            let encoded = encode_to_smallvec::<[u8; 8], _>(Overstated([0; 5].iter()));
+            assert_eq!(&encoded[..], b"00000000");
+            let decoded = decode_to_smallvec::<[u8; 8], _>(Overstated(b"00000000".iter()));
+            assert_eq!(decoded.as_deref(), Ok(&[0; 5][..]));
+        }
+    }
+
+    #[test]
+    fn test_capacity_overflow() {
+        // The results computed in 128 bits.
+        let decode = |len: usize| (len as u128 * 5 / 8) as usize;
+        let encode =
+            |len: usize| core::convert::TryFrom::try_from((len as u128 * 8).div_ceil(5)).ok();
+        let max_encodable = (usize::MAX as u128 * 5 / 8) as usize;
+        for len in (0..100)
+            .chain(usize::MAX / 8 - 100..usize::MAX / 8 + 100)
+            .chain(max_encodable - 100..max_encodable + 100)
+            .chain(usize::MAX - 100..=usize::MAX)
+        {
+            assert_eq!(capacity_hint_for_decode(len), decode(len), "{}", len);
+            assert_eq!(checked_capacity_for_encode(len), encode(len), "{}", len);
+            assert_eq!(
+                capacity_hint_for_encode(len),
+                encode(len).unwrap_or(usize::MAX)
+            );
+        }
+        assert!(checked_capacity_for_encode(max_encodable).is_some());
+        assert_eq!(checked_capacity_for_encode(max_encodable + 1), None);
+    }
+
     #[test]
     fn test_decode_corner_cases() {
         const CORNER_CASES: [TestCase; 3] = [
@@ -597,14 +2136,349 @@ mod tests {
         let res = decode_to_string(b"U");
         assert!(res.is_err());
         let err = res.as_ref().err().unwrap();
-        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(matches!(err, Error::InvalidSymbol { byte: b'U', .. }));
         assert_eq!(format!("{}", err), "invalid symbol value U");
 
+        let res = decode_to_string(b":");
+        assert!(matches!(res, Err(Error::InvalidSymbol { byte: b':', .. })));
+
         let res = decode_to_string(b"confuse");
         assert!(res.is_err());
         let err = res.as_ref().err().unwrap();
-        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(matches!(err, Error::InvalidSymbol { byte: b'u', .. }));
         assert_eq!(format!("{}", err), "invalid symbol value u");
+
+        let err = std::io::Error::from(res.err().unwrap());
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(format!("{}", err), "invalid symbol value u");
+    }
+
+    #[test]
+    fn test_decode_utf8() {
+        let plain = "Grüße, 世界! ".repeat(20);
+        let encoded = encode_to_string(plain.as_bytes());
+        assert_eq!(decode_to_string(encoded.as_bytes()).ok().unwrap(), plain);
+
+        let mut dest = String::from("prefix ");
+        append_decoded_to_string(&mut dest, encoded.as_bytes().iter()).unwrap();
+        assert_eq!(dest, format!("prefix {}", plain));
+
+        for invalid in [&b"\xff"[..], b"\xe4\xb8", b"a\xe4\xb8a"].iter() {
+            let encoded = encode_to_string(*invalid);
+            let err = decode_to_string(encoded.as_bytes()).err().unwrap();
+            assert!(matches!(err, Error::InvalidUtf8));
+            let err = append_decoded_to_string(&mut String::new(), encoded.as_bytes().iter())
+                .err()
+                .unwrap();
+            assert!(matches!(err, Error::InvalidUtf8));
+        }
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn test_smallvec() {
+        for c in CASES.iter() {
+            let encoded = encode_to_smallvec::<[u8; 16], _>(c.plain.as_bytes());
+            assert_eq!(&encoded[..], c.encoded.as_bytes());
+            assert_eq!(encoded.spilled(), c.encoded.len() > 16);
+            let decoded = decode_to_smallvec::<[u8; 16], _>(c.encoded.as_bytes())
+                .ok()
+                .unwrap();
+            assert_eq!(&decoded[..], c.plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_slice() {
+        for c in CASES.iter() {
+            let mut buf = [0; 128];
+            let len = encode_to_slice(c.plain.as_bytes(), &mut buf).ok().unwrap();
+            assert_eq!(&buf[..len], c.encoded.as_bytes());
+            let len = decode_to_slice(c.encoded.as_bytes(), &mut buf)
+                .ok()
+                .unwrap();
+            assert_eq!(&buf[..len], c.plain.as_bytes());
+
+            let mut short = [0; 1];
+            let err = encode_to_slice(c.plain.as_bytes(), &mut short)
+                .err()
+                .unwrap();
+            assert!(matches!(err, Error::BufferTooSmall));
+            if c.plain.len() > 1 {
+                let err = decode_to_slice(c.encoded.as_bytes(), &mut short)
+                    .err()
+                    .unwrap();
+                assert!(matches!(err, Error::BufferTooSmall));
+            }
+        }
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn test_heapless() {
+        for c in CASES.iter() {
+            let mut encoded = heapless::String::<128>::new();
+            append_encoded_to_heapless_string(&mut encoded, c.plain.as_bytes()).unwrap();
+            assert_eq!(encoded.as_str(), c.encoded);
+            let mut decoded = heapless::Vec::<u8, 128>::new();
+            append_decoded_to_heapless_vec(&mut decoded, c.encoded.as_bytes()).unwrap();
+            assert_eq!(&decoded, c.plain.as_bytes());
+
+            let mut short = heapless::String::<1>::new();
+            let err = append_encoded_to_heapless_string(&mut short, c.plain.as_bytes()).err();
+            assert!(matches!(err, Some(Error::BufferTooSmall)));
+            assert!(short.is_empty());
+        }
+
+        let mut dest = heapless::Vec::<u8, 16>::new();
+        let err = append_decoded_to_heapless_vec(&mut dest, b"CSQPU").err();
+        assert!(matches!(err, Some(Error::InvalidSymbol { byte: b'U', .. })));
+        assert!(dest.is_empty());
+
+        // Any byte iterator works, also longer than a chunk.
+        let plain = [b'f'; 2 * ITER_BYTE_CHUNK_LEN + 1];
+        let mut encoded = heapless::Vec::<u8, 4096>::new();
+        append_encoded_to_heapless_vec(&mut encoded, plain.iter()).unwrap();
+        assert_eq!(&encoded[..], CLOCKWORK.encode(&plain[..]).as_bytes());
+        let mut decoded = heapless::Vec::<u8, 4096>::new();
+        append_decoded_to_heapless_vec(&mut decoded, encoded.iter().filter(|_| true)).unwrap();
+        assert_eq!(&decoded[..], &plain[..]);
+    }
+
+    #[cfg(feature = "arrayvec")]
+    #[test]
+    fn test_arrayvec() {
+        for c in CASES.iter() {
+            let mut encoded = arrayvec::ArrayString::<128>::new();
+            append_encoded_to_array_string(&mut encoded, c.plain.as_bytes()).unwrap();
+            assert_eq!(encoded.as_str(), c.encoded);
+            let mut decoded = arrayvec::ArrayVec::<u8, 128>::new();
+            append_decoded_to_array_vec(&mut decoded, c.encoded.as_bytes()).unwrap();
+            assert_eq!(&decoded[..], c.plain.as_bytes());
+
+            let mut short = arrayvec::ArrayString::<1>::new();
+            let err = append_encoded_to_array_string(&mut short, c.plain.as_bytes()).err();
+            assert!(matches!(err, Some(Error::BufferTooSmall)));
+            assert!(short.is_empty());
+        }
+
+        let mut dest = arrayvec::ArrayVec::<u8, 16>::new();
+        let err = append_decoded_to_array_vec(&mut dest, b"CSQPU").err();
+        assert!(matches!(err, Some(Error::InvalidSymbol { byte: b'U', .. })));
+        assert!(dest.is_empty());
+
+        // Any byte iterator works, also longer than a chunk.
+        let plain = [b'f'; 2 * ITER_BYTE_CHUNK_LEN + 1];
+        let mut encoded = arrayvec::ArrayVec::<u8, 4096>::new();
+        append_encoded_to_array_vec(&mut encoded, plain.iter()).unwrap();
+        assert_eq!(&encoded[..], CLOCKWORK.encode(&plain[..]).as_bytes());
+        let mut decoded = arrayvec::ArrayVec::<u8, 4096>::new();
+        append_decoded_to_array_vec(&mut decoded, encoded.iter().filter(|_| true)).unwrap();
+        assert_eq!(&decoded[..], &plain[..]);
+    }
+
+    #[test]
+    fn test_into_input() {
+        let plain = String::from("foobar");
+        let bytes = plain.clone().into_bytes();
+        assert_eq!(CLOCKWORK.encode(&plain), "CSQPYRK1E8");
+        assert_eq!(CLOCKWORK.encode(plain.as_str()), "CSQPYRK1E8");
+        assert_eq!(CLOCKWORK.encode(&bytes), "CSQPYRK1E8");
+        assert_eq!(CLOCKWORK.encode(bytes.iter()), "CSQPYRK1E8");
+        assert_eq!(CLOCKWORK.encode(b"foobar"), "CSQPYRK1E8");
+        assert_eq!(CLOCKWORK.decode("CSQPYRK1E8").ok().unwrap(), bytes);
+        assert_eq!(
+            CLOCKWORK.decode(&String::from("CSQPYRK1E8")).ok().unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn test_iterator_input() {
+        // Non-slice iterators, longer than a chunk, with the length unknown.
+        let plain: Vec<u8> = (0..=255).collect();
+        let encoded = encode_to_string(&plain);
+        let symbols = || encoded.as_bytes().iter().filter(|_| true);
+        let bytes = || plain.iter().take(100).chain(plain[100..].iter());
+
+        assert_eq!(encode_to_vec(bytes()), encoded.as_bytes());
+        assert_eq!(encode_to_string(bytes()), encoded);
+        assert_eq!(decode_to_vec(symbols()).ok().unwrap(), plain);
+        let mut buf = [0; 512];
+        let len = encode_to_slice(bytes(), &mut buf).ok().unwrap();
+        assert_eq!(&buf[..len], encoded.as_bytes());
+        let len = decode_to_slice(symbols(), &mut buf).ok().unwrap();
+        assert_eq!(&buf[..len], &plain[..]);
+        let err = decode_to_slice(symbols(), &mut buf[..100]).err().unwrap();
+        assert!(matches!(err, Error::BufferTooSmall));
+
+        let text = encode_to_string(b"Hello, world!");
+        let symbols = text.as_bytes().iter().take(8);
+        assert_eq!(decode_to_string(symbols).ok().unwrap(), "Hello");
+        let symbols = text.as_bytes().iter().take(8);
+        assert_eq!(decode_to_string_lossy(symbols).ok().unwrap(), "Hello");
+        let err = decode_to_vec(b"CSQPY".iter().chain(b"U")).err().unwrap();
+        assert!(matches!(err, Error::InvalidSymbol { byte: b'U', .. }));
+    }
+
+    #[test]
+    fn test_error_position() {
+        let mut input = encode_to_vec(&[0; 100]);
+        input.extend_from_slice(b"CSQPU");
+        let expected = Error::InvalidSymbol {
+            byte: b'U',
+            position: 164,
+        };
+        assert_eq!(
+            decode_slice(&mut Vec::new(), &input).err(),
+            Some(expected.clone())
+        );
+        let symbols = input.iter().filter(|_| true);
+        assert_eq!(decode_to_vec(symbols).err(), Some(expected.clone()));
+        let mut buf = [0; 128];
+        assert_eq!(
+            decode_to_slice(&input, &mut buf).err(),
+            Some(expected.clone())
+        );
+        let err = append_decoded_to_vec(&mut Vec::new(), input.iter()).err();
+        assert_eq!(err, Some(expected));
+    }
+
+    #[test]
+    fn test_slice_and_iter_paths_agree() {
+        let input: Vec<u8> = (0..=255).collect();
+        for len in 0..input.len() {
+            let mut encoded = Vec::new();
+            append_encoded_to_vec(&mut encoded, input[..len].iter());
+            assert_eq!(encode_to_vec(&input[..len]), encoded);
+
+            let mut decoded = Vec::new();
+            append_decoded_to_vec(&mut decoded, encoded.iter()).unwrap();
+            assert_eq!(decode_to_vec(&encoded).ok().unwrap(), decoded);
+            assert_eq!(decoded, &input[..len]);
+        }
+    }
+
+    #[test]
+    fn test_no_panic() {
+        let inputs: Vec<Vec<u8>> = (0..=255u8)
+            .map(|b| {
+                (0..(b as usize % 23))
+                    .map(|i| b.wrapping_mul(i as u8))
+                    .collect()
+            })
+            .collect();
+        for input in inputs.iter() {
+            for dest_len in 0..20 {
+                let mut dest = vec![0; dest_len];
+                let _ = decode_to_slice(input, &mut dest);
+                let _ = encode_to_slice(input, &mut dest);
+                let _ = Encoder::new().update(input, &mut dest);
+                let _ = Encoder::new().finish(&mut dest);
+                let mut decoder = Decoder::new();
+                let _ = decoder.update(input, &mut dest);
+                let _ = decoder.finish(&mut dest);
+                let _ = StackEncoder::<8>::new().encode(input);
+            }
+            let _ = decode_to_string(input);
+            let _ = append_decoded_to_string(&mut String::new(), input.iter());
+        }
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive() {
+        #[derive(Base32, PartialEq)]
+        struct Id([u8; 3]);
+
+        #[derive(Base32, PartialEq)]
+        struct Blob {
+            bytes: Vec<u8>,
+        }
+
+        let id: Id = "CSQPY".parse().unwrap();
+        assert_eq!(id.0, *b"foo");
+        assert_eq!(id.to_string(), "CSQPY");
+        assert_eq!(format!("{:?}", id), "Id(\"CSQPY\")");
+        let err = "CSQPYRK".parse::<Id>().err().unwrap();
+        assert!(matches!(
+            err.error(),
+            Error::LengthMismatch {
+                expected: 3,
+                actual: 4
+            }
+        ));
+        let err = "CSQPYRK1".parse::<Id>().err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "8 symbols decode to 5 bytes, but 3 bytes are expected"
+        );
+        assert_eq!("CSUPY".parse::<Id>().err().unwrap().position(), 2);
+
+        let blob: Blob = "csqpyrk1e8".parse().unwrap();
+        assert_eq!(blob.bytes, b"foobar");
+        assert_eq!(
+            format!("{} {:?}", blob, blob),
+            "CSQPYRK1E8 Blob(\"CSQPYRK1E8\")"
+        );
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&id).unwrap();
+            assert_eq!(json, r#""CSQPY""#);
+            assert!(serde_json::from_str::<Id>(&json).unwrap() == id);
+            let json = serde_json::to_string(&blob).unwrap();
+            assert!(serde_json::from_str::<Blob>(&json).unwrap() == blob);
+        }
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_integer() {
+        use core::num::NonZeroU32;
+
+        #[derive(Base32, Clone, Copy, PartialEq)]
+        struct UserId(NonZeroU32);
+
+        #[derive(Base32, PartialEq)]
+        struct Serial {
+            value: u64,
+        }
+
+        let id = UserId(NonZeroU32::new(1).unwrap());
+        assert_eq!(id.to_string(), "0000008");
+        assert_eq!(format!("{:?}", id), "UserId(\"0000008\")");
+        assert!("0000008".parse::<UserId>().unwrap() == id);
+        let err = "0000000".parse::<UserId>().err().unwrap();
+        assert!(matches!(err.error(), Error::OutOfRange));
+        let err = "00000080".parse::<UserId>().err().unwrap();
+        assert!(matches!(err.error(), Error::LengthMismatch { .. }));
+
+        let serial = Serial { value: u64::MAX };
+        assert_eq!(serial.to_string(), "ZZZZZZZZZZZZY");
+        assert!("ZZZZZZZZZZZZY".parse::<Serial>().unwrap() == serial);
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&id).unwrap();
+            assert_eq!(json, r#""0000008""#);
+            assert!(serde_json::from_str::<UserId>(&json).unwrap() == id);
+            assert!(serde_json::from_str::<UserId>(r#""0000000""#).is_err());
+        }
+    }
+
+    #[test]
+    fn test_decode_symbol() {
+        for b in 0..=255u8 {
+            assert_eq!(decode_symbol(b), DECODE_SYMBOLS[b as usize], "byte {}", b);
+        }
+        assert_eq!(decode_symbol(b'U'), XU);
+        assert!(!is_symbol_value(XU) && !is_symbol_value(XX));
+        // Digits and letters of both cases except `U` and `u`.
+        assert_eq!(
+            (0..=255).filter(|&b| is_valid_symbol(b)).count(),
+            10 + 25 * 2
+        );
     }
 
     #[test]
@@ -0,0 +1,69 @@
+//! A native Node.js addon built with [napi-rs](https://napi.rs).
+//!
+//! Build the addon with the `@napi-rs/cli` package, or with cargo and copy the
+//! shared library to a `.node` file:
+//!
+//! ```sh
+//! cargo rustc --release --features napi --crate-type cdylib
+//! cp target/release/libclockwork_base32.so clockwork_base32.node
+//! ```
+//!
+//! The Node-API functions are resolved when Node.js loads the addon, so
+//! executables cannot link to this crate with the `napi` feature enabled.
+//!
+//! The addon exports these functions, passing bytes as `Buffer` without copying
+//! them into a JavaScript string first:
+//!
+//! ```ts
+//! export function encode(input: Buffer): string;
+//! export function decode(input: string | Buffer): Buffer;
+//! export function validate(input: string | Buffer, strict?: boolean): void;
+//! ```
+//!
+//! `decode` and `validate` throw an `Error` with the position of the problem.
+
+use alloc::string::{String, ToString};
+
+use napi::bindgen_prelude::{Buffer, Either};
+use napi_derive::napi;
+
+use crate::{check_input, decode_checked, encode_to_string, ParseError};
+
+/// Encodes bytes to a string.
+#[napi]
+pub fn encode(input: Buffer) -> String {
+    encode_to_string(&*input)
+}
+
+/// Decodes a string or bytes, accepting lowercase symbols and the aliases
+/// `I`, `L` and `O`.
+///
+/// # Errors
+/// Throws an `Error` if the input contains an invalid symbol.
+#[napi]
+pub fn decode(input: Either<String, Buffer>) -> napi::Result<Buffer> {
+    decode_checked(as_bytes(&input))
+        .map(Buffer::from)
+        .map_err(to_napi_error)
+}
+
+/// Checks whether a string or bytes can be decoded, or with `strict` whether it
+/// is also a canonical encoding.
+///
+/// # Errors
+/// Throws an `Error` if the input is invalid.
+#[napi]
+pub fn validate(input: Either<String, Buffer>, strict: Option<bool>) -> napi::Result<()> {
+    check_input(as_bytes(&input), strict.unwrap_or_default()).map_err(to_napi_error)
+}
+
+fn as_bytes(input: &Either<String, Buffer>) -> &[u8] {
+    match input {
+        Either::A(s) => s.as_bytes(),
+        Either::B(b) => b,
+    }
+}
+
+fn to_napi_error(err: ParseError) -> napi::Error {
+    napi::Error::from_reason(err.to_string())
+}
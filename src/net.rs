@@ -0,0 +1,138 @@
+//! IPv6 addresses and network prefixes as 26 symbols, for identifiers which
+//! embed addresses and must be safe in DNS labels and file names.
+//!
+//! Unlike the textual form of IPv6 addresses, the encoded form has no colons and
+//! always the same length. A network prefix is encoded as its address with the
+//! bits after the prefix length cleared, so all addresses in a network give the
+//! same identifier.
+//!
+//! # Examples
+//! ```
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32::net;
+//! use std::net::Ipv6Addr;
+//!
+//! let addr: Ipv6Addr = "2001:db8::1".parse().unwrap();
+//! let encoded = net::encode_ipv6(addr);
+//! assert_eq!(encoded.as_str(), "400GVE00000000000000000004");
+//! assert_eq!(net::decode_ipv6(&*encoded)?, addr);
+//!
+//! let network = net::encode_ipv6_prefix(addr, 32);
+//! assert_eq!(network.as_str(), "400GVE00000000000000000000");
+//! assert_eq!(
+//!     net::decode_ipv6_prefix(&*network, 32)?,
+//!     "2001:db8::".parse::<Ipv6Addr>().unwrap()
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+use core::net::Ipv6Addr;
+
+use crate::hwaddr::decode_fixed;
+use crate::{Error, IntoInput, Result, StackEncoder};
+
+/// The length of an encoded IPv6 address or network prefix in symbols.
+pub const IPV6_ENCODED_LEN: usize = 26;
+
+/// Encodes an IPv6 address to 26 symbols.
+pub fn encode_ipv6(addr: Ipv6Addr) -> StackEncoder<IPV6_ENCODED_LEN> {
+    let mut encoder = StackEncoder::new();
+    // The encoder has the exact length, so this never fails.
+    let _ = encoder.encode(&addr.octets());
+    encoder
+}
+
+/// Decodes an IPv6 address from 26 symbols.
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte or is not 26 symbols
+/// long.
+pub fn decode_ipv6<'a, I>(input: I) -> Result<Ipv6Addr>
+where
+    I: IntoInput<'a>,
+{
+    decode_fixed(input.into_input(), IPV6_ENCODED_LEN).map(Ipv6Addr::from)
+}
+
+/// Encodes the network prefix of `prefix_len` bits of `addr` to 26 symbols.
+///
+/// The bits after the prefix are cleared. A `prefix_len` over 128 is taken as
+/// 128.
+pub fn encode_ipv6_prefix(addr: Ipv6Addr, prefix_len: u8) -> StackEncoder<IPV6_ENCODED_LEN> {
+    encode_ipv6(Ipv6Addr::from(u128::from(addr) & prefix_mask(prefix_len)))
+}
+
+/// Decodes a network prefix of `prefix_len` bits from 26 symbols.
+///
+/// # Errors
+/// Returns [`Err`] like [`decode_ipv6`], or [`Error::NonCanonical`] if the
+/// bits after the prefix are not zero.
+pub fn decode_ipv6_prefix<'a, I>(input: I, prefix_len: u8) -> Result<Ipv6Addr>
+where
+    I: IntoInput<'a>,
+{
+    let addr = u128::from(decode_ipv6(input)?);
+    if addr & !prefix_mask(prefix_len) != 0 {
+        return Err(Error::NonCanonical);
+    }
+    Ok(Ipv6Addr::from(addr))
+}
+
+// prefix_mask returns the mask of the first `prefix_len` bits.
+fn prefix_mask(prefix_len: u8) -> u128 {
+    u128::MAX
+        .checked_shl(128 - u32::from(prefix_len.min(128)))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv6() {
+        for addr in [
+            Ipv6Addr::UNSPECIFIED,
+            Ipv6Addr::LOCALHOST,
+            Ipv6Addr::from(u128::MAX),
+            Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0xff00, 0x42, 0x8329),
+        ] {
+            let encoded = encode_ipv6(addr);
+            assert_eq!(encoded.len(), IPV6_ENCODED_LEN);
+            assert_eq!(decode_ipv6(&*encoded).unwrap(), addr);
+            assert_eq!(decode_ipv6_prefix(&*encoded, 128).unwrap(), addr);
+        }
+        assert!(matches!(
+            decode_ipv6("0000000000000000000000000"),
+            Err(Error::LengthMismatch {
+                expected: 16,
+                actual: 15
+            })
+        ));
+    }
+
+    #[test]
+    fn test_ipv6_prefix() {
+        let addr = Ipv6Addr::from(u128::MAX);
+        for (prefix_len, network) in [
+            (0, Ipv6Addr::UNSPECIFIED),
+            (16, Ipv6Addr::new(0xffff, 0, 0, 0, 0, 0, 0, 0)),
+            (
+                63,
+                Ipv6Addr::new(0xffff, 0xffff, 0xffff, 0xfffe, 0, 0, 0, 0),
+            ),
+            (128, addr),
+            (200, addr),
+        ] {
+            let encoded = encode_ipv6_prefix(addr, prefix_len);
+            assert_eq!(encoded.as_str(), encode_ipv6(network).as_str());
+            assert_eq!(decode_ipv6_prefix(&*encoded, prefix_len).unwrap(), network);
+        }
+        let encoded = encode_ipv6(Ipv6Addr::LOCALHOST);
+        assert!(matches!(
+            decode_ipv6_prefix(&*encoded, 64),
+            Err(Error::NonCanonical)
+        ));
+    }
+}
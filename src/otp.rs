@@ -0,0 +1,109 @@
+//! RFC 4648 base32 secrets as used by TOTP and HOTP authenticators.
+//!
+//! The `secret` parameter of `otpauth://` URIs holds the key in the RFC 4648
+//! alphabet, not in Clockwork Base32. These functions convert between such
+//! secrets and the raw key bytes, so authenticator related code does not need
+//! another base32 crate.
+//!
+//! Decoding is lenient like the authenticator apps: lowercase letters, spaces
+//! between groups and trailing `=` padding are accepted. Encoding gives
+//! uppercase letters without padding, which is the form `otpauth://` URIs use.
+//!
+//! # Examples
+//! ```
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32 as base32;
+//! use clockwork_base32::otp;
+//!
+//! let key = otp::decode_secret("jbsw y3dp ehpk 3pxp")?;
+//! assert_eq!(key, b"Hello!\xde\xad\xbe\xef");
+//! assert_eq!(otp::encode_secret(&key), "JBSWY3DPEHPK3PXP");
+//!
+//! // Store the key in Clockwork Base32 instead.
+//! assert_eq!(base32::encode_to_string(&key), "91JPRV3F47FAVFQF");
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{rfc4648, Error, Result};
+
+/// The RFC 4648 symbols in the order of their values.
+pub const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encodes a key to an RFC 4648 secret without padding.
+pub fn encode_secret(key: &[u8]) -> String {
+    rfc4648::encode(ALPHABET, false, key)
+}
+
+/// Encodes a key to an RFC 4648 secret padded with `=` to a multiple of 8
+/// symbols.
+pub fn encode_secret_padded(key: &[u8]) -> String {
+    rfc4648::encode(ALPHABET, true, key)
+}
+
+/// Decodes an RFC 4648 secret to the key bytes.
+///
+/// Lowercase letters, ASCII whitespace and trailing `=` padding are accepted.
+/// Trailing bits which do not form a whole byte are dropped.
+///
+/// # Errors
+/// Returns [`Error::InvalidSymbol`] if the secret contains a byte which is not
+/// in the alphabet, including `=` followed by symbols.
+pub fn decode_secret(secret: &str) -> Result<Vec<u8>> {
+    let secret = secret.trim_end_matches(|c: char| c == '=' || c.is_ascii_whitespace());
+    let bytes = secret.bytes().enumerate();
+    rfc4648::decode(
+        bytes
+            .filter(|(_, b)| !b.is_ascii_whitespace())
+            .map(|(position, byte)| value(byte).ok_or(Error::InvalidSymbol { byte, position })),
+    )
+}
+
+// value returns the value of a symbol, ignoring the case.
+fn value(b: u8) -> Option<u16> {
+    match b.to_ascii_uppercase() {
+        c @ b'A'..=b'Z' => Some(u16::from(c - b'A')),
+        c @ b'2'..=b'7' => Some(u16::from(c - b'2' + 26)),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    // The test vectors in RFC 4648 section 10.
+    const VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "MY======"),
+        ("fo", "MZXQ===="),
+        ("foo", "MZXW6==="),
+        ("foob", "MZXW6YQ="),
+        ("fooba", "MZXW6YTB"),
+        ("foobar", "MZXW6YTBOI======"),
+    ];
+
+    #[test]
+    fn test_vectors() {
+        for (key, secret) in VECTORS {
+            assert_eq!(encode_secret_padded(key.as_bytes()), secret);
+            assert_eq!(encode_secret(key.as_bytes()), secret.trim_end_matches('='));
+            assert_eq!(decode_secret(secret).unwrap(), key.as_bytes());
+            let lower = secret.trim_end_matches('=').to_ascii_lowercase();
+            assert_eq!(decode_secret(&lower).unwrap(), key.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        for (secret, byte, position) in
+            [("MZXW1", b'1', 4), ("MZ=XW", b'=', 2), ("MZXW-6", b'-', 4)]
+        {
+            let err = decode_secret(secret).unwrap_err();
+            assert_eq!(err, Error::InvalidSymbol { byte, position });
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Padding the payload up to a fixed or bucketed output length, for tokens whose
+//! payload length itself is sensitive.
+//!
+//! [`Clockwork::encode_padded`](crate::Clockwork::encode_padded) encodes a frame
+//! of a 4 bytes big-endian length marker, the payload and filler bytes, so every
+//! payload up to the size of a [`Padding`] gives an output of the same length.
+//! [`Clockwork::decode_padded`](crate::Clockwork::decode_padded) returns the
+//! payload without the marker and the filler.
+//!
+//! The filler is zero by default. Note that the marker and the payload are only
+//! encoded, not encrypted, so the length is hidden from those who see the length
+//! of the output, not from those who can decode it.
+//!
+//! # Examples
+//! ```
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32::pad::Padding;
+//! use clockwork_base32::CLOCKWORK;
+//!
+//! let padding = Padding::fixed(32);
+//! let short = CLOCKWORK.encode_padded(b"foo", &padding)?;
+//! let long = CLOCKWORK.encode_padded(b"Hello, world!", &padding)?;
+//! assert_eq!(short.len(), 32);
+//! assert_eq!(long.len(), 32);
+//! assert_eq!(CLOCKWORK.decode_padded(&short)?, b"foo");
+//! assert_eq!(CLOCKWORK.decode_padded(&long)?, b"Hello, world!");
+//!
+//! let padding = Padding::bucket(16);
+//! assert_eq!(padding.encoded_len(3), Some(16));
+//! assert_eq!(padding.encoded_len(13), Some(32));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BLOCK_BYTE_LEN, BLOCK_SYMBOL_LEN};
+
+/// The length of the length marker in bytes.
+pub const MARKER_LEN: usize = 4;
+
+// MAX_FRAME_LEN is the length of the longest frame whose encoding is no longer
+// than isize::MAX, the largest allocation.
+const MAX_FRAME_LEN: usize = (usize::MAX >> 1) / BLOCK_SYMBOL_LEN * BLOCK_BYTE_LEN;
+
+/// The output length of padded encoding.
+///
+/// Lengths are given in symbols and rounded up to a multiple of 8, a whole
+/// block, so the output has exactly that length.
+#[derive(Debug, Clone, Copy)]
+pub struct Padding {
+    size: Size,
+    fill: fn(&mut [u8]),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Size {
+    Fixed(usize),
+    Bucket(usize),
+}
+
+impl Padding {
+    /// Pads every payload to `symbols` symbols.
+    ///
+    /// Encoding fails for payloads which don't fit.
+    pub const fn fixed(symbols: usize) -> Self {
+        Self {
+            size: Size::Fixed(symbols),
+            fill: fill_zero,
+        }
+    }
+
+    /// Pads every payload to a multiple of `symbols` symbols, which is 8 if
+    /// `symbols` is 0.
+    pub const fn bucket(symbols: usize) -> Self {
+        Self {
+            size: Size::Bucket(symbols),
+            fill: fill_zero,
+        }
+    }
+
+    /// Returns the padding which fills the bytes after the payload with `fill`
+    /// instead of zeros, like a function writing random bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// use clockwork_base32::pad::Padding;
+    /// let padding = Padding::fixed(16).with_filler(|buf| buf.fill(0xa5));
+    /// ```
+    #[must_use]
+    pub const fn with_filler(self, fill: fn(&mut [u8])) -> Self {
+        Self {
+            size: self.size,
+            fill,
+        }
+    }
+
+    /// Returns the output length for a payload of `input_byte_len` bytes, or
+    /// [`None`] if the payload doesn't fit or the output would be longer than
+    /// `isize::MAX`.
+    pub fn encoded_len(&self, input_byte_len: usize) -> Option<usize> {
+        let frame_len = self.frame_len(input_byte_len)?;
+        Some(frame_len / BLOCK_BYTE_LEN * BLOCK_SYMBOL_LEN)
+    }
+
+    // frame_len returns the length in bytes of the frame holding the marker,
+    // a payload of `input_byte_len` bytes and the filler.
+    // The frame is at most MAX_FRAME_LEN bytes, so it and its encoding can be
+    // allocated.
+    pub(crate) fn frame_len(&self, input_byte_len: usize) -> Option<usize> {
+        let needed = input_byte_len.checked_add(MARKER_LEN)?;
+        let frame_len = match self.size {
+            Size::Fixed(symbols) => symbols
+                .div_ceil(BLOCK_SYMBOL_LEN)
+                .checked_mul(BLOCK_BYTE_LEN)
+                .filter(|&frame_len| needed <= frame_len)?,
+            Size::Bucket(symbols) => {
+                let blocks = symbols.div_ceil(BLOCK_SYMBOL_LEN).max(1);
+                let bucket_len = blocks.checked_mul(BLOCK_BYTE_LEN)?;
+                needed.div_ceil(bucket_len).checked_mul(bucket_len)?
+            }
+        };
+        Some(frame_len).filter(|&frame_len| frame_len <= MAX_FRAME_LEN)
+    }
+
+    pub(crate) fn fill(&self, filler: &mut [u8]) {
+        (self.fill)(filler)
+    }
+}
+
+fn fill_zero(filler: &mut [u8]) {
+    filler.fill(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, CLOCKWORK};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_padding() {
+        let input: Vec<u8> = (0..64).collect();
+        for (i, padding) in [Padding::fixed(110), Padding::bucket(10), Padding::bucket(0)]
+            .iter()
+            .enumerate()
+        {
+            let mut lens = Vec::new();
+            for len in 0..=input.len() {
+                let encoded = CLOCKWORK.encode_padded(&input[..len], padding).unwrap();
+                assert_eq!(Some(encoded.len()), padding.encoded_len(len));
+                assert_eq!(encoded.len() % 8, 0);
+                assert_eq!(CLOCKWORK.decode_padded(&encoded).unwrap(), &input[..len]);
+                lens.push(encoded.len());
+            }
+            if i == 0 {
+                assert!(lens.iter().all(|&len| len == 112));
+            }
+        }
+        assert_eq!(Padding::bucket(10).encoded_len(6), Some(16));
+        assert_eq!(Padding::bucket(10).encoded_len(7), Some(32));
+        assert_eq!(Padding::bucket(0).encoded_len(0), Some(8));
+        assert_eq!(Padding::fixed(8).encoded_len(1), Some(8));
+        assert_eq!(Padding::fixed(8).encoded_len(2), None);
+        assert_eq!(Padding::bucket(8).encoded_len(usize::MAX), None);
+        assert_eq!(Padding::fixed(usize::MAX).encoded_len(0), None);
+        assert_eq!(Padding::bucket(usize::MAX).encoded_len(0), None);
+        for padding in [Padding::fixed(usize::MAX), Padding::bucket(usize::MAX)].iter() {
+            assert!(matches!(
+                CLOCKWORK.encode_padded(b"foo", padding),
+                Err(Error::BufferTooSmall)
+            ));
+        }
+        // The longest frame is accepted, but too large to allocate.
+        let symbols = MAX_FRAME_LEN / BLOCK_BYTE_LEN * BLOCK_SYMBOL_LEN;
+        let padding = Padding::fixed(symbols);
+        assert_eq!(padding.encoded_len(0), Some(symbols));
+        assert_eq!(Padding::fixed(symbols + 1).encoded_len(0), None);
+        assert!(matches!(
+            CLOCKWORK.encode_padded(b"foo", &padding),
+            Err(Error::BufferTooSmall)
+        ));
+        assert!(matches!(
+            CLOCKWORK.encode_padded(b"fo", &Padding::fixed(8)),
+            Err(Error::BufferTooSmall)
+        ));
+
+        let padding = Padding::fixed(16).with_filler(|buf| buf.fill(0xff));
+        let encoded = CLOCKWORK.encode_padded(b"foo", &padding).unwrap();
+        assert_eq!(encoded, "000000V6DXQZZZZZ");
+        assert_eq!(CLOCKWORK.decode_padded(&encoded).unwrap(), b"foo");
+
+        assert!(matches!(
+            CLOCKWORK.decode_padded("000"),
+            Err(Error::LengthMismatch {
+                expected: 4,
+                actual: 1
+            })
+        ));
+        assert!(matches!(
+            CLOCKWORK.decode_padded("000001BR"),
+            Err(Error::LengthMismatch {
+                expected: 5,
+                actual: 1
+            })
+        ));
+    }
+}
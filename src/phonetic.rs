@@ -0,0 +1,123 @@
+//! Reading encoded codes out loud with the NATO phonetic alphabet, for support
+//! teams dictating recovery codes over the phone.
+//!
+//! Each symbol is read as its NATO word or digit name. The alphabet has no `I`,
+//! `L`, `O` and `U`, so no word sounds like a digit. Lowercase symbols and the
+//! aliases are read as the canonical symbol they decode to, and hyphens and
+//! whitespace in the input are ignored.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "alloc")]
+//! # fn main() -> Result<(), clockwork_base32::ParseError> {
+//! use clockwork_base32::phonetic;
+//!
+//! assert_eq!(phonetic::word(b'C'), Some("Charlie"));
+//! assert_eq!(phonetic::word(b'o'), Some("Zero"));
+//! assert_eq!(
+//!     phonetic::read_out("csqp-yrk1", 4)?,
+//!     "Charlie Sierra Quebec Papa / Yankee Romeo Kilo One"
+//! );
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "alloc"))]
+//! # fn main() {}
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+#[cfg(feature = "alloc")]
+use crate::ParseError;
+use crate::{decode_symbol, is_symbol_value};
+
+/// The words for the symbols in the order of their values.
+pub const WORDS: [&str; 32] = [
+    "Zero", "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Alfa",
+    "Bravo", "Charlie", "Delta", "Echo", "Foxtrot", "Golf", "Hotel", "Juliett", "Kilo", "Mike",
+    "November", "Papa", "Quebec", "Romeo", "Sierra", "Tango", "Victor", "Whiskey", "X-ray",
+    "Yankee", "Zulu",
+];
+
+/// The separator between groups in [`read_out`], a pause in dictation.
+pub const GROUP_SEPARATOR: &str = " / ";
+
+/// Returns the word for the symbol `b`, or [`None`] if it is not a valid symbol.
+pub fn word(b: u8) -> Option<&'static str> {
+    let v = decode_symbol(b);
+    if is_symbol_value(v) {
+        WORDS.get(usize::from(v)).copied()
+    } else {
+        None
+    }
+}
+
+/// Returns the words for the symbols of `input` separated by spaces, with
+/// [`GROUP_SEPARATOR`] after every `group_len` symbols, or no groups if
+/// `group_len` is 0.
+///
+/// # Errors
+/// Returns [`Err`] with the position of the first byte which is neither a
+/// symbol nor a hyphen or whitespace.
+#[cfg(feature = "alloc")]
+pub fn read_out(input: &str, group_len: usize) -> Result<String, ParseError> {
+    let mut output = String::new();
+    let mut symbol_len = 0;
+    for (i, b) in input.bytes().enumerate() {
+        if b == b'-' || b.is_ascii_whitespace() {
+            continue;
+        }
+        let word = word(b).ok_or_else(|| ParseError::invalid_symbol(b, i))?;
+        if symbol_len > 0 {
+            if group_len > 0 && symbol_len % group_len == 0 {
+                output.push_str(GROUP_SEPARATOR);
+            } else {
+                output.push(' ');
+            }
+        }
+        output.push_str(word);
+        symbol_len += 1;
+    }
+    Ok(output)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::ENCODE_SYMBOLS;
+
+    #[test]
+    fn test_word() {
+        for (&symbol, &word) in ENCODE_SYMBOLS.iter().zip(WORDS.iter()) {
+            assert_eq!(super::word(symbol), Some(word));
+            if symbol.is_ascii_alphabetic() {
+                assert_eq!(word.as_bytes()[0], symbol);
+                assert_eq!(super::word(symbol.to_ascii_lowercase()), Some(word));
+            }
+        }
+        assert_eq!(word(b'I'), Some("One"));
+        assert_eq!(word(b'l'), Some("One"));
+        assert_eq!(word(b'O'), Some("Zero"));
+        assert_eq!(word(b'U'), None);
+        assert_eq!(word(b'-'), None);
+    }
+
+    #[test]
+    fn test_read_out() {
+        assert_eq!(read_out("", 4).unwrap(), "");
+        assert_eq!(read_out("91J", 0).unwrap(), "Nine One Juliett");
+        assert_eq!(
+            read_out("91JPR V3F5", 3).unwrap(),
+            "Nine One Juliett / Papa Romeo Victor / Three Foxtrot Five"
+        );
+        let err = read_out("CS QPU", 4).unwrap_err();
+        assert!(matches!(
+            err.error(),
+            crate::Error::InvalidSymbol {
+                byte: b'U',
+                position: 5
+            }
+        ));
+        assert_eq!(err.position(), 5);
+    }
+}
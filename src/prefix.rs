@@ -0,0 +1,96 @@
+//! Prefixes of encoded IDs for abbreviated IDs like in git.
+//!
+//! Symbols are compared by their values, so lowercase symbols and the aliases
+//! `I`, `L` and `O` match the symbols they stand for, and an abbreviation typed
+//! by a user matches the canonical ID.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::prefix;
+//!
+//! let ids: [&[u8]; 3] = [b"CSQPYRK1E8", b"CSQPZ0A1E8", b"91JPRV3F5G"];
+//! assert!(prefix::is_prefix(b"csqpy", ids[0]));
+//! assert!(!prefix::is_prefix(b"csqpy", ids[1]));
+//!
+//! // The shortest prefixes telling the IDs apart.
+//! assert_eq!(prefix::unique_prefix_len(ids[0], ids), 5);
+//! assert_eq!(prefix::unique_prefix_len(ids[2], ids), 1);
+//! ```
+
+use crate::{decode_symbol, is_symbol_value};
+
+/// Returns whether `prefix` is a prefix of `id`, comparing symbols by their
+/// values.
+///
+/// An invalid symbol matches nothing, so a prefix with one is never a prefix.
+pub fn is_prefix(prefix: &[u8], id: &[u8]) -> bool {
+    prefix.len() <= id.len() && common_prefix_len(prefix, id) == prefix.len()
+}
+
+/// Returns the length of the longest common prefix of `a` and `b`, comparing
+/// symbols by their values.
+///
+/// The common prefix ends at the first invalid symbol.
+pub fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter()
+        .zip(b)
+        .take_while(|&(&a, &b)| {
+            let v = decode_symbol(a);
+            is_symbol_value(v) && v == decode_symbol(b)
+        })
+        .count()
+}
+
+/// Returns the length of the shortest prefix of `id` which is not a prefix of
+/// the other IDs in `ids`.
+///
+/// IDs equal to `id` are skipped, so `ids` may contain `id` itself. If `id` is
+/// a prefix of another ID, there is no such prefix and this returns the length
+/// of `id`.
+pub fn unique_prefix_len<I>(id: &[u8], ids: I) -> usize
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let len = ids
+        .into_iter()
+        .filter_map(|other| {
+            let other = other.as_ref();
+            let common = common_prefix_len(id, other);
+            if common == id.len() && common == other.len() {
+                None
+            } else {
+                Some(common + 1)
+            }
+        })
+        .max()
+        .unwrap_or(1);
+    len.min(id.len())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_prefix() {
+        assert!(is_prefix(b"", b"CSQP"));
+        assert!(is_prefix(b"CSQP", b"CSQP"));
+        assert!(is_prefix(b"csqp", b"CSQP"));
+        assert!(is_prefix(b"01", b"OL"));
+        assert!(!is_prefix(b"CSQPY", b"CSQP"));
+        assert!(!is_prefix(b"CSU", b"CSU"));
+        assert_eq!(common_prefix_len(b"ABCU", b"abcu"), 3);
+    }
+
+    #[test]
+    fn test_unique_prefix_len() {
+        let ids = ["CSQPYRK1E8", "CSQPYRK1", "CSQPZ0A1E8", "91JPRV3F5G"];
+        assert_eq!(unique_prefix_len(b"CSQPYRK1E8", ids), 9);
+        assert_eq!(unique_prefix_len(b"CSQPYRK1", ids), 8);
+        assert_eq!(unique_prefix_len(b"csqpz0a1e8", ids), 5);
+        assert_eq!(unique_prefix_len(b"91JPRV3F5G", ids), 1);
+        assert_eq!(unique_prefix_len(b"91JPRV3F5G", ["91JPRV3F5G"]), 1);
+        assert_eq!(unique_prefix_len(b"", ids), 0);
+    }
+}
@@ -0,0 +1,45 @@
+//! The commonly used types, traits and macros, to import them with one line.
+//!
+//! The free functions stay at the crate root, where they are called with the
+//! crate name like `clockwork_base32::encode_to_string`. Their method forms on
+//! [`CLOCKWORK`] are imported.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "alloc")]
+//! # fn main() -> Result<(), clockwork_base32::Error> {
+//! use clockwork_base32::prelude::*;
+//!
+//! let id = Base32String::encode(b"Hello");
+//! assert_eq!(id, "91JPRV3F");
+//! let parsed: Base32String = "91JPRV3F".parse()?;
+//! assert_eq!(parsed.decode(), b"Hello");
+//!
+//! let mut encoder = Encoder::new();
+//! let mut output = [0; 8];
+//! let (_, len) = encoder.update(b"Hello", &mut output);
+//! assert_eq!(&output[..len], b"91JPRV3F");
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "alloc"))]
+//! # fn main() {}
+//! ```
+
+pub use crate::engine::Engine;
+pub use crate::new_engine;
+pub use crate::{
+    Clockwork, Decoder, Encoder, Error, ErrorKind, IntoInput, Key, ParseError, StackEncoder,
+    Transcode, CLOCKWORK,
+};
+
+#[cfg(feature = "alloc")]
+pub use crate::{Base32Bytes, Base32String};
+
+#[cfg(feature = "std")]
+pub use crate::io::{DecodeReader, DecodeWriter, EncodeReader, EncodeWriter};
+
+#[cfg(feature = "secrecy")]
+pub use crate::SecretBase32;
+
+#[cfg(feature = "derive")]
+pub use crate::Base32;
@@ -0,0 +1,121 @@
+//! [`proptest`](::proptest) strategies for encoded text.
+//!
+//! With the `proptest` feature enabled, downstream crates can property-test
+//! their code handling encoded text with valid, lenient and corrupted inputs.
+//! [`Base32String`] and [`Base32Bytes`] also implement
+//! `proptest::arbitrary::Arbitrary`, so they can be generated with `any`.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::{decode_to_vec, proptest::corrupted};
+//! use proptest::prelude::*;
+//!
+//! // Usually in a #[test] function.
+//! proptest!(|(s in corrupted())| {
+//!     prop_assert!(decode_to_vec(s.as_bytes()).is_err());
+//! });
+//! ```
+
+use std::string::String;
+use std::vec::Vec;
+
+use ::proptest::arbitrary::{any, Arbitrary};
+use ::proptest::collection::vec;
+use ::proptest::sample::{select, Index};
+use ::proptest::strategy::{BoxedStrategy, Strategy};
+
+use crate::{is_valid_symbol, Base32Bytes, Base32String};
+
+/// The maximum length of the bytes and strings generated by the strategies.
+pub const MAX_LEN: usize = 64;
+
+// Characters which are not valid symbols, including the commonly mistyped ones.
+const INVALID_CHARS: &[char] = &['U', 'u', '-', '=', ' ', '\n', '*', '\u{e9}', '\u{ff10}'];
+
+/// Returns a strategy for the canonical encodings of up to [`MAX_LEN`] bytes.
+pub fn canonical() -> impl Strategy<Value = Base32String> {
+    vec(any::<u8>(), 0..=MAX_LEN).prop_map(|bytes| Base32String::encode(&bytes))
+}
+
+/// Returns a strategy for decodable strings of up to [`MAX_LEN`] symbols which
+/// are usually not canonical: they mix lowercase symbols and the aliases `I`,
+/// `L` and `O`, and have any length.
+pub fn lenient() -> impl Strategy<Value = String> {
+    let symbols: Vec<char> = (0..=u8::MAX)
+        .filter(|&b| is_valid_symbol(b))
+        .map(char::from)
+        .collect();
+    vec(select(symbols), 0..=MAX_LEN).prop_map(|chars| chars.into_iter().collect())
+}
+
+/// Returns a strategy for canonical encodings corrupted by replacing a symbol
+/// with, or inserting, a character which is not a valid symbol.
+///
+/// Decoding the generated strings always fails.
+pub fn corrupted() -> impl Strategy<Value = String> {
+    (
+        canonical(),
+        any::<Index>(),
+        select(INVALID_CHARS),
+        any::<bool>(),
+    )
+        .prop_map(|(encoded, index, invalid, insert)| {
+            let mut chars: Vec<char> = encoded.chars().collect();
+            let i = index.index(chars.len() + 1);
+            match chars.get_mut(i) {
+                Some(c) if !insert => *c = invalid,
+                _ => chars.insert(i, invalid),
+            }
+            chars.into_iter().collect()
+        })
+}
+
+impl Arbitrary for Base32String {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        canonical().boxed()
+    }
+}
+
+impl Arbitrary for Base32Bytes {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        vec(any::<u8>(), 0..=MAX_LEN)
+            .prop_map(Base32Bytes::new)
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode_to_vec;
+    use ::proptest::prelude::*;
+    use core::str::FromStr;
+
+    proptest! {
+        #[test]
+        fn test_canonical(encoded in canonical()) {
+            prop_assert_eq!(Base32String::from_str(&encoded).unwrap(), encoded);
+        }
+
+        #[test]
+        fn test_lenient(s in lenient()) {
+            prop_assert!(decode_to_vec(s.as_bytes()).is_ok());
+        }
+
+        #[test]
+        fn test_corrupted(s in corrupted()) {
+            prop_assert!(decode_to_vec(s.as_bytes()).is_err());
+        }
+
+        #[test]
+        fn test_arbitrary(bytes in any::<Base32Bytes>()) {
+            prop_assert_eq!(bytes.encode().decode(), bytes.as_bytes());
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! A Python extension module built with [PyO3](https://pyo3.rs).
+//!
+//! Build the module with [maturin](https://www.maturin.rs), or with cargo and
+//! copy the shared library to a file named like the module:
+//!
+//! ```sh
+//! cargo rustc --release --features python --crate-type cdylib
+//! cp target/release/libclockwork_base32.so clockwork_base32.so
+//! ```
+//!
+//! The module provides the same functions as this crate:
+//!
+//! ```python
+//! import clockwork_base32
+//!
+//! assert clockwork_base32.encode(b"Hello, world!") == "91JPRV3F5GG7EVVJDHJ22"
+//! assert clockwork_base32.decode("91JPRV3F5GG7EVVJDHJ22") == b"Hello, world!"
+//! assert clockwork_base32.decode(b"91jprv3f") == b"Hello"
+//! clockwork_base32.validate("91JPRV3F", strict=True)
+//! ```
+//!
+//! `decode` and `validate` raise `ValueError` with the position of the problem.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::pybacked::{PyBackedBytes, PyBackedStr};
+
+use crate::{check_input, decode_checked, encode_to_string, ParseError};
+
+// Encoded text given as either `str` or `bytes`.
+#[derive(FromPyObject)]
+enum Text {
+    Str(PyBackedStr),
+    Bytes(PyBackedBytes),
+}
+
+impl AsRef<[u8]> for Text {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Text::Str(s) => s.as_bytes(),
+            Text::Bytes(b) => b,
+        }
+    }
+}
+
+/// Encodes bytes to a string.
+#[pyfunction]
+fn encode(data: PyBackedBytes) -> String {
+    encode_to_string(&*data)
+}
+
+/// Decodes a string or bytes, accepting lowercase symbols and the aliases
+/// I, L and O.
+///
+/// Raises ValueError if the input contains an invalid symbol.
+#[pyfunction]
+fn decode(text: Text) -> PyResult<Vec<u8>> {
+    decode_checked(text.as_ref()).map_err(to_py_err)
+}
+
+/// Checks whether a string or bytes can be decoded, or with strict whether it
+/// is also a canonical encoding.
+///
+/// Raises ValueError if the input is invalid.
+#[pyfunction]
+#[pyo3(signature = (text, strict = false))]
+fn validate(text: Text, strict: bool) -> PyResult<()> {
+    check_input(text.as_ref(), strict).map_err(to_py_err)
+}
+
+/// Clockwork Base32 encoding and decoding.
+#[pymodule]
+fn clockwork_base32(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(encode, m)?)?;
+    m.add_function(wrap_pyfunction!(decode, m)?)?;
+    m.add_function(wrap_pyfunction!(validate, m)?)?;
+    Ok(())
+}
+
+fn to_py_err(err: ParseError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
@@ -0,0 +1,180 @@
+//! Encoded data in the alphanumeric mode of QR codes.
+//!
+//! The alphanumeric mode of QR codes stores digits, uppercase letters and a few
+//! punctuation characters in 5.5 bits each, while the byte mode takes 8 bits
+//! per character. The symbols of Clockwork Base32 are digits and uppercase
+//! letters, so the encoding functions of this crate always give text in the
+//! alphanumeric mode. Encoded text takes 8.8 bits per input byte there, instead
+//! of 12.8 bits for the same text in the byte mode.
+//!
+//! Uppercase is the canonical form, so convert lowercase input to uppercase
+//! before putting it in a QR code. Scanned text decodes in either case.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "alloc")]
+//! # {
+//! use clockwork_base32 as base32;
+//! use clockwork_base32::qr::{self, EcLevel};
+//!
+//! let id = [0x42; 16];
+//! let encoded = base32::encode_to_string(&id);
+//! assert!(encoded.bytes().all(qr::is_alphanumeric));
+//!
+//! // 16 bytes fit in a version 2 QR code with the medium error correction level.
+//! assert_eq!(qr::min_version(id.len(), EcLevel::M), Some(2));
+//! assert_eq!(qr::max_input_len(2, EcLevel::M), Some(23));
+//! # }
+//! ```
+
+use crate::capacity_hint_for_encode;
+
+/// The error correction level of a QR code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EcLevel {
+    /// Recovers about 7% of the data.
+    L,
+    /// Recovers about 15% of the data.
+    M,
+    /// Recovers about 25% of the data.
+    Q,
+    /// Recovers about 30% of the data.
+    H,
+}
+
+/// The largest version of QR codes.
+pub const MAX_VERSION: u8 = 40;
+
+// The bits of the mode indicator of a segment.
+const MODE_BIT_LEN: usize = 4;
+
+/// Returns whether `b` is a character of the alphanumeric mode.
+///
+/// All symbols given by encoding are.
+pub fn is_alphanumeric(b: u8) -> bool {
+    matches!(
+        b,
+        b'0'..=b'9' | b'A'..=b'Z' | b' ' | b'$' | b'%' | b'*' | b'+' | b'-' | b'.' | b'/' | b':'
+    )
+}
+
+/// Returns the bits of an alphanumeric segment holding the encoded result of
+/// `input_byte_len` bytes in a QR code of `version`, including the mode and
+/// length indicators.
+///
+/// Returns [`None`] if `version` is not in `1..=40`.
+pub fn alphanumeric_bit_len(input_byte_len: usize, version: u8) -> Option<usize> {
+    let symbol_len = capacity_hint_for_encode(input_byte_len);
+    let header_bit_len = MODE_BIT_LEN + count_bit_len(version)?;
+    Some(
+        (symbol_len / 2)
+            .saturating_mul(11)
+            .saturating_add(symbol_len % 2 * 6 + header_bit_len),
+    )
+}
+
+/// Returns the largest number of bytes whose encoded result fits in a QR code
+/// of `version` with `ec_level` as an alphanumeric segment.
+///
+/// Returns [`None`] if `version` is not in `1..=40`.
+pub fn max_input_len(version: u8, ec_level: EcLevel) -> Option<usize> {
+    let data_bit_len = data_bit_len(version, ec_level)?;
+    let bit_len = data_bit_len.saturating_sub(MODE_BIT_LEN + count_bit_len(version)?);
+    let symbol_len = bit_len / 11 * 2 + usize::from(bit_len % 11 >= 6);
+    Some(symbol_len * 5 / 8)
+}
+
+/// Returns the smallest version of QR codes which holds the encoded result of
+/// `input_byte_len` bytes with `ec_level` as an alphanumeric segment.
+///
+/// Returns [`None`] if the input is too long for any version.
+pub fn min_version(input_byte_len: usize, ec_level: EcLevel) -> Option<u8> {
+    (1..=MAX_VERSION)
+        .find(|&version| max_input_len(version, ec_level).is_some_and(|len| input_byte_len <= len))
+}
+
+// count_bit_len returns the bits of the character count indicator of an
+// alphanumeric segment.
+fn count_bit_len(version: u8) -> Option<usize> {
+    match version {
+        1..=9 => Some(9),
+        10..=26 => Some(11),
+        27..=MAX_VERSION => Some(13),
+        _ => None,
+    }
+}
+
+// data_bit_len returns the bits of data a QR code holds.
+fn data_bit_len(version: u8, ec_level: EcLevel) -> Option<usize> {
+    let lens = DATA_BIT_LENS.get(usize::from(version).checked_sub(1)?)?;
+    let len = match ec_level {
+        EcLevel::L => lens[0],
+        EcLevel::M => lens[1],
+        EcLevel::Q => lens[2],
+        EcLevel::H => lens[3],
+    };
+    Some(usize::from(len))
+}
+
+// The bits of data in QR codes of each version for the error correction levels
+// L, M, Q and H, from ISO/IEC 18004 table 7.
+const DATA_BIT_LENS: [[u16; 4]; 40] = [
+    [152, 128, 104, 72],
+    [272, 224, 176, 128],
+    [440, 352, 272, 208],
+    [640, 512, 384, 288],
+    [864, 688, 496, 368],
+    [1088, 864, 608, 480],
+    [1248, 992, 704, 528],
+    [1552, 1232, 880, 688],
+    [1856, 1456, 1056, 800],
+    [2192, 1728, 1232, 976],
+    [2592, 2032, 1440, 1120],
+    [2960, 2320, 1648, 1264],
+    [3424, 2672, 1952, 1440],
+    [3688, 2920, 2088, 1576],
+    [4184, 3320, 2360, 1784],
+    [4712, 3624, 2600, 2024],
+    [5176, 4056, 2936, 2264],
+    [5768, 4504, 3176, 2504],
+    [6360, 5016, 3560, 2728],
+    [6888, 5352, 3880, 3080],
+    [7456, 5712, 4096, 3248],
+    [8048, 6256, 4544, 3536],
+    [8752, 6880, 4912, 3712],
+    [9392, 7312, 5312, 4112],
+    [10208, 8000, 5744, 4304],
+    [10960, 8496, 6032, 4768],
+    [11744, 9024, 6464, 5024],
+    [12248, 9544, 6968, 5288],
+    [13048, 10136, 7288, 5608],
+    [13880, 10984, 7880, 5960],
+    [14744, 11640, 8264, 6344],
+    [15640, 12328, 8920, 6760],
+    [16568, 13048, 9368, 7208],
+    [17528, 13800, 9848, 7688],
+    [18448, 14496, 10288, 7888],
+    [19472, 15312, 10832, 8432],
+    [20528, 15936, 11408, 8768],
+    [21616, 16816, 12016, 9136],
+    [22496, 17728, 12656, 9776],
+    [23648, 18672, 13328, 10208],
+];
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_alphanumeric() {
+        for b in 0..=255 {
+            assert_eq!(
+                is_alphanumeric(b),
+                b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:".contains(&b)
+            );
+            if crate::is_valid_symbol(b) && !b.is_ascii_lowercase() {
+                assert!(is_alphanumeric(b));
+            }
+        }
+    }
+}
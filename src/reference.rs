@@ -0,0 +1,110 @@
+//! A naive reference implementation for differential testing.
+//!
+//! These functions process one bit at a time and look up symbols by scanning
+//! the alphabet. They are slow but simple enough to check by reading, so
+//! fuzzers and test harnesses can compare the optimized functions of this crate,
+//! including the `portable-simd` kernels, against them.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32 as base32;
+//! use clockwork_base32::reference;
+//!
+//! let input = b"Hello, world!";
+//! assert_eq!(reference::encode(input), base32::encode_to_vec(input));
+//! assert_eq!(
+//!     reference::decode(b"91jprv3f5gg7evvjdhj22").ok(),
+//!     base32::decode_to_vec(b"91jprv3f5gg7evvjdhj22").ok()
+//! );
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{Error, Result};
+
+/// The symbols in the order of their values.
+pub const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Encodes bytes, padding the last symbol with zero bits.
+pub fn encode(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mut bits = 0u32;
+    let mut bit_len = 0;
+    for &b in input {
+        bits = (bits << 8) | u32::from(b);
+        bit_len += 8;
+        while bit_len >= 5 {
+            bit_len -= 5;
+            output.push(symbol((bits >> bit_len) & 0x1f));
+        }
+    }
+    if bit_len > 0 {
+        output.push(symbol((bits << (5 - bit_len)) & 0x1f));
+    }
+    output
+}
+
+/// Decodes symbols, accepting lowercase symbols and the aliases `I`, `L` and
+/// `O`. Trailing bits which do not form a whole byte are dropped.
+///
+/// # Errors
+/// Returns [`Err`] if the input contains a invalid byte.
+pub fn decode(input: &[u8]) -> Result<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut bits = 0u32;
+    let mut bit_len = 0;
+    for (position, &byte) in input.iter().enumerate() {
+        bits = (bits << 5) | value(byte).ok_or(Error::InvalidSymbol { byte, position })?;
+        bit_len += 5;
+        if bit_len >= 8 {
+            bit_len -= 8;
+            output.push((bits >> bit_len) as u8);
+        }
+    }
+    Ok(output)
+}
+
+fn symbol(v: u32) -> u8 {
+    ALPHABET.get(v as usize).copied().unwrap_or(b'0')
+}
+
+fn value(b: u8) -> Option<u32> {
+    let b = match b.to_ascii_uppercase() {
+        b'O' => b'0',
+        b'I' | b'L' => b'1',
+        b => b,
+    };
+    ALPHABET.iter().position(|&s| s == b).map(|v| v as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_to_vec, encode_to_vec, is_valid_symbol};
+
+    #[test]
+    fn test_encode() {
+        let input: Vec<u8> = (0..=255).rev().collect();
+        for len in 0..input.len() {
+            assert_eq!(encode(&input[..len]), encode_to_vec(&input[..len]));
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        for b in 0..=255u8 {
+            assert_eq!(value(b).is_some(), is_valid_symbol(b), "{}", b);
+        }
+        let symbols: Vec<u8> = (0..=255).filter(|&b| is_valid_symbol(b)).collect();
+        for len in 0..symbols.len() {
+            assert_eq!(
+                decode(&symbols[..len]).unwrap(),
+                decode_to_vec(&symbols[..len]).unwrap()
+            );
+        }
+        assert!(matches!(
+            decode(b"CSQPU"),
+            Err(Error::InvalidSymbol { byte: b'U', .. })
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+// The 5-bit packing of the RFC 4648 base32 alphabets, shared by `otp` and
+// `compat`, which differ only in the alphabet, the padding and how they read
+// symbols.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::capacity_hint_for_encode;
+
+// encode returns the symbols of `data` in `alphabet`, padded with `=` to a
+// multiple of 8 symbols if `padding` is true.
+pub(crate) fn encode(alphabet: &[u8; 32], padding: bool, data: &[u8]) -> String {
+    let mut output = String::with_capacity(capacity_hint_for_encode(data.len()));
+    let mut bits = 0u16;
+    let mut bit_len = 0;
+    let symbol = |v: u16| {
+        char::from(
+            alphabet
+                .get(usize::from(v & 0x1f))
+                .copied()
+                .unwrap_or_default(),
+        )
+    };
+    for &b in data {
+        bits = (bits << 8) | u16::from(b);
+        bit_len += 8;
+        while bit_len >= 5 {
+            bit_len -= 5;
+            output.push(symbol(bits >> bit_len));
+        }
+    }
+    if bit_len > 0 {
+        output.push(symbol(bits << (5 - bit_len)));
+    }
+    if padding {
+        let padded_len = output.len().div_ceil(8) * 8;
+        while output.len() < padded_len {
+            output.push('=');
+        }
+    }
+    output
+}
+
+// decode returns the bytes of the 5-bit symbol values in `values`, or the
+// first error among them. Trailing bits which do not form a whole byte are
+// dropped.
+pub(crate) fn decode<E, I>(values: I) -> Result<Vec<u8>, E>
+where
+    I: IntoIterator<Item = Result<u16, E>>,
+{
+    let values = values.into_iter();
+    let mut output = Vec::with_capacity(values.size_hint().0 * 5 / 8);
+    let mut bits = 0u16;
+    let mut bit_len = 0;
+    for value in values {
+        bits = (bits << 5) | value?;
+        bit_len += 5;
+        if bit_len >= 8 {
+            bit_len -= 8;
+            output.push((bits >> bit_len) as u8);
+        }
+    }
+    Ok(output)
+}
@@ -0,0 +1,306 @@
+//! An adapter feeding encoded output into a ring buffer which hands out
+//! contiguous write grants, such as the producer half of a `bbqueue`.
+//!
+//! This module is available with the `bbqueue` feature.
+//!
+//! The ring buffer is abstracted by the [`GrantProducer`] trait, which is
+//! implemented for [`bbqueue::Producer`] and can be implemented for any other
+//! grant based queue.
+//!
+//! # Examples
+//! ```
+//! use bbqueue::BBBuffer;
+//! use clockwork_base32::ring::RingEncoder;
+//!
+//! static BUFFER: BBBuffer<32> = BBBuffer::new();
+//! let (producer, mut consumer) = BUFFER.try_split().unwrap();
+//!
+//! let mut encoder = RingEncoder::new(producer);
+//! assert_eq!(encoder.write(b"Hello, world!").unwrap(), 13);
+//! assert!(encoder.finish().unwrap());
+//!
+//! let grant = consumer.read().unwrap();
+//! assert_eq!(grant.buf(), b"91JPRV3F5GG7EVVJDHJ22");
+//! ```
+
+use crate::{capacity_hint_for_encode, copy_prefix, Encoder, BLOCK_BYTE_LEN, BLOCK_SYMBOL_LEN};
+
+/// The producer half of a ring buffer which hands out contiguous write grants.
+pub trait GrantProducer {
+    /// The error type of the ring buffer.
+    type Error;
+
+    /// Grants a contiguous writable region of at least one and at most `max` bytes
+    /// to `f` and commits the number of bytes `f` returns.
+    ///
+    /// Returns the number of committed bytes, or `Ok(0)` without calling `f` if the
+    /// ring buffer is full.
+    fn grant<F>(&mut self, max: usize, f: F) -> Result<usize, Self::Error>
+    where
+        F: FnOnce(&mut [u8]) -> usize;
+}
+
+impl<'a, const N: usize> GrantProducer for bbqueue::Producer<'a, N> {
+    type Error = bbqueue::Error;
+
+    fn grant<F>(&mut self, max: usize, f: F) -> Result<usize, Self::Error>
+    where
+        F: FnOnce(&mut [u8]) -> usize,
+    {
+        match self.grant_max_remaining(max) {
+            Ok(mut grant) => {
+                let len = f(grant.buf());
+                grant.commit(len);
+                Ok(len)
+            }
+            Err(bbqueue::Error::InsufficientSize) => Ok(0),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// An encoder which writes the encoded result directly into the grants of a ring buffer.
+///
+/// Whole blocks are encoded directly into the granted regions. Only a block which
+/// straddles the end of a region is staged in a small internal buffer.
+///
+/// # Examples
+/// ```
+/// use clockwork_base32::ring::{GrantProducer, RingEncoder};
+///
+/// // A producer which grants at most 4 bytes at a time.
+/// struct Chunks(Vec<u8>);
+///
+/// impl GrantProducer for Chunks {
+///     type Error = core::convert::Infallible;
+///
+///     fn grant<F>(&mut self, max: usize, f: F) -> Result<usize, Self::Error>
+///     where
+///         F: FnOnce(&mut [u8]) -> usize,
+///     {
+///         let mut region = [0; 4];
+///         let len = f(&mut region[..max.min(4)]);
+///         self.0.extend_from_slice(&region[..len]);
+///         Ok(len)
+///     }
+/// }
+///
+/// let mut encoder = RingEncoder::new(Chunks(Vec::new()));
+/// assert_eq!(encoder.write(b"Hello, world!").unwrap(), 13);
+/// assert!(encoder.finish().unwrap());
+/// assert_eq!(&encoder.into_inner().0, b"91JPRV3F5GG7EVVJDHJ22");
+/// ```
+#[derive(Debug)]
+pub struct RingEncoder<P> {
+    producer: P,
+    encoder: Encoder,
+    staged: [u8; BLOCK_SYMBOL_LEN],
+    staged_pos: usize,
+    staged_len: usize,
+}
+
+impl<P: GrantProducer> RingEncoder<P> {
+    /// Creates a new encoder which writes to `producer`.
+    pub fn new(producer: P) -> Self {
+        Self {
+            producer,
+            encoder: Encoder::new(),
+            staged: [0; BLOCK_SYMBOL_LEN],
+            staged_pos: 0,
+            staged_len: 0,
+        }
+    }
+
+    /// Encodes as much of `input` as fits in the ring buffer and returns the number
+    /// of consumed bytes.
+    ///
+    /// Fewer bytes than `input` are consumed when the ring buffer becomes full.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the ring buffer fails.
+    pub fn write(&mut self, input: &[u8]) -> Result<usize, P::Error> {
+        let mut consumed = 0;
+        loop {
+            if !self.write_staged()? || consumed == input.len() {
+                return Ok(consumed);
+            }
+            let rest = input.get(consumed..).unwrap_or_default();
+            let encoder = &mut self.encoder;
+            let staged = &mut self.staged;
+            let mut used = 0;
+            let mut staged_len = 0;
+            let max = capacity_hint_for_encode(rest.len()) + BLOCK_SYMBOL_LEN;
+            let written = self.producer.grant(max, |region| {
+                if region.len() >= BLOCK_SYMBOL_LEN {
+                    let (c, w) = encoder.update(rest, region);
+                    used = c;
+                    w
+                } else {
+                    // Stage a block which does not fit in this region.
+                    let rest = rest.get(..BLOCK_BYTE_LEN).unwrap_or(rest);
+                    let (c, w) = encoder.update(rest, staged);
+                    used = c;
+                    staged_len = w;
+                    copy_prefix(region, staged.get(..w).unwrap_or_default())
+                }
+            })?;
+            if used == 0 {
+                return Ok(consumed);
+            }
+            consumed += used;
+            if staged_len > 0 {
+                self.staged_pos = written;
+                self.staged_len = staged_len;
+            }
+        }
+    }
+
+    /// Writes the last partial block and any staged output to the ring buffer.
+    ///
+    /// Returns `false` if the ring buffer became full before all the output was
+    /// written, in which case this should be called again later.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the ring buffer fails.
+    pub fn finish(&mut self) -> Result<bool, P::Error> {
+        if !self.write_staged()? {
+            return Ok(false);
+        }
+        // The staged buffer is always long enough for the last block.
+        self.staged_len = self.encoder.finish(&mut self.staged).unwrap_or_default();
+        self.staged_pos = 0;
+        self.write_staged()
+    }
+
+    /// Returns a reference to the producer.
+    pub fn get_ref(&self) -> &P {
+        &self.producer
+    }
+
+    /// Returns the producer.
+    pub fn into_inner(self) -> P {
+        self.producer
+    }
+
+    // Writes the staged output and returns whether all of it was written.
+    fn write_staged(&mut self) -> Result<bool, P::Error> {
+        while self.staged_pos < self.staged_len {
+            let staged = self.staged.get(self.staged_pos..self.staged_len);
+            let staged = staged.unwrap_or_default();
+            let written = self
+                .producer
+                .grant(staged.len(), |region| copy_prefix(region, staged))?;
+            if written == 0 {
+                return Ok(false);
+            }
+            self.staged_pos += written;
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::encode_to_vec;
+    use std::collections::VecDeque;
+
+    // A ring buffer with a fixed capacity whose grants end at the wrap position.
+    struct Ring {
+        data: VecDeque<u8>,
+        capacity: usize,
+        write_pos: usize,
+    }
+
+    impl GrantProducer for Ring {
+        type Error = ();
+
+        fn grant<F>(&mut self, max: usize, f: F) -> Result<usize, ()>
+        where
+            F: FnOnce(&mut [u8]) -> usize,
+        {
+            let free = self.capacity - self.data.len();
+            let len = free.min(self.capacity - self.write_pos).min(max);
+            if len == 0 {
+                return Ok(0);
+            }
+            let mut region = vec![0; len];
+            let written = f(&mut region);
+            self.data.extend(&region[..written]);
+            self.write_pos = (self.write_pos + written) % self.capacity;
+            Ok(written)
+        }
+    }
+
+    #[test]
+    fn test_ring_encoder() {
+        let plain: Vec<u8> = (0..300).map(|i| (i * 7) as u8).collect();
+        let expected = encode_to_vec(&plain);
+        for capacity in [3, 8, 13, 64].iter() {
+            let ring = Ring {
+                data: VecDeque::new(),
+                capacity: *capacity,
+                write_pos: 0,
+            };
+            let mut encoder = RingEncoder::new(ring);
+            let mut output = Vec::new();
+            let mut input = &plain[..];
+            while !input.is_empty() {
+                let consumed = encoder.write(input).unwrap();
+                input = &input[consumed..];
+                let len = encoder.producer.data.len().min(3);
+                output.extend(encoder.producer.data.drain(..len));
+            }
+            while !encoder.finish().unwrap() {
+                output.extend(encoder.producer.data.drain(..));
+            }
+            output.extend(encoder.producer.data.drain(..));
+            assert_eq!(output, expected, "capacity {}", capacity);
+        }
+    }
+
+    #[test]
+    fn test_bbqueue_grant() {
+        static BUFFER: bbqueue::BBBuffer<8> = bbqueue::BBBuffer::new();
+        let (mut producer, mut consumer) = BUFFER.try_split().unwrap();
+        assert_eq!(
+            producer.grant(5, |region| copy_prefix(region, b"CSQPY")),
+            Ok(5)
+        );
+        // Only 3 bytes are left, and a full buffer does not call `f`.
+        assert_eq!(
+            producer.grant(8, |region| copy_prefix(region, b"RK1")),
+            Ok(3)
+        );
+        assert_eq!(producer.grant(8, |_| unreachable!()), Ok(0));
+        let grant = consumer.read().unwrap();
+        assert_eq!(grant.buf(), b"CSQPYRK1");
+    }
+
+    #[test]
+    fn test_bbqueue() {
+        static BUFFER: bbqueue::BBBuffer<13> = bbqueue::BBBuffer::new();
+        let (producer, mut consumer) = BUFFER.try_split().unwrap();
+        let plain: Vec<u8> = (0..100).map(|i| (i * 7) as u8).collect();
+        let mut encoder = RingEncoder::new(producer);
+        let mut output = Vec::new();
+        let mut drain = |output: &mut Vec<u8>| {
+            while let Ok(grant) = consumer.read() {
+                output.extend_from_slice(grant.buf());
+                let len = grant.buf().len();
+                grant.release(len);
+            }
+        };
+        let mut input = &plain[..];
+        while !input.is_empty() {
+            let consumed = encoder.write(input).unwrap();
+            input = &input[consumed..];
+            drain(&mut output);
+        }
+        while !encoder.finish().unwrap() {
+            drain(&mut output);
+        }
+        drain(&mut output);
+        assert_eq!(output, encode_to_vec(&plain));
+    }
+}
@@ -0,0 +1,66 @@
+//! `rusqlite` conversions storing the wrapper types in TEXT columns.
+
+use alloc::boxed::Box;
+use alloc::string::ToString;
+use core::convert::TryFrom;
+
+use ::rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+use crate::{Base32Bytes, Base32String};
+
+impl ToSql for Base32String {
+    fn to_sql(&self) -> ::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for Base32String {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Self::try_from(value.as_str()?).map_err(|err| FromSqlError::Other(Box::new(err)))
+    }
+}
+
+impl ToSql for Base32Bytes {
+    fn to_sql(&self) -> ::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for Base32Bytes {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Self::decode(value.as_str()?).map_err(|err| FromSqlError::Other(Box::new(err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::rusqlite::{params, Connection};
+
+    #[test]
+    fn test_roundtrip() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE ids (id TEXT NOT NULL, key TEXT NOT NULL)", [])
+            .unwrap();
+        let id = Base32String::encode("foo");
+        let key = Base32Bytes::from(&b"bar"[..]);
+        conn.execute("INSERT INTO ids VALUES (?1, ?2)", params![id, key])
+            .unwrap();
+
+        let text: String = conn
+            .query_row("SELECT key FROM ids", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(text, "C9GQ4");
+        let row: (Base32String, Base32Bytes) = conn
+            .query_row("SELECT id, key FROM ids", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(row, (id, key));
+
+        let err = conn.query_row("SELECT 'csqpy'", [], |row| row.get::<_, Base32String>(0));
+        assert!(err.is_err());
+        let err = conn.query_row("SELECT 1", [], |row| row.get::<_, Base32Bytes>(0));
+        assert!(err.is_err());
+    }
+}
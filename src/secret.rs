@@ -0,0 +1,97 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use ::secrecy::{ExposeSecret, SecretSlice};
+
+use crate::{ct, Error, IntoInput, Result};
+
+/// Decoded secret bytes, like an OTP seed or a signing key, which are only
+/// exposed through [`ExposeSecret::expose_secret`].
+///
+/// The bytes are held in a [`SecretSlice`], so they are wiped when dropped and
+/// are not shown by [`Debug`](fmt::Debug). Parsing decodes with the
+/// constant-time functions in [`ct`], so an invalid input gives
+/// [`Error::InvalidSymbols`] without telling which symbol is invalid.
+///
+/// This is available with the `secrecy` feature.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32::SecretBase32;
+/// use secrecy::ExposeSecret;
+///
+/// let seed: SecretBase32 = "csqpyrk1e8".parse()?;
+/// assert_eq!(seed.expose_secret(), b"foobar");
+/// assert_eq!(format!("{:?}", seed), "SecretBase32([REDACTED])");
+/// # Ok(())
+/// # }
+/// ```
+pub struct SecretBase32(SecretSlice<u8>);
+
+impl SecretBase32 {
+    /// Creates a secret holding `bytes`.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(SecretSlice::from(bytes))
+    }
+
+    /// Decodes the input to a new secret in constant time.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidSymbols`] if the input contains an invalid symbol.
+    pub fn decode<'a, I>(input: I) -> Result<Self>
+    where
+        I: IntoInput<'a>,
+    {
+        ct::decode_to_vec(input.into_input()).map(Self::new)
+    }
+}
+
+impl From<Vec<u8>> for SecretBase32 {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<Box<[u8]>> for SecretBase32 {
+    fn from(bytes: Box<[u8]>) -> Self {
+        Self(SecretSlice::from(bytes))
+    }
+}
+
+impl FromStr for SecretBase32 {
+    type Err = Error;
+
+    /// Decodes a string in constant time.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::decode(s)
+    }
+}
+
+impl ExposeSecret<[u8]> for SecretBase32 {
+    fn expose_secret(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+}
+
+impl fmt::Debug for SecretBase32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretBase32([REDACTED])")
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let secret = SecretBase32::decode(b"91JPRV3F5GG7EVVJDHJ22").unwrap();
+        assert_eq!(secret.expose_secret(), b"Hello, world!");
+        let err = "91JPRV3F5GG7EVVJDHJ2U".parse::<SecretBase32>().unwrap_err();
+        assert!(matches!(err, Error::InvalidSymbols));
+        assert_eq!(SecretBase32::from(vec![1, 2]).expose_secret(), [1, 2]);
+    }
+}
@@ -0,0 +1,593 @@
+//! Serializes byte fields as Clockwork Base32 strings with serde.
+//!
+//! Use this module with `#[serde(with = "clockwork_base32::serde")]` on a [`Vec<u8>`]
+//! field. The field is serialized as an encoded string in human readable formats
+//! like JSON or TOML, and deserializing it fails if the string contains an invalid
+//! symbol. In binary formats like bincode, postcard or CBOR, where an encoded string
+//! would be 60% larger, the field is serialized as raw bytes instead.
+//!
+//! Use the [`string`] sub-module to serialize encoded strings in all formats.
+//!
+//! # Examples
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Config {
+//!     #[serde(with = "clockwork_base32::serde")]
+//!     key: Vec<u8>,
+//! }
+//!
+//! let config = Config { key: b"foobar".to_vec() };
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"key":"CSQPYRK1E8"}"#);
+//! assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+//!
+//! assert!(serde_json::from_str::<Config>(r#"{"key":"CSQPU"}"#).is_err());
+//! ```
+//!
+//! The [`array`] and [`option`] sub-modules do the same for `[u8; N]` and
+//! `Option<Vec<u8>>` fields.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Config {
+//!     #[serde(with = "clockwork_base32::serde::array")]
+//!     id: [u8; 5],
+//!     #[serde(with = "clockwork_base32::serde::option", default)]
+//!     key: Option<Vec<u8>>,
+//! }
+//!
+//! let config = Config { id: *b"fooba", key: None };
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"id":"CSQPYRK1","key":null}"#);
+//! assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+//! assert_eq!(serde_json::from_str::<Config>(r#"{"id":"CSQPYRK1"}"#).unwrap(), config);
+//!
+//! // The decoded length must match the array length.
+//! assert!(serde_json::from_str::<Config>(r#"{"id":"CSQPYR"}"#).is_err());
+//! ```
+//!
+//! # Interoperability with `serde_bytes`
+//! The helpers serialize and deserialize any type convertible from and to
+//! [`Vec<u8>`], so they can be used on [`serde_bytes::ByteBuf`] fields too.
+//! They also accept raw byte values as written by `serde_bytes`, so stored data
+//! stays readable when a field is switched from one to the other.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use serde_bytes::ByteBuf;
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Config {
+//!     #[serde(with = "clockwork_base32::serde")]
+//!     key: ByteBuf,
+//! }
+//!
+//! let config = Config { key: ByteBuf::from(b"foobar".to_vec()) };
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"key":"CSQPYRK1E8"}"#);
+//! assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+//! ```
+//!
+//! [`serde_bytes::ByteBuf`]: https://docs.rs/serde_bytes/latest/serde_bytes/struct.ByteBuf.html
+//!
+//! # Borrowing
+//! The decoded bytes can never borrow from the input, so the fields above allocate
+//! on deserialize. To avoid that, use an [`EncodedStr`] field which borrows the
+//! validated text from the input and decodes it on demand.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use ::serde::de::{self, Deserializer, Visitor};
+use ::serde::{Serialize, Serializer};
+
+use crate::{
+    capacity_hint_for_decode, decode_symbol, encode_to_string, is_symbol_value, Error,
+    Result as DecodeResult, CLOCKWORK,
+};
+
+/// Serializes `bytes` as an encoded string, or as raw bytes if the format is not
+/// human readable.
+///
+/// # Errors
+/// Returns [`Err`] if the serializer fails.
+pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<[u8]> + ?Sized,
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        string::serialize(bytes, serializer)
+    } else {
+        serializer.serialize_bytes(bytes.as_ref())
+    }
+}
+
+/// Deserializes bytes from an encoded string or a raw byte value.
+///
+/// # Errors
+/// Returns [`Err`] if the value is neither a string nor bytes, or contains an
+/// invalid symbol.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: From<Vec<u8>>,
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        string::deserialize(deserializer)
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor).map(T::from)
+    }
+}
+
+/// Serializes byte fields as encoded strings in all formats.
+///
+/// Use this with `#[serde(with = "clockwork_base32::serde::string")]` if binary
+/// formats must hold encoded strings too, for example to keep existing data
+/// readable.
+pub mod string {
+    use super::*;
+
+    /// Serializes `bytes` as an encoded string.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the serializer fails.
+    pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<[u8]> + ?Sized,
+        S: Serializer,
+    {
+        serializer.serialize_str(&encode_to_string(bytes.as_ref()))
+    }
+
+    /// Deserializes bytes from an encoded string or a raw byte value.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the value is neither a string nor bytes, or contains an
+    /// invalid symbol.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: From<Vec<u8>>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(BytesVisitor).map(T::from)
+    }
+}
+
+struct BytesVisitor;
+
+impl Visitor<'_> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a Clockwork Base32 string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        CLOCKWORK.decode(v).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+}
+
+/// An encoded string borrowed from the deserializer input.
+///
+/// Deserializing validates the symbols without allocating, and the bytes are
+/// decoded later with [`EncodedStr::decode_to_slice`] or [`EncodedStr::decode_to_vec`].
+/// Deserializing fails if the deserializer cannot lend the string, for example
+/// when it contains JSON escape sequences.
+///
+/// # Examples
+/// ```
+/// use clockwork_base32::serde::EncodedStr;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Request<'a> {
+///     #[serde(borrow)]
+///     key: EncodedStr<'a>,
+/// }
+///
+/// let json = r#"{"key":"CSQPYRK1E8"}"#;
+/// let request: Request = serde_json::from_str(json).unwrap();
+/// assert_eq!(request.key.as_str(), "CSQPYRK1E8");
+///
+/// let mut key = [0; 6];
+/// let len = request.key.decode_to_slice(&mut key).unwrap();
+/// assert_eq!(&key[..len], b"foobar");
+///
+/// assert!(serde_json::from_str::<Request>(r#"{"key":"CSQPU"}"#).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EncodedStr<'a>(&'a str);
+
+impl<'a> EncodedStr<'a> {
+    /// Creates a new encoded string after validating its symbols.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if `s` contains an invalid symbol.
+    pub fn new(s: &'a str) -> DecodeResult<Self> {
+        match s.bytes().position(|b| !is_symbol_value(decode_symbol(b))) {
+            Some(position) => {
+                let byte = s.as_bytes().get(position).copied().unwrap_or_default();
+                Err(Error::InvalidSymbol { byte, position })
+            }
+            None => Ok(Self(s)),
+        }
+    }
+
+    /// Returns the encoded string.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// Returns the length of the decoded bytes.
+    pub fn decoded_len(&self) -> usize {
+        capacity_hint_for_decode(self.0.len())
+    }
+
+    /// Decodes the string to `dest` and returns the decoded length.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if `dest` is shorter than [`EncodedStr::decoded_len`].
+    pub fn decode_to_slice(&self, dest: &mut [u8]) -> DecodeResult<usize> {
+        CLOCKWORK.decode_to_slice(self.0, dest)
+    }
+
+    /// Decodes the string to a new [`Vec<u8>`].
+    pub fn decode_to_vec(&self) -> Vec<u8> {
+        // The symbols are validated on creation, so this never fails.
+        CLOCKWORK.decode(self.0).unwrap_or_default()
+    }
+}
+
+impl Serialize for EncodedStr<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de: 'a, 'a> de::Deserialize<'de> for EncodedStr<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(EncodedStrVisitor)
+    }
+}
+
+struct EncodedStrVisitor;
+
+impl<'de> Visitor<'de> for EncodedStrVisitor {
+    type Value = EncodedStr<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a borrowed Clockwork Base32 string")
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        EncodedStr::new(v).map_err(E::custom)
+    }
+}
+
+/// Serializes `[u8; N]` fields as encoded strings, or as raw bytes in binary formats.
+///
+/// Deserializing fails unless the decoded length is exactly `N`.
+pub mod array {
+    use core::convert::TryFrom;
+
+    use super::*;
+
+    /// Serializes `bytes` as an encoded string, or as raw bytes if the format is
+    /// not human readable.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the serializer fails.
+    pub fn serialize<S, const N: usize>(bytes: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        super::serialize(bytes, serializer)
+    }
+
+    /// Deserializes a byte array from an encoded string or a raw byte value.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the value is neither a string nor bytes, contains an invalid
+    /// symbol or its decoded length is not `N`.
+    pub fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(ArrayVisitor)
+        } else {
+            deserializer.deserialize_bytes(ArrayVisitor)
+        }
+    }
+
+    struct ArrayVisitor<const N: usize>;
+
+    impl<const N: usize> Visitor<'_> for ArrayVisitor<N> {
+        type Value = [u8; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a Clockwork Base32 string of {} bytes", N)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            let len = capacity_hint_for_decode(v.len());
+            if len != N {
+                return Err(E::invalid_length(len, &self));
+            }
+            let mut bytes = [0; N];
+            CLOCKWORK
+                .decode_to_slice(v, &mut bytes)
+                .map_err(E::custom)?;
+            Ok(bytes)
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            <[u8; N]>::try_from(v).map_err(|_| E::invalid_length(v.len(), &self))
+        }
+    }
+}
+
+/// Serializes `Option<Vec<u8>>` fields as encoded strings or nulls, or as raw bytes
+/// or nulls in binary formats.
+///
+/// Add `#[serde(default)]` too if the field may be missing.
+pub mod option {
+    use super::*;
+
+    /// Serializes `bytes` like [`serialize`](super::serialize), or `None` as a null.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the serializer fails.
+    pub fn serialize<T, S>(bytes: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<[u8]>,
+        S: Serializer,
+    {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&Encoded(bytes.as_ref())),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes optional bytes from an encoded string, a raw byte value or a null.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the value is neither a string, bytes nor a null, or
+    /// contains an invalid symbol.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: From<Vec<u8>>,
+        D: Deserializer<'de>,
+    {
+        let bytes = deserializer.deserialize_option(OptionVisitor)?;
+        Ok(bytes.map(T::from))
+    }
+
+    struct Encoded<'a>(&'a [u8]);
+
+    impl Serialize for Encoded<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::serialize(self.0, serializer)
+        }
+    }
+
+    struct OptionVisitor;
+
+    impl<'de> Visitor<'de> for OptionVisitor {
+        type Value = Option<Vec<u8>>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a Clockwork Base32 string or null")
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            super::deserialize(deserializer).map(Some)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::EncodedStr;
+    use crate::Error;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Record {
+        #[serde(with = "crate::serde")]
+        data: Vec<u8>,
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for (plain, encoded) in [
+            ("", ""),
+            ("f", "CR"),
+            ("Hello, world!", "91JPRV3F5GG7EVVJDHJ22"),
+        ] {
+            let record = Record {
+                data: plain.as_bytes().to_vec(),
+            };
+            let json = serde_json::to_string(&record).unwrap();
+            assert_eq!(json, format!(r#"{{"data":"{encoded}"}}"#));
+            assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Fields {
+        #[serde(with = "crate::serde::array")]
+        id: [u8; 3],
+        #[serde(with = "crate::serde::option", default)]
+        key: Option<Vec<u8>>,
+    }
+
+    #[test]
+    fn test_array_and_option() {
+        let fields = Fields {
+            id: *b"foo",
+            key: Some(b"f".to_vec()),
+        };
+        let json = serde_json::to_string(&fields).unwrap();
+        assert_eq!(json, r#"{"id":"CSQPY","key":"CR"}"#);
+        assert_eq!(serde_json::from_str::<Fields>(&json).unwrap(), fields);
+
+        let fields = Fields {
+            id: *b"foo",
+            key: None,
+        };
+        let json = serde_json::to_string(&fields).unwrap();
+        assert_eq!(json, r#"{"id":"CSQPY","key":null}"#);
+        assert_eq!(serde_json::from_str::<Fields>(&json).unwrap(), fields);
+        assert_eq!(
+            serde_json::from_str::<Fields>(r#"{"id":"CSQPY"}"#).unwrap(),
+            fields
+        );
+
+        for id in ["CSQP", "CSQPYRK", "CSQPYRK1"] {
+            let json = format!(r#"{{"id":"{}"}}"#, id);
+            let err = serde_json::from_str::<Fields>(&json).unwrap_err();
+            assert!(err.to_string().contains("3 bytes"), "{}", err);
+        }
+        assert!(serde_json::from_str::<Fields>(r#"{"id":"CSQPU"}"#).is_err());
+        assert!(serde_json::from_str::<Fields>(r#"{"id":"CSQPY","key":"CU"}"#).is_err());
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Borrowed<'a> {
+        #[serde(borrow)]
+        data: EncodedStr<'a>,
+    }
+
+    #[test]
+    fn test_encoded_str() {
+        let json = r#"{"data":"91JPRV3F5GG7EVVJDHJ22"}"#;
+        let borrowed: Borrowed = serde_json::from_str(json).unwrap();
+        assert_eq!(borrowed.data.as_str(), "91JPRV3F5GG7EVVJDHJ22");
+        assert_eq!(borrowed.data.decoded_len(), 13);
+        assert_eq!(borrowed.data.decode_to_vec(), b"Hello, world!");
+        let mut buf = [0; 12];
+        assert!(matches!(
+            borrowed.data.decode_to_slice(&mut buf),
+            Err(Error::BufferTooSmall)
+        ));
+        assert_eq!(serde_json::to_string(&borrowed).unwrap(), json);
+
+        assert!(serde_json::from_str::<Borrowed>(r#"{"data":"CSQPU"}"#).is_err());
+        // An escaped string cannot be borrowed.
+        assert!(serde_json::from_str::<Borrowed>(r#"{"data":"CS\u0051P"}"#).is_err());
+        assert!(matches!(
+            EncodedStr::new("CSQPu"),
+            Err(Error::InvalidSymbol { byte: b'u', .. })
+        ));
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct ByteBufRecord {
+        #[serde(with = "crate::serde")]
+        data: serde_bytes::ByteBuf,
+        #[serde(with = "crate::serde::option")]
+        key: Option<serde_bytes::ByteBuf>,
+    }
+
+    #[test]
+    fn test_serde_bytes() {
+        let record = ByteBufRecord {
+            data: serde_bytes::ByteBuf::from(b"foo".to_vec()),
+            key: Some(serde_bytes::ByteBuf::from(b"f".to_vec())),
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(json, r#"{"data":"CSQPY","key":"CR"}"#);
+        assert_eq!(
+            serde_json::from_str::<ByteBufRecord>(&json).unwrap(),
+            record
+        );
+
+        // Raw byte values as written by serde_bytes are accepted.
+        let deserializer = serde::de::value::BytesDeserializer::<serde_json::Error>::new(b"foo");
+        assert_eq!(
+            crate::serde::deserialize::<Vec<u8>, _>(deserializer).unwrap(),
+            b"foo"
+        );
+        let deserializer = serde::de::value::BytesDeserializer::<serde_json::Error>::new(b"foo");
+        assert_eq!(
+            crate::serde::array::deserialize::<_, 3>(deserializer).unwrap(),
+            *b"foo"
+        );
+        let deserializer = serde::de::value::BytesDeserializer::<serde_json::Error>::new(b"foo");
+        assert!(crate::serde::array::deserialize::<_, 4>(deserializer).is_err());
+    }
+
+    #[test]
+    fn test_binary_format() {
+        let record = Record {
+            data: b"Hello, world!".to_vec(),
+        };
+        let bytes = postcard::to_allocvec(&record).unwrap();
+        assert_eq!(bytes.len(), 1 + 13);
+        assert_eq!(postcard::from_bytes::<Record>(&bytes).unwrap(), record);
+
+        let fields = Fields {
+            id: *b"foo",
+            key: Some(b"f".to_vec()),
+        };
+        let bytes = postcard::to_allocvec(&fields).unwrap();
+        assert_eq!(bytes, [3, b'f', b'o', b'o', 1, 1, b'f']);
+        assert_eq!(postcard::from_bytes::<Fields>(&bytes).unwrap(), fields);
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Strings {
+            #[serde(with = "crate::serde::string")]
+            data: Vec<u8>,
+        }
+        let strings = Strings {
+            data: b"foo".to_vec(),
+        };
+        let bytes = postcard::to_allocvec(&strings).unwrap();
+        assert_eq!(bytes, b"\x05CSQPY");
+        assert_eq!(postcard::from_bytes::<Strings>(&bytes).unwrap(), strings);
+        let json = serde_json::to_string(&strings).unwrap();
+        assert_eq!(json, r#"{"data":"CSQPY"}"#);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let err = serde_json::from_str::<Record>(r#"{"data":"CSQPU"}"#).unwrap_err();
+        assert!(
+            err.to_string().starts_with("invalid symbol value U"),
+            "{}",
+            err
+        );
+
+        let err = serde_json::from_str::<Record>(r#"{"data":[1,2]}"#).unwrap_err();
+        assert!(
+            err.to_string().contains("a Clockwork Base32 string"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Block kernels implemented with the portable SIMD API (`core::simd`).
+//!
+//! These replace the scalar block kernels when the `portable-simd` feature is
+//! enabled. The feature requires a nightly compiler.
+
+use core::simd::prelude::*;
+
+use super::{
+    decode_symbol, is_symbol_value, Error, Result, BLOCK_BYTE_LEN, BLOCK_SYMBOL_LEN,
+    DECODE_SYMBOLS, ENCODE_SYMBOLS, XX,
+};
+
+// The bit offset of each symbol in the 40 bits of a block, from MSB to LSB.
+const SYMBOL_SHIFTS: u64x8 = u64x8::from_array([35, 30, 25, 20, 15, 10, 5, 0]);
+
+pub(crate) fn decode_block(block: &[u8]) -> Result<[u8; BLOCK_BYTE_LEN]> {
+    let symbols = u8x8::load_or_default(block);
+    let values = u8x8::gather_or(&DECODE_SYMBOLS, symbols.cast(), u8x8::splat(XX));
+    if values.simd_ge(u8x8::splat(32)).any() {
+        // Report the first invalid symbol like the scalar kernel does.
+        let position = block
+            .iter()
+            .position(|&b| !is_symbol_value(decode_symbol(b)))
+            .unwrap_or_default();
+        let byte = block.get(position).copied().unwrap_or_default();
+        return Err(Error::InvalidSymbol { byte, position });
+    }
+    let bits = (values.cast::<u64>() << SYMBOL_SHIFTS).reduce_or();
+    let mut output = [0; BLOCK_BYTE_LEN];
+    output.copy_from_slice(&bits.to_be_bytes()[8 - BLOCK_BYTE_LEN..]);
+    Ok(output)
+}
+
+pub(crate) fn encode_block(block: &[u8]) -> [u8; BLOCK_SYMBOL_LEN] {
+    let bits = block.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+    let indexes = (u64x8::splat(bits) >> SYMBOL_SHIFTS) & u64x8::splat(0x1f);
+    u8x8::gather_or_default(&ENCODE_SYMBOLS, indexes.cast()).to_array()
+}
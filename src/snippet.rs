@@ -0,0 +1,164 @@
+use core::fmt::{self, Write};
+
+// The maximum number of bytes shown on each side of the offending byte.
+const CONTEXT_LEN: usize = 32;
+
+const RED: &str = "\x1b[1;31m";
+const RESET: &str = "\x1b[0m";
+
+/// An adapter displaying the line of the input around a problem, with a caret
+/// below the offending byte, returned by [`ParseError::snippet`].
+///
+/// It is written as two lines indented by four spaces, without a trailing
+/// newline. At most 32 bytes are shown on each side of the offending byte, and
+/// `...` marks the cut parts of the line. Bytes which are not printable ASCII
+/// are shown as `.`, so the caret stays below the offending byte.
+///
+/// [`ParseError::snippet`]: crate::ParseError::snippet
+///
+/// # Examples
+/// ```
+/// use clockwork_base32::Snippet;
+///
+/// let input = b"CSQPYRK1\n5GG7-EVVJ\n";
+/// let snippet = Snippet::new(input, 13);
+/// assert_eq!(snippet.to_string(), "    5GG7-EVVJ\n        ^");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Snippet<'a> {
+    input: &'a [u8],
+    position: usize,
+    continued: bool,
+    color: bool,
+}
+
+impl<'a> Snippet<'a> {
+    /// Creates a snippet of the line around the byte at `position` in `input`.
+    ///
+    /// A `position` at the end of `input`, like that of an invalid length,
+    /// puts the caret after the last byte.
+    pub const fn new(input: &'a [u8], position: usize) -> Self {
+        Self {
+            input,
+            position,
+            continued: false,
+            color: false,
+        }
+    }
+
+    /// Sets whether the first line of `input` continues a line before it, for
+    /// input read in chunks, so the snippet marks that line as cut.
+    pub const fn continued(mut self, continued: bool) -> Self {
+        self.continued = continued;
+        self
+    }
+
+    /// Sets whether the offending byte and the caret are highlighted in red
+    /// with ANSI escape codes, for terminals.
+    pub const fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl fmt::Display for Snippet<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let position = self.position.min(self.input.len());
+        let (head, tail) = self.input.split_at(position);
+        let line_start = head.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+        let line_end = tail
+            .iter()
+            .position(|b| *b == b'\n' || *b == b'\r')
+            .map_or(self.input.len(), |i| position + i);
+        let start = line_start.max(position.saturating_sub(CONTEXT_LEN));
+        let end = line_end.min(position.saturating_add(1 + CONTEXT_LEN));
+        let cut_before = start > line_start || (self.continued && line_start == 0);
+        let ellipsis = if cut_before { "..." } else { "" };
+        let (red, reset) = if self.color { (RED, RESET) } else { ("", "") };
+
+        let before = self.input.get(start..position).unwrap_or_default();
+        let offending = self.input.get(position).filter(|_| position < end);
+        let after = self.input.get(position + 1..end).unwrap_or_default();
+        f.write_str("    ")?;
+        f.write_str(ellipsis)?;
+        write_printable(f, before)?;
+        f.write_str(red)?;
+        write_printable(f, offending.map_or(&b" "[..], core::slice::from_ref))?;
+        f.write_str(reset)?;
+        write_printable(f, after)?;
+        if end < line_end {
+            f.write_str("...")?;
+        }
+        write!(
+            f,
+            "\n    {:width$}{}^{}",
+            "",
+            red,
+            reset,
+            width = ellipsis.len() + before.len()
+        )
+    }
+}
+
+// write_printable writes `bytes` with the bytes which are not printable ASCII
+// replaced by `.`, so every byte takes one column.
+fn write_printable(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    for &b in bytes {
+        let c = if b.is_ascii_graphic() || b == b' ' {
+            b as char
+        } else {
+            '.'
+        };
+        f.write_char(c)?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet() {
+        let input = b"CSQPYRK1\n5GG7-E\tVJ\r\n";
+        assert_eq!(
+            Snippet::new(input, 13).to_string(),
+            "    5GG7-E.VJ\n        ^"
+        );
+        assert_eq!(
+            Snippet::new(input, 13).color(true).to_string(),
+            "    5GG7\x1b[1;31m-\x1b[0mE.VJ\n        \x1b[1;31m^\x1b[0m"
+        );
+        assert_eq!(Snippet::new(b"CSQ", 3).to_string(), "    CSQ \n       ^");
+
+        let mut input = vec![b'0'; 100];
+        input[50] = b'u';
+        let rendered = Snippet::new(&input, 50).to_string();
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("    ...{}u{}...", "0".repeat(32), "0".repeat(32))
+        );
+        assert_eq!(lines[1].find('^'), Some(4 + 3 + 32));
+
+        // The line started before the input.
+        assert_eq!(
+            Snippet::new(b"PU", 1).continued(true).to_string(),
+            "    ...PU\n        ^"
+        );
+        assert_eq!(
+            Snippet::new(b"Y\nPU", 3).continued(true).to_string(),
+            "    PU\n     ^"
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = "CSQPYRK1 CSQPU";
+        let err = crate::CLOCKWORK.validate(input).unwrap_err();
+        assert_eq!(
+            err.snippet(input.as_bytes()).to_string(),
+            "    CSQPYRK1 CSQPU\n            ^"
+        );
+    }
+}
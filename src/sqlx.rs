@@ -0,0 +1,86 @@
+//! `sqlx` conversions storing the wrapper types in TEXT columns.
+
+use alloc::string::{String, ToString};
+use core::convert::TryFrom;
+
+use ::sqlx::database::Database;
+use ::sqlx::decode::Decode;
+use ::sqlx::encode::{Encode, IsNull};
+use ::sqlx::error::BoxDynError;
+use ::sqlx::types::Type;
+
+use crate::{Base32Bytes, Base32String};
+
+impl<DB: Database> Type<DB> for Base32String
+where
+    str: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <str as Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <str as Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, DB: Database> Encode<'q, DB> for Base32String
+where
+    String: Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        self.as_str().to_string().encode(buf)
+    }
+
+    fn encode(self, buf: &mut <DB as Database>::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+        self.into_string().encode(buf)
+    }
+}
+
+impl<'r, DB: Database> Decode<'r, DB> for Base32String
+where
+    &'r str: Decode<'r, DB>,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        let s = <&str as Decode<DB>>::decode(value)?;
+        Ok(Self::try_from(s)?)
+    }
+}
+
+impl<DB: Database> Type<DB> for Base32Bytes
+where
+    str: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <str as Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <str as Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, DB: Database> Encode<'q, DB> for Base32Bytes
+where
+    String: Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        self.to_string().encode(buf)
+    }
+}
+
+impl<'r, DB: Database> Decode<'r, DB> for Base32Bytes
+where
+    &'r str: Decode<'r, DB>,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        let s = <&str as Decode<DB>>::decode(value)?;
+        Ok(Self::decode(s)?)
+    }
+}
@@ -0,0 +1,122 @@
+use core::fmt;
+use core::ops::Deref;
+
+use crate::{IntoInput, Result, CLOCKWORK};
+
+/// An encoder which keeps the encoded result in an internal array of `N` bytes.
+///
+/// It neither allocates nor panics, so it can be used to format small payloads
+/// such as IDs in interrupt handlers and other allocation-free contexts. With
+/// the `defmt` feature enabled, the result can be logged with `defmt` too.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32::StackEncoder;
+/// let mut encoder = StackEncoder::<32>::new();
+/// assert_eq!(encoder.encode(b"Hello, world!")?, "91JPRV3F5GG7EVVJDHJ22");
+/// assert_eq!(encoder.encode(b"foobar")?, "CSQPYRK1E8");
+/// assert_eq!(format!("id={}", encoder), "id=CSQPYRK1E8");
+/// assert!(encoder.encode(&[0; 32]).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct StackEncoder<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> StackEncoder<N> {
+    /// Creates a new encoder holding an empty result.
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Encodes bytes, replacing the previous result, and returns the encoded result.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the encoded result is longer than `N` bytes. The previous
+    /// result is cleared in that case.
+    pub fn encode<'a, I>(&mut self, input: I) -> Result<&str>
+    where
+        I: IntoInput<'a>,
+    {
+        self.len = 0;
+        self.len = CLOCKWORK.encode_to_slice(input, &mut self.buf)?;
+        Ok(self.as_str())
+    }
+
+    /// Returns the encoded result.
+    pub fn as_str(&self) -> &str {
+        // The result consists of ASCII symbols only, so this never fails.
+        core::str::from_utf8(self.as_bytes()).unwrap_or_default()
+    }
+
+    /// Returns the encoded result as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.buf.get(..self.len).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> Default for StackEncoder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for StackEncoder<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for StackEncoder<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Display for StackEncoder<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for StackEncoder<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StackEncoder").field(&self.as_str()).finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for StackEncoder<N> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=str}", self.as_str());
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn test_stack_encoder() {
+        let mut encoder = StackEncoder::<8>::default();
+        assert_eq!(encoder.as_str(), "");
+        assert_eq!(encoder.encode(b"fooba").unwrap(), "CSQPYRK1");
+        assert_eq!(&*encoder, "CSQPYRK1");
+        assert_eq!(format!("{:?}", encoder), "StackEncoder(\"CSQPYRK1\")");
+        assert!(matches!(
+            encoder.encode(b"foobar"),
+            Err(Error::BufferTooSmall)
+        ));
+        assert!(encoder.is_empty());
+    }
+}
@@ -0,0 +1,451 @@
+use crate::{
+    capacity_hint_for_encode, copy_prefix, decode_block, encode_block, Error, Result,
+    BLOCK_BYTE_LEN, BLOCK_SYMBOL_LEN, BYTE_BIT_LEN, DECODED_BIT_LEN,
+};
+
+// The maximum number of bytes `Transcode::finish` writes.
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+pub(crate) const MAX_FINISH_LEN: usize = BLOCK_SYMBOL_LEN;
+
+pub(crate) mod sealed {
+    pub trait Sealed {}
+}
+
+/// A resumable state machine which transcodes input given in chunks.
+///
+/// This trait is sealed and implemented for [`Encoder`] and [`Decoder`], so the
+/// streaming adapters can be written once for both directions.
+pub trait Transcode: sealed::Sealed {
+    /// Transcodes `input` into `output` and returns the number of consumed input bytes
+    /// and the number of written output bytes.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input is invalid.
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<(usize, usize)>;
+
+    /// Writes the result for the remaining buffered input to `output` and returns
+    /// the number of written bytes.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if `output` is shorter than needed.
+    fn finish(&mut self, output: &mut [u8]) -> Result<usize>;
+}
+
+/// A resumable encoder.
+///
+/// Input can be given in chunks of any size. Bytes which do not form a whole block
+/// of 5 bytes are kept in the encoder until the next call of [`Encoder::update`]
+/// or [`Encoder::finish`].
+///
+/// # Examples
+/// ```
+/// use clockwork_base32::Encoder;
+/// let mut encoder = Encoder::new();
+/// let mut output = [0; 32];
+/// let mut len = 0;
+/// for chunk in [&b"Hello"[..], b", wor", b"ld!"].iter() {
+///     let (consumed, written) = encoder.update(chunk, &mut output[len..]);
+///     assert_eq!(consumed, chunk.len());
+///     len += written;
+/// }
+/// len += encoder.finish(&mut output[len..]).unwrap();
+/// assert_eq!(&output[..len], b"91JPRV3F5GG7EVVJDHJ22");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Encoder {
+    pending: [u8; BLOCK_BYTE_LEN],
+    pending_len: usize,
+}
+
+impl Encoder {
+    /// Creates a new encoder.
+    pub const fn new() -> Self {
+        Self {
+            pending: [0; BLOCK_BYTE_LEN],
+            pending_len: 0,
+        }
+    }
+
+    /// Encodes `input` into `output` and returns the number of consumed input bytes
+    /// and the number of written output bytes.
+    ///
+    /// Output is written in blocks of 8 symbols, so fewer bytes than `input`
+    /// are consumed when `output` is full.
+    pub fn update(&mut self, input: &[u8], output: &mut [u8]) -> (usize, usize) {
+        let encode = |block: &[u8]| Ok(encode_block(block));
+        update_blocks(
+            &mut self.pending,
+            &mut self.pending_len,
+            input,
+            output,
+            encode,
+        )
+        .unwrap_or_default()
+    }
+
+    /// Encodes the remaining buffered bytes into `output` and returns the number of
+    /// written bytes, which is at most 8.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if `output` is shorter than needed.
+    pub fn finish(&mut self, output: &mut [u8]) -> Result<usize> {
+        let len = capacity_hint_for_encode(self.pending_len);
+        let encode = |block: &[u8]| Ok(encode_block(block));
+        finish_block(
+            &mut self.pending,
+            &mut self.pending_len,
+            0,
+            output,
+            len,
+            encode,
+        )
+    }
+}
+
+impl sealed::Sealed for Encoder {}
+impl Transcode for Encoder {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<(usize, usize)> {
+        Ok(Encoder::update(self, input, output))
+    }
+
+    fn finish(&mut self, output: &mut [u8]) -> Result<usize> {
+        Encoder::finish(self, output)
+    }
+}
+
+/// A resumable decoder.
+///
+/// Input can be given in chunks of any size. Symbols which do not form a whole block
+/// of 8 symbols are kept in the decoder until the next call of [`Decoder::update`]
+/// or [`Decoder::finish`].
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32::Decoder;
+/// let mut decoder = Decoder::new();
+/// let mut output = [0; 16];
+/// let mut len = 0;
+/// for chunk in [&b"91JPRV3"[..], b"F5GG7EVVJ", b"DHJ22"].iter() {
+///     let (consumed, written) = decoder.update(chunk, &mut output[len..])?;
+///     assert_eq!(consumed, chunk.len());
+///     len += written;
+/// }
+/// len += decoder.finish(&mut output[len..])?;
+/// assert_eq!(&output[..len], b"Hello, world!");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Decoder {
+    pending: [u8; BLOCK_SYMBOL_LEN],
+    pending_len: usize,
+    // The number of symbols in the decoded blocks, for the positions of errors.
+    position: usize,
+}
+
+impl Decoder {
+    /// Creates a new decoder.
+    pub const fn new() -> Self {
+        Self {
+            pending: [0; BLOCK_SYMBOL_LEN],
+            pending_len: 0,
+            position: 0,
+        }
+    }
+
+    /// Decodes `input` into `output` and returns the number of consumed input bytes
+    /// and the number of written output bytes.
+    ///
+    /// Output is written in blocks of 5 bytes, so fewer bytes than `input`
+    /// are consumed when `output` is full.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input contains a invalid byte. The position of the
+    /// error counts all the symbols given to this decoder.
+    pub fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<(usize, usize)> {
+        let position = &mut self.position;
+        update_blocks(
+            &mut self.pending,
+            &mut self.pending_len,
+            input,
+            output,
+            |block| {
+                let decoded = decode_block(block).map_err(|err| err.offset_by(*position))?;
+                *position += BLOCK_SYMBOL_LEN;
+                Ok(decoded)
+            },
+        )
+    }
+
+    /// Decodes the remaining buffered symbols into `output` and returns the number of
+    /// written bytes, which is at most 4.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the buffered symbols contain a invalid byte or `output`
+    /// is shorter than needed.
+    pub fn finish(&mut self, output: &mut [u8]) -> Result<usize> {
+        let len = self.pending_len * DECODED_BIT_LEN / BYTE_BIT_LEN;
+        let position = self.position;
+        finish_block(
+            &mut self.pending,
+            &mut self.pending_len,
+            b'0',
+            output,
+            len,
+            |block| decode_block(block).map_err(|err| err.offset_by(position)),
+        )
+    }
+}
+
+impl sealed::Sealed for Decoder {}
+impl Transcode for Decoder {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<(usize, usize)> {
+        Decoder::update(self, input, output)
+    }
+
+    fn finish(&mut self, output: &mut [u8]) -> Result<usize> {
+        Decoder::finish(self, output)
+    }
+}
+
+// ReadBuffer keeps the input read from an inner reader and the transcoded output
+// not yet returned to the caller, so the reader adapters only need to move bytes.
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+#[derive(Debug)]
+pub(crate) struct ReadBuffer<const N: usize> {
+    input: [u8; N],
+    input_pos: usize,
+    input_len: usize,
+    output: [u8; N],
+    output_pos: usize,
+    output_len: usize,
+    finished: bool,
+}
+
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+impl<const N: usize> ReadBuffer<N> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            input: [0; N],
+            input_pos: 0,
+            input_len: 0,
+            output: [0; N],
+            output_pos: 0,
+            output_len: 0,
+            finished: false,
+        }
+    }
+
+    // Copies pending output to `buf` and returns its length, or returns `None`
+    // if more output needs to be produced. Returns `Some(0)` at the end.
+    pub(crate) fn take_output(&mut self, buf: &mut [u8]) -> Option<usize> {
+        if self.output_pos < self.output_len {
+            let output = self.output.get(self.output_pos..self.output_len);
+            let len = copy_prefix(buf, output.unwrap_or_default());
+            self.output_pos += len;
+            return Some(len);
+        }
+        if self.finished || buf.is_empty() {
+            return Some(0);
+        }
+        None
+    }
+
+    // Returns the buffer to read input into if all the previous input was consumed.
+    pub(crate) fn input_to_fill(&mut self) -> Option<&mut [u8]> {
+        if self.input_pos == self.input_len {
+            Some(&mut self.input)
+        } else {
+            None
+        }
+    }
+
+    // Transcodes the input after `len` bytes were read into the buffer returned by
+    // `input_to_fill`, or after the remaining input if `len` is `None`.
+    // Reading zero bytes means the end of the input.
+    pub(crate) fn transcode<T: Transcode>(
+        &mut self,
+        transcoder: &mut T,
+        len: Option<usize>,
+    ) -> Result<()> {
+        self.output_pos = 0;
+        match len {
+            Some(0) => {
+                self.output_len = transcoder.finish(&mut self.output)?;
+                self.finished = true;
+            }
+            _ => {
+                if let Some(len) = len {
+                    self.input_pos = 0;
+                    self.input_len = len;
+                }
+                let input = self.input.get(self.input_pos..self.input_len);
+                let input = input.unwrap_or_default();
+                let (consumed, written) = transcoder.update(input, &mut self.output)?;
+                self.input_pos += consumed;
+                self.output_len = written;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn update_blocks<const IN: usize, const OUT: usize, F>(
+    pending: &mut [u8; IN],
+    pending_len: &mut usize,
+    input: &[u8],
+    output: &mut [u8],
+    mut transcode_block: F,
+) -> Result<(usize, usize)>
+where
+    F: FnMut(&[u8]) -> Result<[u8; OUT]>,
+{
+    let mut consumed = 0;
+    let mut written = 0;
+
+    // Complete the pending block first.
+    if *pending_len > 0 {
+        let free = pending.get_mut(*pending_len..).unwrap_or_default();
+        consumed = copy_prefix(free, input);
+        *pending_len += consumed;
+        if *pending_len < IN || output.len() < OUT {
+            return Ok((consumed, 0));
+        }
+        written = copy_prefix(output, &transcode_block(pending)?);
+        *pending_len = 0;
+    }
+
+    let in_blocks = input.get(consumed..).unwrap_or_default().chunks_exact(IN);
+    let out_blocks = output
+        .get_mut(written..)
+        .unwrap_or_default()
+        .chunks_exact_mut(OUT);
+    for (in_block, out_block) in in_blocks.zip(out_blocks) {
+        out_block.copy_from_slice(&transcode_block(in_block)?);
+        consumed += IN;
+        written += OUT;
+    }
+
+    // Keep the trailing partial block for the next call.
+    let rest = input.get(consumed..).unwrap_or_default();
+    if rest.len() < IN {
+        *pending_len = copy_prefix(pending, rest);
+        consumed = input.len();
+    }
+    Ok((consumed, written))
+}
+
+fn finish_block<const IN: usize, const OUT: usize, F>(
+    pending: &mut [u8; IN],
+    pending_len: &mut usize,
+    padding: u8,
+    output: &mut [u8],
+    len: usize,
+    transcode_block: F,
+) -> Result<usize>
+where
+    F: FnOnce(&[u8]) -> Result<[u8; OUT]>,
+{
+    if *pending_len == 0 {
+        return Ok(0);
+    }
+    let output = output.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+    pending
+        .get_mut(*pending_len..)
+        .unwrap_or_default()
+        .fill(padding);
+    copy_prefix(output, &transcode_block(pending)?);
+    *pending_len = 0;
+    Ok(len)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{decode_to_vec, encode_to_vec};
+
+    #[test]
+    fn test_chunked() {
+        let plain: Vec<u8> = (0..=255).collect();
+        let encoded = encode_to_vec(&plain);
+        for chunk_len in 1..20 {
+            for output_len in [8, 9, 13, 64].iter() {
+                let mut encoder = Encoder::new();
+                let mut result = Vec::new();
+                let mut output = vec![0; *output_len];
+                for chunk in plain.chunks(chunk_len) {
+                    let mut chunk = chunk;
+                    while !chunk.is_empty() {
+                        let (consumed, written) = encoder.update(chunk, &mut output);
+                        result.extend_from_slice(&output[..written]);
+                        chunk = &chunk[consumed..];
+                    }
+                }
+                let written = encoder.finish(&mut output).unwrap();
+                result.extend_from_slice(&output[..written]);
+                assert_eq!(result, encoded);
+
+                let mut decoder = Decoder::new();
+                let mut result = Vec::new();
+                for chunk in encoded.chunks(chunk_len) {
+                    let mut chunk = chunk;
+                    while !chunk.is_empty() {
+                        let (consumed, written) = decoder.update(chunk, &mut output).unwrap();
+                        result.extend_from_slice(&output[..written]);
+                        chunk = &chunk[consumed..];
+                    }
+                }
+                let written = decoder.finish(&mut output).unwrap();
+                result.extend_from_slice(&output[..written]);
+                assert_eq!(result, plain);
+            }
+        }
+        assert_eq!(decode_to_vec(&encoded).ok().unwrap(), plain);
+    }
+
+    #[test]
+    fn test_finish_errors() {
+        let mut encoder = Encoder::new();
+        assert_eq!(encoder.update(b"f", &mut []), (1, 0));
+        assert!(matches!(
+            encoder.finish(&mut [0]),
+            Err(Error::BufferTooSmall)
+        ));
+
+        let mut decoder = Decoder::new();
+        assert_eq!(decoder.update(b"CU", &mut []).unwrap(), (2, 0));
+        assert!(matches!(
+            decoder.finish(&mut [0; 4]),
+            Err(Error::InvalidSymbol { byte: b'U', .. })
+        ));
+    }
+
+    #[test]
+    fn test_error_position() {
+        let mut input = encode_to_vec(&[0; 40]);
+        input.extend_from_slice(b"CSQPU");
+        for chunk_len in [1, 3, 8, 100] {
+            for output_len in [5, 9, 64] {
+                let mut decoder = Decoder::new();
+                let mut output = vec![0; output_len];
+                let mut result = Ok(());
+                for mut chunk in input.chunks(chunk_len) {
+                    while result.is_ok() && !chunk.is_empty() {
+                        result = decoder.update(chunk, &mut output).map(|(consumed, _)| {
+                            chunk = &chunk[consumed..];
+                        });
+                    }
+                }
+                let err = result.and_then(|()| decoder.finish(&mut output).map(drop));
+                assert_eq!(
+                    err.unwrap_err(),
+                    Error::InvalidSymbol {
+                        byte: b'U',
+                        position: 68
+                    }
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+
+use crate::{
+    capacity_hint_for_decode, check_canonical, Error, IntoInput, ParseError, Result, CLOCKWORK,
+};
+
+/// An owned string which is always the canonical encoding of some bytes.
+///
+/// It is created by encoding bytes or by validating a string. A valid string
+/// gives back the same string when its decoded bytes are encoded again, so it
+/// contains no lowercase or aliased symbols.
+///
+/// APIs can take this type for encoded text which is already validated, instead
+/// of validating the text at every use site.
+///
+/// For IDs in URLs, [`UrlSafe`](crate::UrlSafe) wraps any string type and
+/// stays a string in binary formats too.
+///
+/// With the `serde` feature enabled, this implements `Serialize` and `Deserialize`,
+/// and deserializing fails unless the string is valid. Binary formats hold the
+/// decoded bytes instead, which is lossless since the string is canonical.
+///
+/// With the `schemars` feature enabled, this implements `schemars::JsonSchema`
+/// as a string with a pattern constraint.
+///
+/// With the `arbitrary` feature enabled, this implements `arbitrary::Arbitrary`
+/// for fuzzing.
+///
+/// With the `zeroize` feature enabled, this implements `Zeroize` and
+/// `ZeroizeOnDrop`, so the string is wiped when dropped.
+///
+/// With the `defmt` feature enabled, this implements `defmt::Format`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::Error> {
+/// use clockwork_base32::Base32String;
+/// use std::convert::TryFrom;
+///
+/// let encoded = Base32String::encode("foobar");
+/// assert_eq!(encoded, "CSQPYRK1E8");
+/// assert_eq!(encoded.decode(), b"foobar");
+///
+/// let parsed = Base32String::try_from("CSQPYRK1E8")?;
+/// assert_eq!(parsed, encoded);
+/// assert!(parsed.starts_with("CSQ"));
+///
+/// // An invalid symbol.
+/// assert!(Base32String::try_from("CSQPU").is_err());
+/// // Non-zero padding bits in the last symbol.
+/// assert!(Base32String::try_from("CS").is_err());
+/// // No bytes encode to 3 symbols.
+/// assert!(Base32String::try_from("CSQ").is_err());
+/// // Lowercase symbols.
+/// assert!(Base32String::try_from("csqpyrk1e8").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow),
+    diesel(sql_type = diesel::sql_types::Text)
+)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Base32String(String);
+
+impl Base32String {
+    /// Encodes bytes to a new string.
+    pub fn encode<'a, I>(input: I) -> Self
+    where
+        I: IntoInput<'a>,
+    {
+        Self(CLOCKWORK.encode(input))
+    }
+
+    /// Decodes the string to a new [`Vec<u8>`].
+    pub fn decode(&self) -> Vec<u8> {
+        // The string is validated on creation, so this never fails.
+        CLOCKWORK.decode(&self.0).unwrap_or_default()
+    }
+
+    /// Returns the length of the decoded bytes.
+    pub fn decoded_len(&self) -> usize {
+        capacity_hint_for_decode(self.0.len())
+    }
+
+    /// Returns the encoded string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Converts into the encoded [`String`].
+    pub fn into_string(mut self) -> String {
+        core::mem::take(&mut self.0)
+    }
+}
+
+impl TryFrom<String> for Base32String {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        check_canonical(s.as_bytes())?;
+        Ok(Self(s))
+    }
+}
+
+impl TryFrom<&str> for Base32String {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        check_canonical(s.as_bytes())?;
+        Ok(Self(String::from(s)))
+    }
+}
+
+impl FromStr for Base32String {
+    type Err = ParseError;
+
+    /// Parses a canonical encoded string.
+    ///
+    /// Unlike [`TryFrom`], the error holds the position of the problem and suggests
+    /// how to fix it.
+    fn from_str(s: &str) -> core::result::Result<Self, ParseError> {
+        check_canonical(s.as_bytes())?;
+        Ok(Self(String::from(s)))
+    }
+}
+
+impl From<Base32String> for String {
+    fn from(s: Base32String) -> Self {
+        s.into_string()
+    }
+}
+
+impl Deref for Base32String {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Base32String {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Base32String {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Base32String {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for Base32String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Debug for Base32String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Base32String").field(&self.0).finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Base32String {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "Base32String({=str})", self.as_str());
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Base32String {
+    fn serialize<S: ::serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.0)
+        } else {
+            serializer.serialize_bytes(&self.decode())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Base32String {
+    fn deserialize<D: ::serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            Self::try_from(s).map_err(::serde::de::Error::custom)
+        } else {
+            let bytes: Vec<u8> = crate::serde::deserialize(deserializer)?;
+            Ok(Self::encode(&bytes))
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl ::schemars::JsonSchema for Base32String {
+    fn schema_name() -> alloc::borrow::Cow<'static, str> {
+        "Base32String".into()
+    }
+
+    fn schema_id() -> alloc::borrow::Cow<'static, str> {
+        "clockwork_base32::Base32String".into()
+    }
+
+    fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        // The pattern accepts the symbols and the lengths which encoding produces.
+        ::schemars::json_schema!({
+            "type": "string",
+            "pattern": "^(?:[0-9A-HJKMNP-TV-Z]{8})*(?:[0-9A-HJKMNP-TV-Z]{2}|[0-9A-HJKMNP-TV-Z]{4,5}|[0-9A-HJKMNP-TV-Z]{7})?$",
+            "description": "Bytes encoded in canonical Clockwork Base32",
+        })
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for Base32String {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        let bytes = <Vec<u8> as ::arbitrary::Arbitrary>::arbitrary(u)?;
+        Ok(Self::encode(&bytes))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <Vec<u8> as ::arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ::zeroize::Zeroize for Base32String {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Base32String {
+    fn drop(&mut self) {
+        ::zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ::zeroize::ZeroizeOnDrop for Base32String {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical() {
+        for len in 0..=20 {
+            let bytes: Vec<u8> = (0..len).map(|i| 0xff - i as u8).collect();
+            let encoded = Base32String::encode(&bytes);
+            assert_eq!(encoded.decoded_len(), len);
+            assert_eq!(encoded.decode(), bytes);
+            let parsed = Base32String::try_from(encoded.as_str()).unwrap();
+            assert_eq!(parsed, encoded);
+        }
+        for (input, expected) in [
+            ("C", Error::NonCanonical),
+            ("CR1", Error::NonCanonical),
+            ("CS", Error::NonCanonical),
+            ("CSQPYRK1E9", Error::NonCanonical),
+            (
+                "CSQPYRK1E8U",
+                Error::InvalidSymbol {
+                    byte: b'U',
+                    position: 10,
+                },
+            ),
+        ] {
+            let err = Base32String::try_from(input).unwrap_err();
+            assert_eq!(err.to_string(), expected.to_string(), "{}", input);
+        }
+        // Lowercase and aliased symbols decode but are not canonical.
+        assert!(Base32String::try_from("csqpyrk1e8").is_err());
+        assert!(Base32String::try_from("CSQPYRKIE8").is_err());
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "CSQPYRK1E8".parse::<Base32String>().unwrap(),
+            Base32String::encode("foobar")
+        );
+        for (input, position, message) in [
+            (
+                "CSQPU",
+                4,
+                "invalid symbol 'U' at position 4, 'U' is excluded from the alphabet, did you mean 'V'?",
+            ),
+            ("CSQ-PY", 3, "invalid symbol '-' at position 3, remove hyphens"),
+            ("CSQ PY", 3, "invalid symbol ' ' at position 3, remove whitespace"),
+            ("CR======", 2, "invalid symbol '=' at position 2, padding is not used, remove it"),
+            ("CSQP#", 4, "invalid symbol '#' at position 4"),
+            ("CsQPY", 1, "non-canonical symbol at position 1, expected 'S'"),
+            ("CSQPYRKIE8", 7, "non-canonical symbol at position 7, expected '1'"),
+            ("CS", 1, "non-canonical symbol at position 1, expected 'R'"),
+            (
+                "CSQ",
+                3,
+                "invalid length 3, no bytes are encoded to this length; is a symbol missing or extra?",
+            ),
+        ] {
+            let err = input.parse::<Base32String>().unwrap_err();
+            assert_eq!(err.position(), position, "{}", input);
+            assert_eq!(err.to_string(), message);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let encoded = Base32String::encode("foo");
+        let json = serde_json::to_string(&encoded).unwrap();
+        assert_eq!(json, r#""CSQPY""#);
+        assert_eq!(
+            serde_json::from_str::<Base32String>(&json).unwrap(),
+            encoded
+        );
+        assert!(serde_json::from_str::<Base32String>(r#""CSQPZ""#).is_err());
+
+        let bytes = postcard::to_allocvec(&encoded).unwrap();
+        assert_eq!(bytes, b"\x03foo");
+        assert_eq!(
+            postcard::from_bytes::<Base32String>(&bytes).unwrap(),
+            encoded
+        );
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_json_schema() {
+        let schema = schemars::schema_for!(Base32String);
+        assert_eq!(schema.get("type").and_then(|t| t.as_str()), Some("string"));
+        let pattern = schema.get("pattern").and_then(|p| p.as_str()).unwrap();
+        let re = regex::Regex::new(pattern).unwrap();
+        for len in 0..=20 {
+            let encoded = Base32String::encode(&vec![0xa5; len]);
+            assert!(re.is_match(&encoded), "{}", encoded);
+        }
+        for invalid in ["C", "CSQ", "CSQPYR", "csqpy", "CSQPU"] {
+            assert!(!re.is_match(invalid), "{}", invalid);
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+        let data = [3, 0xff, 0x00, 0xa5, 0x5a, 1, 2, 3, 4, 5, 6, 7, 8];
+        let mut u = Unstructured::new(&data);
+        let encoded = Base32String::arbitrary(&mut u).unwrap();
+        assert_eq!(Base32String::try_from(encoded.as_str()).unwrap(), encoded);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize() {
+        use zeroize::Zeroize;
+        let mut encoded = Base32String::encode("foobar");
+        encoded.zeroize();
+        assert_eq!(encoded, "");
+        assert_eq!(Base32String::encode("foobar").into_string(), "CSQPYRK1E8");
+    }
+}
@@ -0,0 +1,155 @@
+//! Versioned tokens, so the payload format can evolve behind one wire format.
+//!
+//! A [`Token`] is encoded as its version byte followed by the payload. The
+//! version is the first byte, so it is always in the first two symbols,
+//! and [`Token::parse`] rejects the versions a service doesn't know before it
+//! looks at the payload.
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::token::{Token, TokenError};
+//!
+//! let token = Token::new(2, b"user:42".to_vec());
+//! let encoded = token.encode();
+//! assert_eq!(encoded, "09TQ6SBJ78T34");
+//! assert_eq!(Token::parse(&encoded, &[1, 2]).unwrap(), token);
+//! assert!(matches!(
+//!     Token::parse(&encoded, &[1]),
+//!     Err(TokenError::UnknownVersion(2))
+//! ));
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{encode_to_string, Error, IntoInput, CLOCKWORK};
+
+/// A token of a version byte and payload bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Token {
+    /// The version of the payload format.
+    pub version: u8,
+    /// The payload in the format of the version.
+    pub payload: Vec<u8>,
+}
+
+impl Token {
+    /// Creates a token.
+    pub fn new(version: u8, payload: Vec<u8>) -> Self {
+        Self { version, payload }
+    }
+
+    /// Returns the encoded version and payload.
+    pub fn encode(&self) -> String {
+        let mut bytes = Vec::with_capacity(1 + self.payload.len());
+        bytes.push(self.version);
+        bytes.extend_from_slice(&self.payload);
+        encode_to_string(&bytes)
+    }
+
+    /// Decodes a token of any version.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if the input can't be decoded or has no version byte.
+    pub fn decode<'a, I>(input: I) -> Result<Self, TokenError>
+    where
+        I: IntoInput<'a>,
+    {
+        let mut payload = CLOCKWORK.decode(input)?;
+        if payload.is_empty() {
+            return Err(TokenError::Empty);
+        }
+        let version = payload.remove(0);
+        Ok(Self { version, payload })
+    }
+
+    /// Decodes a token whose version is one of `versions`.
+    ///
+    /// # Errors
+    /// Returns [`Err`] like [`decode`](Self::decode), or if the version is not
+    /// one of `versions`.
+    pub fn parse<'a, I>(input: I, versions: &[u8]) -> Result<Self, TokenError>
+    where
+        I: IntoInput<'a>,
+    {
+        let token = Self::decode(input)?;
+        if !versions.contains(&token.version) {
+            return Err(TokenError::UnknownVersion(token.version));
+        }
+        Ok(token)
+    }
+}
+
+/// The error type for decoding tokens.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum TokenError {
+    /// The input can't be decoded.
+    Decode(Error),
+    /// The input decodes to no bytes, so it has no version.
+    Empty,
+    /// The version is not one of the known versions.
+    UnknownVersion(u8),
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenError::Decode(err) => err.fmt(f),
+            TokenError::Empty => f.write_str("token has no version"),
+            TokenError::UnknownVersion(version) => {
+                write!(f, "token version {} is unknown", version)
+            }
+        }
+    }
+}
+
+impl core::error::Error for TokenError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            TokenError::Decode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for TokenError {
+    fn from(err: Error) -> Self {
+        TokenError::Decode(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn test_token() {
+        for len in 0..12 {
+            for version in [0, 1, 0xff] {
+                let token = Token::new(version, vec![0x5a; len]);
+                let encoded = token.encode();
+                assert_eq!(Token::decode(&encoded).unwrap(), token);
+                assert_eq!(Token::parse(&encoded, &[version]).unwrap(), token);
+            }
+        }
+        assert_eq!(Token::new(0xff, Vec::new()).encode(), "ZW");
+
+        assert!(matches!(Token::decode(""), Err(TokenError::Empty)));
+        assert!(matches!(
+            Token::decode("ZU"),
+            Err(TokenError::Decode(Error::InvalidSymbol { byte: b'U', .. }))
+        ));
+        let err = Token::parse("ZW", &[0, 1]).unwrap_err();
+        assert!(matches!(err, TokenError::UnknownVersion(0xff)));
+        assert_eq!(err.to_string(), "token version 255 is unknown");
+        assert!(matches!(
+            Token::parse("ZW", &[]),
+            Err(TokenError::UnknownVersion(0xff))
+        ));
+    }
+}
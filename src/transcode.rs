@@ -0,0 +1,416 @@
+//! Streaming transcoding between hex or base64 text and encoded text.
+//!
+//! Existing datasets in hex or base64 are re-encoded chunk by chunk through
+//! fixed-size buffers, without decoding them to a whole binary first.
+//! [`TextReader`] and [`TextWriter`] are the adapters behind [`to_base32`] and
+//! [`from_base32`], for combining with the other adapters in [`io`](crate::io).
+//!
+//! # Examples
+//! ```
+//! # fn main() -> std::io::Result<()> {
+//! use clockwork_base32::transcode::{self, TextFormat};
+//!
+//! let encoded = transcode::to_base32(TextFormat::Hex, &b"48656c6c6f"[..], Vec::new())?;
+//! assert_eq!(encoded, b"91JPRV3F");
+//! let encoded = transcode::to_base32(TextFormat::Base64, &b"SGVsbG8="[..], Vec::new())?;
+//! assert_eq!(encoded, b"91JPRV3F");
+//!
+//! let base64 = transcode::from_base32(TextFormat::Base64, &b"91JPRV3F\n"[..], Vec::new())?;
+//! assert_eq!(base64, b"SGVsbG8=");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{self, Read, Write};
+
+use crate::io::{DecodeWriter, EncodeWriter};
+
+// The size of the buffers used by the adapters.
+const BUF_LEN: usize = 4096;
+
+const HEX_SYMBOLS: &[u8; 16] = b"0123456789abcdef";
+const BASE64_SYMBOLS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A text representation of bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TextFormat {
+    /// Hex digits, lowercase when writing and in either case when reading.
+    Hex,
+    /// Base64 of RFC 4648 with the standard alphabet and padding when writing.
+    /// Padding is optional when reading.
+    Base64,
+}
+
+impl TextFormat {
+    // bits returns the number of bits each symbol holds.
+    fn bits(self) -> u32 {
+        match self {
+            TextFormat::Hex => 4,
+            TextFormat::Base64 => 6,
+        }
+    }
+
+    // value returns the value of the symbol `b`, if valid.
+    fn value(self, b: u8) -> Option<u8> {
+        match self {
+            TextFormat::Hex => (b as char).to_digit(16).map(|v| v as u8),
+            TextFormat::Base64 => match b {
+                b'A'..=b'Z' => Some(b - b'A'),
+                b'a'..=b'z' => Some(b - b'a' + 26),
+                b'0'..=b'9' => Some(b - b'0' + 52),
+                b'+' => Some(62),
+                b'/' => Some(63),
+                _ => None,
+            },
+        }
+    }
+
+    // symbol returns the symbol of the value `v`.
+    fn symbol(self, v: u8) -> u8 {
+        let symbols: &[u8] = match self {
+            TextFormat::Hex => HEX_SYMBOLS,
+            TextFormat::Base64 => BASE64_SYMBOLS,
+        };
+        symbols.get(v as usize).copied().unwrap_or_default()
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            TextFormat::Hex => "hex",
+            TextFormat::Base64 => "base64",
+        }
+    }
+}
+
+/// Encodes the hex or base64 text read from `input` and writes the encoded
+/// text to `output`, returning it.
+///
+/// ASCII whitespace in the input is ignored.
+///
+/// # Errors
+/// Returns [`Err`] with [`io::ErrorKind::InvalidData`] if the input is not
+/// valid in `format`, or if reading or writing fails.
+pub fn to_base32<R: Read, W: Write>(format: TextFormat, input: R, output: W) -> io::Result<W> {
+    let mut reader = TextReader::new(format, input);
+    let mut writer = EncodeWriter::new(output);
+    io::copy(&mut reader, &mut writer)?;
+    writer.finish()
+}
+
+/// Decodes the encoded text read from `input` and writes it as hex or base64
+/// text to `output`, returning it.
+///
+/// ASCII whitespace in the input is ignored.
+///
+/// # Errors
+/// Returns [`Err`] if the input contains an invalid symbol, or if reading or
+/// writing fails.
+pub fn from_base32<R: Read, W: Write>(
+    format: TextFormat,
+    mut input: R,
+    output: W,
+) -> io::Result<W> {
+    let mut writer = DecodeWriter::new(TextWriter::new(format, output));
+    let mut buf = [0; BUF_LEN];
+    loop {
+        let n = match input.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        let symbols = buf.get(..n).unwrap_or_default();
+        for symbols in symbols.split(u8::is_ascii_whitespace) {
+            writer.write_all(symbols)?;
+        }
+    }
+    writer.finish()?.finish()
+}
+
+/// A reader which reads hex or base64 text from the inner reader and returns
+/// the bytes.
+///
+/// ASCII whitespace in the text is ignored.
+#[derive(Debug)]
+pub struct TextReader<R: Read> {
+    format: TextFormat,
+    inner: R,
+    text: Vec<u8>,
+    // The bytes decoded but not returned yet, from `pos`.
+    decoded: Vec<u8>,
+    pos: usize,
+    acc: u32,
+    bits: u32,
+    padded: bool,
+    done: bool,
+}
+
+impl<R: Read> TextReader<R> {
+    /// Creates a new reader which reads text in `format` from `inner`.
+    pub fn new(format: TextFormat, inner: R) -> Self {
+        Self {
+            format,
+            inner,
+            text: vec![0; BUF_LEN],
+            decoded: Vec::with_capacity(BUF_LEN),
+            pos: 0,
+            acc: 0,
+            bits: 0,
+            padded: false,
+            done: false,
+        }
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn push(&mut self, n: usize) -> io::Result<()> {
+        let format = self.format;
+        for &b in self.text.get(..n).unwrap_or_default() {
+            if b.is_ascii_whitespace() {
+                continue;
+            }
+            if format == TextFormat::Base64 && b == b'=' {
+                self.padded = true;
+                continue;
+            }
+            let value = match format.value(b) {
+                Some(value) if !self.padded => value,
+                _ => return Err(invalid_text(format, b)),
+            };
+            self.acc = (self.acc << format.bits()) | value as u32;
+            self.bits += format.bits();
+            if self.bits >= 8 {
+                self.bits -= 8;
+                self.decoded.push((self.acc >> self.bits) as u8);
+                self.acc &= (1 << self.bits) - 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&self) -> io::Result<()> {
+        // A base64 symbol alone holds less than a byte, and so does a hex digit.
+        if self.bits >= self.format.bits() {
+            let message = format!("truncated {} text", self.format.name());
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for TextReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let decoded = self.decoded.get(self.pos..).unwrap_or_default();
+            if !decoded.is_empty() || self.done || buf.is_empty() {
+                let len = decoded.len().min(buf.len());
+                let dest = buf.get_mut(..len).unwrap_or_default();
+                dest.copy_from_slice(decoded.get(..len).unwrap_or_default());
+                self.pos += len;
+                return Ok(len);
+            }
+            self.decoded.clear();
+            self.pos = 0;
+            let n = self.inner.read(&mut self.text)?;
+            if n == 0 {
+                self.finish()?;
+                self.done = true;
+            } else {
+                self.push(n)?;
+            }
+        }
+    }
+}
+
+/// A writer which writes the written bytes as hex or base64 text to the inner
+/// writer.
+///
+/// The last base64 symbols and padding are written by [`TextWriter::finish`].
+#[derive(Debug)]
+pub struct TextWriter<W: Write> {
+    format: TextFormat,
+    inner: W,
+    text: Vec<u8>,
+    acc: u32,
+    bits: u32,
+    // The number of symbols written, for the base64 padding.
+    len: usize,
+}
+
+impl<W: Write> TextWriter<W> {
+    /// Creates a new writer which writes text in `format` to `inner`.
+    pub fn new(format: TextFormat, inner: W) -> Self {
+        Self {
+            format,
+            inner,
+            text: Vec::with_capacity(BUF_LEN),
+            acc: 0,
+            bits: 0,
+            len: 0,
+        }
+    }
+
+    /// Writes the last symbols and padding, and returns the inner writer.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if writing to the inner writer fails.
+    pub fn finish(mut self) -> io::Result<W> {
+        let format = self.format;
+        self.text.clear();
+        if self.bits > 0 {
+            let value = (self.acc << (format.bits() - self.bits)) as u8 & 0x3f;
+            self.text.push(format.symbol(value));
+            self.len += 1;
+        }
+        if format == TextFormat::Base64 {
+            let padding = (4 - self.len % 4) % 4;
+            self.text.resize(self.text.len() + padding, b'=');
+        }
+        self.inner.write_all(&self.text)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for TextWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let format = self.format;
+        let mask = (1 << format.bits()) - 1;
+        let buf = buf.get(..BUF_LEN / 2).unwrap_or(buf);
+        self.text.clear();
+        for &b in buf {
+            self.acc = (self.acc << 8) | b as u32;
+            self.bits += 8;
+            while self.bits >= format.bits() {
+                self.bits -= format.bits();
+                self.text
+                    .push(format.symbol((self.acc >> self.bits) as u8 & mask));
+            }
+            self.acc &= (1 << self.bits) - 1;
+        }
+        self.len += self.text.len();
+        self.inner.write_all(&self.text)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn invalid_text(format: TextFormat, b: u8) -> io::Error {
+    let message = if b.is_ascii_graphic() {
+        format!("invalid {} symbol {:?}", format.name(), b as char)
+    } else {
+        format!("invalid {} symbol 0x{:02x}", format.name(), b)
+    };
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads through a reader returning one byte at a time, to test state
+    // carried between reads.
+    struct OneByte<'a>(&'a [u8]);
+
+    impl Read for OneByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.0.len().min(buf.len()).min(1);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_transcode() {
+        let cases: [(&[u8], &str, &str); 5] = [
+            (b"", "", ""),
+            (b"f", "66", "Zg=="),
+            (b"fo", "666f", "Zm8="),
+            (b"foo", "666f6f", "Zm9v"),
+            (
+                b"Hello, world!",
+                "48656c6c6f2c20776f726c6421",
+                "SGVsbG8sIHdvcmxkIQ==",
+            ),
+        ];
+        for (bytes, hex, base64) in cases {
+            let encoded = crate::encode_to_vec(bytes);
+            for (format, text) in [(TextFormat::Hex, hex), (TextFormat::Base64, base64)] {
+                let to = to_base32(format, text.as_bytes(), Vec::new()).unwrap();
+                assert_eq!(to, encoded);
+                let to = to_base32(format, OneByte(text.as_bytes()), Vec::new()).unwrap();
+                assert_eq!(to, encoded);
+                let from = from_base32(format, &encoded[..], Vec::new()).unwrap();
+                assert_eq!(from, text.as_bytes());
+            }
+        }
+
+        let large: Vec<u8> = (0..10 * BUF_LEN).map(|i| (i * 7) as u8).collect();
+        let encoded = crate::encode_to_vec(&large);
+        let base64 = from_base32(TextFormat::Base64, &encoded[..], Vec::new()).unwrap();
+        assert_eq!(base64.len(), large.len().div_ceil(3) * 4);
+        let mut hex = Vec::new();
+        let mut writer = TextWriter::new(TextFormat::Hex, &mut hex);
+        writer.write_all(&large).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(hex.len(), large.len() * 2);
+        assert_eq!(
+            to_base32(TextFormat::Base64, &base64[..], Vec::new()).unwrap(),
+            encoded
+        );
+        assert_eq!(
+            to_base32(TextFormat::Hex, &hex[..], Vec::new()).unwrap(),
+            encoded
+        );
+    }
+
+    #[test]
+    fn test_lenient_input() {
+        let to = |format, text: &[u8]| {
+            to_base32(format, text, Vec::new()).map_err(|err| err.to_string())
+        };
+        assert_eq!(
+            to(TextFormat::Hex, b"48 65\n6C6c6f\n"),
+            Ok(b"91JPRV3F".to_vec())
+        );
+        assert_eq!(
+            to(TextFormat::Base64, b"SGVs\nbG8"),
+            Ok(b"91JPRV3F".to_vec())
+        );
+        assert_eq!(
+            from_base32(TextFormat::Hex, &b"91jp rv3f\r\n"[..], Vec::new()).unwrap(),
+            b"48656c6c6f"
+        );
+
+        assert_eq!(
+            to(TextFormat::Hex, b"4865x"),
+            Err("invalid hex symbol 'x'".to_string())
+        );
+        assert_eq!(
+            to(TextFormat::Hex, b"486"),
+            Err("truncated hex text".to_string())
+        );
+        assert_eq!(
+            to(TextFormat::Base64, b"SGVsb"),
+            Err("truncated base64 text".to_string())
+        );
+        assert_eq!(
+            to(TextFormat::Base64, b"Zg==Zg=="),
+            Err("invalid base64 symbol 'Z'".to_string())
+        );
+        assert_eq!(
+            to(TextFormat::Base64, b"Zg-_"),
+            Err("invalid base64 symbol '-'".to_string())
+        );
+        assert!(from_base32(TextFormat::Hex, &b"CSQPU"[..], Vec::new()).is_err());
+    }
+}
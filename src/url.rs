@@ -0,0 +1,219 @@
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+
+use crate::{check_canonical, ParseError};
+
+/// A wrapper around a string which is always the canonical encoding of some
+/// bytes, for IDs in URL path segments and query parameters.
+///
+/// The symbols are unreserved characters in URIs, so the wrapped string is put
+/// in a URL as is, without percent-encoding. Parsing accepts only the canonical
+/// encoding, so every ID has exactly one URL and routes or caches keyed by the
+/// URL never see two spellings of the same ID.
+///
+/// Unlike [`Base32String`](crate::Base32String), any string type can be
+/// wrapped: `UrlSafe<&str>` borrows from the request without `alloc`, and
+/// `UrlSafe<String>` or `UrlSafe<Box<str>>` own the text.
+///
+/// With the `serde` feature enabled, this implements `Serialize` and
+/// `Deserialize` as a string in every format, and deserializing fails unless
+/// the string is canonical. Deserializing `UrlSafe<&str>` borrows from the
+/// input.
+///
+/// With the `defmt` feature enabled, this implements `defmt::Format` for any
+/// wrapped string type.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), clockwork_base32::ParseError> {
+/// use clockwork_base32::UrlSafe;
+///
+/// let id = UrlSafe::new("CSQPYRK1E8")?;
+/// assert_eq!(format!("/items/{}", id), "/items/CSQPYRK1E8");
+///
+/// let id: UrlSafe<String> = "CSQPYRK1E8".parse()?;
+/// assert_eq!(id.into_inner(), "CSQPYRK1E8");
+///
+/// let err = UrlSafe::new("csqpyrk1e8").unwrap_err();
+/// assert_eq!(err.to_string(), "non-canonical symbol at position 0, expected 'C'");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UrlSafe<S>(S);
+
+impl<S: AsRef<str>> UrlSafe<S> {
+    /// Wraps `s` if it is a canonical encoding.
+    ///
+    /// # Errors
+    /// Returns [`Err`] with the position of the problem if `s` contains an
+    /// invalid, lowercase or aliased symbol, or its length or padding bits are
+    /// not produced by encoding.
+    pub fn new(s: S) -> Result<Self, ParseError> {
+        check_canonical(s.as_ref().as_bytes())?;
+        Ok(Self(s))
+    }
+
+    /// Returns the encoded string.
+    pub fn as_str(&self) -> &str {
+        self.0.as_ref()
+    }
+
+    /// Returns the wrapped string.
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: AsRef<str>> UrlSafe<S> {
+    /// Decodes the string to a new [`Vec<u8>`](alloc::vec::Vec).
+    pub fn decode(&self) -> alloc::vec::Vec<u8> {
+        // The string is validated on creation, so this never fails.
+        crate::CLOCKWORK.decode(self.as_str()).unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl UrlSafe<alloc::string::String> {
+    /// Encodes bytes to a new string.
+    pub fn encode<'a, I>(input: I) -> Self
+    where
+        I: crate::IntoInput<'a>,
+    {
+        Self(crate::CLOCKWORK.encode(input))
+    }
+}
+
+impl<S> FromStr for UrlSafe<S>
+where
+    S: AsRef<str> + for<'a> From<&'a str>,
+{
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        check_canonical(s.as_bytes())?;
+        Ok(Self(S::from(s)))
+    }
+}
+
+impl<S: AsRef<str>> Deref for UrlSafe<S> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<S: AsRef<str>> AsRef<str> for UrlSafe<S> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<S: AsRef<str>> PartialEq<str> for UrlSafe<S> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<S: AsRef<str>> PartialEq<&str> for UrlSafe<S> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<S: AsRef<str>> fmt::Display for UrlSafe<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<S: AsRef<str>> fmt::Debug for UrlSafe<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UrlSafe").field(&self.as_str()).finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<S: AsRef<str>> defmt::Format for UrlSafe<S> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "UrlSafe({=str})", self.as_str());
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<S: AsRef<str>> ::serde::Serialize for UrlSafe<S> {
+    fn serialize<T: ::serde::Serializer>(
+        &self,
+        serializer: T,
+    ) -> core::result::Result<T::Ok, T::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, S> ::serde::Deserialize<'de> for UrlSafe<S>
+where
+    S: AsRef<str> + ::serde::Deserialize<'de>,
+{
+    fn deserialize<D: ::serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        let s = S::deserialize(deserializer)?;
+        Self::new(s).map_err(::serde::de::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        for len in 0..=20 {
+            let bytes: Vec<u8> = (0..len).map(|i| 0xff - i as u8).collect();
+            let encoded = UrlSafe::encode(&bytes);
+            assert_eq!(encoded.decode(), bytes);
+            let borrowed = UrlSafe::new(encoded.as_str()).unwrap();
+            assert_eq!(borrowed, encoded.as_str());
+            assert_eq!(borrowed.parse::<UrlSafe<String>>().unwrap(), encoded);
+        }
+        for (input, position) in [("CSQPU", 4), ("CSQ-PY", 3), ("CsQPY", 1), ("CSQ", 3)] {
+            let err = UrlSafe::new(input).unwrap_err();
+            assert_eq!(err.position(), position, "{}", input);
+            let err = input.parse::<UrlSafe<Box<str>>>().unwrap_err();
+            assert_eq!(err.position(), position, "{}", input);
+        }
+        assert_eq!(
+            format!("{:?}", UrlSafe::new("CSQPY").unwrap()),
+            r#"UrlSafe("CSQPY")"#
+        );
+    }
+
+    #[test]
+    fn test_unreserved() {
+        // The unreserved characters of RFC 3986, never percent-encoded.
+        let unreserved = |b: u8| b.is_ascii_alphanumeric() || b"-._~".contains(&b);
+        let bytes: Vec<u8> = (0..=255).collect();
+        let encoded = UrlSafe::encode(&bytes);
+        assert!(encoded.bytes().all(unreserved));
+        assert!(crate::ENCODE_SYMBOLS.iter().all(|&b| unreserved(b)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let id: UrlSafe<&str> = serde_json::from_str(r#""CSQPY""#).unwrap();
+        assert_eq!(id, "CSQPY");
+        assert_eq!(serde_json::to_string(&id).unwrap(), r#""CSQPY""#);
+        assert!(serde_json::from_str::<UrlSafe<String>>(r#""csqpy""#).is_err());
+
+        // Binary formats hold the text too, unlike `Base32String`.
+        let bytes = postcard::to_allocvec(&id).unwrap();
+        assert_eq!(bytes, b"\x05CSQPY");
+        let decoded: UrlSafe<String> = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, "CSQPY");
+    }
+}
@@ -0,0 +1,371 @@
+//! Checking input given in chunks without decoding it.
+//!
+//! A [`Validator`] reads the input like a decoder but writes no output. It
+//! reports each problem as a [`ParseError`] with its position in the whole
+//! input to an optional callback, and [`Validator::finish`] returns a
+//! [`Validation`] with the decoded length and whether the input is valid and
+//! canonical. This is a pre-flight check before allocating for and committing
+//! to a decode, and it works without the `alloc` feature.
+//!
+//! [`Measurement`] holds the properties of a whole input measured in one pass by
+//! [`Clockwork::measure`](crate::Clockwork::measure).
+//!
+//! # Examples
+//! ```
+//! use clockwork_base32::validator::Validator;
+//! use clockwork_base32::Error;
+//!
+//! let mut validator = Validator::new();
+//! validator.update(b"91JPRV3F");
+//! validator.update(b"5GG7EVVJDHJ22");
+//! let validation = validator.finish();
+//! assert!(validation.is_valid());
+//! assert!(validation.is_canonical());
+//! assert_eq!(validation.decoded_len(), 13);
+//!
+//! let mut positions = Vec::new();
+//! let mut validator = Validator::with_callback(|err| positions.push(err.position()));
+//! validator.update(b"csqp");
+//! validator.update(b"UrK1-");
+//! let validation = validator.finish();
+//! assert!(!validation.is_valid());
+//! assert_eq!(validation.invalid_symbol_count(), 2);
+//! assert!(matches!(
+//!     validation.first_problem().map(|err| err.error()),
+//!     Some(Error::NonCanonical)
+//! ));
+//! assert_eq!(positions, [0, 1, 2, 3, 4, 5, 8, 7]);
+//! ```
+
+use crate::{
+    capacity_hint_for_decode, capacity_hint_for_encode, decode_symbol, encode_symbol,
+    is_symbol_value, Error, ParseError, BYTE_BIT_LEN, DECODED_BIT_LEN,
+};
+
+/// A validator of input given in chunks, reporting problems to a callback.
+///
+/// See the [module documentation](self) for examples.
+#[derive(Debug)]
+pub struct Validator<F = fn(&ParseError)> {
+    on_problem: F,
+    // The number of bytes given so far.
+    len: usize,
+    // The number of valid symbols given so far, and the value and the
+    // position of the last of them.
+    symbol_len: usize,
+    last: u8,
+    last_position: usize,
+    invalid_symbol_count: usize,
+    non_canonical_count: usize,
+    first_problem: Option<ParseError>,
+}
+
+impl Validator {
+    /// Creates a validator without a callback.
+    pub fn new() -> Self {
+        Self::with_callback(|_| {})
+    }
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> Validator<F>
+where
+    F: FnMut(&ParseError),
+{
+    /// Creates a validator which calls `on_problem` with each problem found.
+    pub fn with_callback(on_problem: F) -> Self {
+        Self {
+            on_problem,
+            len: 0,
+            symbol_len: 0,
+            last: 0,
+            last_position: 0,
+            invalid_symbol_count: 0,
+            non_canonical_count: 0,
+            first_problem: None,
+        }
+    }
+
+    /// Checks the next chunk of the input.
+    pub fn update(&mut self, input: &[u8]) {
+        for &b in input {
+            let v = decode_symbol(b);
+            if !is_symbol_value(v) {
+                self.invalid_symbol_count += 1;
+                self.report(ParseError::invalid_symbol(b, self.len));
+            } else {
+                let canonical = encode_symbol(v);
+                if canonical != b {
+                    self.non_canonical_count += 1;
+                    self.report(ParseError::new(
+                        Error::NonCanonical,
+                        self.len,
+                        Some(canonical),
+                    ));
+                }
+                self.symbol_len += 1;
+                self.last = v;
+                self.last_position = self.len;
+            }
+            self.len += 1;
+        }
+    }
+
+    /// Checks the length and the padding bits of the last symbol and returns
+    /// the result for the whole input.
+    pub fn finish(mut self) -> Validation {
+        let decoded_len = capacity_hint_for_decode(self.symbol_len);
+        if capacity_hint_for_encode(decoded_len) != self.symbol_len {
+            self.non_canonical_count += 1;
+            self.report(ParseError::new(Error::NonCanonical, self.len, None));
+        } else {
+            let padding_bits = self.symbol_len * DECODED_BIT_LEN - decoded_len * BYTE_BIT_LEN;
+            let canonical = self.last >> padding_bits << padding_bits;
+            if canonical != self.last {
+                self.non_canonical_count += 1;
+                self.report(ParseError::new(
+                    Error::NonCanonical,
+                    self.last_position,
+                    Some(encode_symbol(canonical)),
+                ));
+            }
+        }
+        Validation {
+            len: self.len,
+            decoded_len,
+            invalid_symbol_count: self.invalid_symbol_count,
+            non_canonical_count: self.non_canonical_count,
+            first_problem: self.first_problem,
+        }
+    }
+
+    fn report(&mut self, err: ParseError) {
+        (self.on_problem)(&err);
+        if self.first_problem.is_none() {
+            self.first_problem = Some(err);
+        }
+    }
+}
+
+/// The result of validating the whole input, returned by [`Validator::finish`].
+#[derive(Debug)]
+pub struct Validation {
+    len: usize,
+    decoded_len: usize,
+    invalid_symbol_count: usize,
+    non_canonical_count: usize,
+    first_problem: Option<ParseError>,
+}
+
+impl Validation {
+    /// Returns the length of the input in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the input is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the length of the decoded result, which is exact if the input
+    /// is valid. Invalid symbols are not counted.
+    pub fn decoded_len(&self) -> usize {
+        self.decoded_len
+    }
+
+    /// Returns whether the input can be decoded.
+    pub fn is_valid(&self) -> bool {
+        self.invalid_symbol_count == 0
+    }
+
+    /// Returns whether the input is the canonical encoding of its decoded
+    /// bytes, as the encode functions give.
+    pub fn is_canonical(&self) -> bool {
+        self.is_valid() && self.non_canonical_count == 0
+    }
+
+    /// Returns the number of invalid symbols.
+    pub fn invalid_symbol_count(&self) -> usize {
+        self.invalid_symbol_count
+    }
+
+    /// Returns the number of non-canonical symbols, plus one for an invalid
+    /// length or nonzero padding bits.
+    pub fn non_canonical_count(&self) -> usize {
+        self.non_canonical_count
+    }
+
+    /// Returns the first problem found, if any.
+    pub fn first_problem(&self) -> Option<&ParseError> {
+        self.first_problem.as_ref()
+    }
+}
+
+/// The properties of an input measured in one pass by
+/// [`Clockwork::measure`](crate::Clockwork::measure), to decide how to decode
+/// it and how much to allocate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Measurement {
+    /// The length of the decoded result of the symbols, not counting
+    /// separators and invalid symbols.
+    pub decoded_len: usize,
+    /// Whether the input can be decoded as is, without separators and invalid
+    /// symbols.
+    pub is_valid: bool,
+    /// Whether the input is the canonical encoding of its decoded bytes.
+    pub is_canonical: bool,
+    /// Whether the input contains the aliases `I`, `L` or `O` in either case.
+    pub uses_aliases: bool,
+    /// Whether the input contains separators, hyphens or ASCII whitespace.
+    pub has_separators: bool,
+}
+
+impl Measurement {
+    pub(crate) fn new(input: &[u8]) -> Self {
+        let mut symbol_len = 0;
+        let mut last = 0;
+        let mut has_invalid = false;
+        let mut has_non_canonical = false;
+        let mut uses_aliases = false;
+        let mut has_separators = false;
+        for &b in input {
+            if b == b'-' || b.is_ascii_whitespace() {
+                has_separators = true;
+                continue;
+            }
+            let v = decode_symbol(b);
+            if !is_symbol_value(v) {
+                has_invalid = true;
+                continue;
+            }
+            let canonical = encode_symbol(v);
+            if canonical != b {
+                has_non_canonical = true;
+                uses_aliases |= canonical != b.to_ascii_uppercase();
+            }
+            symbol_len += 1;
+            last = v;
+        }
+        let decoded_len = capacity_hint_for_decode(symbol_len);
+        let padding_bits = symbol_len * DECODED_BIT_LEN - decoded_len * BYTE_BIT_LEN;
+        let is_valid = !has_invalid && !has_separators;
+        Self {
+            decoded_len,
+            is_valid,
+            is_canonical: is_valid
+                && !has_non_canonical
+                && capacity_hint_for_encode(decoded_len) == symbol_len
+                && last >> padding_bits << padding_bits == last,
+            uses_aliases,
+            has_separators,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{decode_to_vec, encode_to_vec, CLOCKWORK};
+
+    fn validate(input: &[u8], chunk_len: usize) -> (Validation, Vec<usize>) {
+        let mut positions = Vec::new();
+        let mut validator = Validator::with_callback(|err| positions.push(err.position()));
+        for chunk in input.chunks(chunk_len) {
+            validator.update(chunk);
+        }
+        (validator.finish(), positions)
+    }
+
+    #[test]
+    fn test_validator() {
+        let plain: Vec<u8> = (0..=255).rev().collect();
+        for len in 0..40 {
+            let encoded = encode_to_vec(&plain[..len]);
+            for chunk_len in [1, 3, 8, 64] {
+                let (validation, positions) = validate(&encoded, chunk_len);
+                assert!(validation.is_canonical());
+                assert_eq!(validation.decoded_len(), len);
+                assert_eq!(validation.len(), encoded.len());
+                assert!(positions.is_empty());
+            }
+        }
+
+        for input in [
+            &b"csqpyrk1"[..],
+            b"CSQPYR",
+            b"CSQPZ",
+            b"CSQPU",
+            b"CS QP",
+            b"OI",
+            b"",
+        ] {
+            let (validation, _) = validate(input, 2);
+            assert_eq!(validation.is_valid(), decode_to_vec(input).is_ok());
+            assert_eq!(
+                validation.is_canonical(),
+                CLOCKWORK.validate_canonical(input).is_ok()
+            );
+            if let Err(err) = CLOCKWORK.validate_canonical(input) {
+                let first = validation.first_problem().unwrap();
+                assert_eq!(first.position(), err.position());
+                assert_eq!(first.to_string(), err.to_string());
+            }
+        }
+
+        let (validation, positions) = validate(b"CSQPZ", 2);
+        assert!(validation.is_valid());
+        assert!(!validation.is_canonical());
+        assert_eq!(validation.decoded_len(), 3);
+        assert_eq!(positions, [4]);
+
+        let (validation, positions) = validate(b"cs\nqp\nu", 2);
+        assert_eq!(validation.invalid_symbol_count(), 3);
+        assert_eq!(validation.non_canonical_count(), 5);
+        assert_eq!(validation.decoded_len(), 2);
+        // The padding bits are reported at the last valid symbol.
+        assert_eq!(positions, [0, 1, 2, 3, 4, 5, 6, 4]);
+    }
+
+    #[test]
+    fn test_measure() {
+        for (input, decoded_len, is_valid, is_canonical, uses_aliases, has_separators) in [
+            ("", 0, true, true, false, false),
+            ("CSQPYRK1", 5, true, true, false, false),
+            ("csqpyrk1", 5, true, false, false, false),
+            ("CSQPYRKI", 5, true, false, true, false),
+            ("CSQPYRKl", 5, true, false, true, false),
+            ("CSQP-YRK1", 5, false, false, false, true),
+            ("CSQP YRK1\n", 5, false, false, false, true),
+            ("CSQPU", 2, false, false, false, false),
+            ("CSQPYR", 3, true, false, false, false),
+            ("CSQPZ", 3, true, false, false, false),
+            ("0O-", 1, false, false, true, true),
+        ] {
+            let m = CLOCKWORK.measure(input);
+            assert_eq!(m.decoded_len, decoded_len, "{:?}", input);
+            assert_eq!(m.is_valid, is_valid, "{:?}", input);
+            assert_eq!(m.is_canonical, is_canonical, "{:?}", input);
+            assert_eq!(m.uses_aliases, uses_aliases, "{:?}", input);
+            assert_eq!(m.has_separators, has_separators, "{:?}", input);
+            assert_eq!(
+                m.is_valid,
+                decode_to_vec(input.as_bytes()).is_ok(),
+                "{:?}",
+                input
+            );
+            assert_eq!(
+                m.is_canonical,
+                CLOCKWORK.validate_canonical(input).is_ok(),
+                "{:?}",
+                input
+            );
+        }
+    }
+}
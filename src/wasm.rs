@@ -0,0 +1,458 @@
+//! JavaScript bindings built with [wasm-bindgen](https://github.com/rustwasm/wasm-bindgen).
+//!
+//! Build a module for browsers (or Node.js with `--target nodejs`) with the
+//! `wasm-bindgen` command line tool:
+//!
+//! ```sh
+//! cargo rustc --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg \
+//!     target/wasm32-unknown-unknown/release/clockwork_base32.wasm
+//! ```
+//!
+//! The generated TypeScript declarations are:
+//!
+//! ```ts
+//! export function encode(input: Uint8Array): string;
+//! export function decode(input: string): Uint8Array;
+//! export function validate(input: string, strict: boolean): void;
+//! export function encodeStream(): TransformStream<Uint8Array, string>;
+//! export function decodeStream(): TransformStream<Uint8Array | string, Uint8Array>;
+//! export class StreamEncoder {
+//!   constructor();
+//!   push(chunk: Uint8Array): string;
+//!   finish(): string;
+//! }
+//! export class StreamDecoder {
+//!   constructor();
+//!   push(chunk: Uint8Array): Uint8Array;
+//!   finish(): Uint8Array;
+//! }
+//! export class Buffer {
+//!   constructor(length: number);
+//!   readonly length: number;
+//!   view(): Uint8Array;
+//! }
+//! export function encodeInto(input: Buffer, inputLen: number, output: Buffer): number;
+//! export function decodeInto(input: Buffer, inputLen: number, output: Buffer): number;
+//! ```
+//!
+//! `decode` and `validate` throw an `Error` with the position of the problem.
+//!
+//! `encodeStream` and `decodeStream` transcode large files chunk by chunk
+//! without buffering them, and `StreamEncoder` and `StreamDecoder` are the
+//! state machines behind them, for other kinds of streams:
+//!
+//! ```js
+//! const encoded = file.stream().pipeThrough(encodeStream());
+//! const decoded = response.body.pipeThrough(decodeStream());
+//! ```
+//!
+//! The other functions copy their arguments into the WASM memory and their
+//! results out of it on each call. `encodeInto` and `decodeInto` instead work
+//! on `Buffer`s allocated in the WASM memory once and reused, which JavaScript
+//! reads and writes through `Uint8Array` views:
+//!
+//! ```js
+//! const input = new Buffer(5 * 1024);
+//! const output = new Buffer(8 * 1024);
+//! const len = await readInto(input.view());
+//! const written = encodeInto(input, len, output);
+//! send(output.view().subarray(0, written));
+//! ```
+//!
+//! A view is detached when the WASM memory grows and points to reused memory
+//! after `free()`, so get a new one with `view()` after calling other functions
+//! instead of keeping it.
+
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use js_sys::{Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use web_sys::{TransformStream, TransformStreamDefaultController};
+
+use crate::{
+    capacity_hint_for_decode, capacity_hint_for_encode, check_input, decode_checked,
+    decode_to_slice, encode_to_slice, encode_to_string, find_invalid_symbol, Decoder, Encoder,
+    ParseError, BLOCK_SYMBOL_LEN,
+};
+
+/// Encodes bytes to a string.
+#[wasm_bindgen]
+pub fn encode(input: &[u8]) -> String {
+    encode_to_string(input)
+}
+
+/// Decodes a string to bytes, accepting lowercase symbols and the aliases
+/// `I`, `L` and `O`.
+///
+/// # Errors
+/// Throws an `Error` if the input contains an invalid symbol.
+#[wasm_bindgen]
+pub fn decode(input: &str) -> Result<Vec<u8>, JsError> {
+    decode_checked(input.as_bytes()).map_err(to_js_error)
+}
+
+/// Checks whether a string can be decoded, or with `strict` whether it is
+/// also a canonical encoding.
+///
+/// # Errors
+/// Throws an `Error` if the input is invalid.
+#[wasm_bindgen]
+pub fn validate(input: &str, strict: bool) -> Result<(), JsError> {
+    check_input(input.as_bytes(), strict).map_err(to_js_error)
+}
+
+/// A buffer in the WASM memory, which JavaScript reads and writes through a
+/// view without copying.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct Buffer {
+    bytes: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Buffer {
+    /// Creates a buffer of `length` zero bytes.
+    #[wasm_bindgen(constructor)]
+    pub fn new(length: usize) -> Self {
+        Self {
+            bytes: alloc::vec![0; length],
+        }
+    }
+
+    /// Returns the length of the buffer in bytes.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns a `Uint8Array` viewing the buffer in the WASM memory.
+    ///
+    /// The view is only valid until the next call into WASM: it must not be
+    /// used after `free()` on this buffer, since the memory is then reused, or
+    /// after any other call which may allocate, since growing the WASM memory
+    /// detaches it.
+    pub fn view(&mut self) -> Uint8Array {
+        // SAFETY: The view covers exactly the bytes owned by `self`, which are
+        // valid when it is created. Nothing keeps them valid afterwards, so the
+        // documentation above restricts the use of the view to before the next
+        // call into WASM, which is the only thing that can free or move them.
+        unsafe { Uint8Array::view_mut_raw(self.bytes.as_mut_ptr(), self.bytes.len()) }
+    }
+}
+
+impl Buffer {
+    // prefix returns the first `len` bytes.
+    fn prefix(&self, len: usize) -> Result<&[u8], JsError> {
+        self.bytes
+            .get(..len)
+            .ok_or_else(|| JsError::new("length is longer than the buffer"))
+    }
+}
+
+/// Encodes the first `input_len` bytes of `input` into `output` and returns the
+/// number of written symbols.
+///
+/// # Errors
+/// Throws an `Error` if `input_len` is longer than `input` or `output` is too
+/// small.
+#[wasm_bindgen(js_name = encodeInto)]
+pub fn encode_into(
+    input: &Buffer,
+    input_len: usize,
+    output: &mut Buffer,
+) -> Result<usize, JsError> {
+    let input = input.prefix(input_len)?;
+    Ok(encode_to_slice(input, &mut output.bytes)?)
+}
+
+/// Decodes the first `input_len` symbols of `input` into `output` and returns
+/// the number of written bytes.
+///
+/// # Errors
+/// Throws an `Error` with the position of the problem if the input contains an
+/// invalid symbol, or an `Error` if `input_len` is longer than `input` or
+/// `output` is too small.
+#[wasm_bindgen(js_name = decodeInto)]
+pub fn decode_into(
+    input: &Buffer,
+    input_len: usize,
+    output: &mut Buffer,
+) -> Result<usize, JsError> {
+    let input = input.prefix(input_len)?;
+    decode_slice_checked(input, &mut output.bytes).map_err(to_js_error)
+}
+
+// decode_slice_checked decodes `input` into `output` with the position of an
+// invalid symbol in the error.
+fn decode_slice_checked(input: &[u8], output: &mut [u8]) -> Result<usize, ParseError> {
+    find_invalid_symbol(input)?;
+    decode_to_slice(input, output).map_err(|err| ParseError::new(err, input.len(), None))
+}
+
+/// A resumable encoder for input given in chunks.
+#[wasm_bindgen]
+#[derive(Debug, Default)]
+pub struct StreamEncoder {
+    inner: Encoder,
+}
+
+#[wasm_bindgen]
+impl StreamEncoder {
+    /// Creates a new encoder.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encodes the next chunk and returns the encoded whole blocks.
+    pub fn push(&mut self, chunk: &[u8]) -> String {
+        let mut output = alloc::vec![0; capacity_hint_for_encode(chunk.len()) + BLOCK_SYMBOL_LEN];
+        let (_, len) = self.inner.update(chunk, &mut output);
+        output.truncate(len);
+        // The symbols are ASCII, so this never fails.
+        String::from_utf8(output).unwrap_or_default()
+    }
+
+    /// Encodes the remaining bytes.
+    pub fn finish(&mut self) -> String {
+        let mut output = [0; BLOCK_SYMBOL_LEN];
+        let len = self.inner.finish(&mut output).unwrap_or_default();
+        let output = output.get(..len).unwrap_or_default();
+        core::str::from_utf8(output).unwrap_or_default().into()
+    }
+}
+
+/// A resumable decoder for input given in chunks, skipping line breaks.
+#[wasm_bindgen]
+#[derive(Debug, Default)]
+pub struct StreamDecoder {
+    inner: Decoder,
+    // The number of bytes of the input given so far, for error positions.
+    position: usize,
+}
+
+#[wasm_bindgen]
+impl StreamDecoder {
+    /// Creates a new decoder.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes the next chunk and returns the decoded whole blocks.
+    ///
+    /// # Errors
+    /// Throws an `Error` with the position in the whole input if the chunk
+    /// contains an invalid symbol.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<u8>, JsError> {
+        self.push_chunk(chunk).map_err(to_js_error)
+    }
+
+    /// Decodes the remaining symbols.
+    ///
+    /// # Errors
+    /// Never throws, as the remaining symbols are checked by `push`.
+    pub fn finish(&mut self) -> Result<Vec<u8>, JsError> {
+        let mut output = alloc::vec![0; BLOCK_SYMBOL_LEN];
+        let len = self.inner.finish(&mut output)?;
+        output.truncate(len);
+        Ok(output)
+    }
+}
+
+impl StreamDecoder {
+    fn push_chunk(&mut self, chunk: &[u8]) -> Result<Vec<u8>, ParseError> {
+        let mut output = alloc::vec![0; capacity_hint_for_decode(chunk.len()) + BLOCK_SYMBOL_LEN];
+        let mut len = 0;
+        let mut start = 0;
+        for line in chunk.split(|&b| b == b'\n' || b == b'\r') {
+            find_invalid_symbol(line).map_err(|err| {
+                ParseError::new(
+                    err.error().clone(),
+                    self.position + start + err.position(),
+                    None,
+                )
+            })?;
+            // The symbols are checked above, so this never fails.
+            let (_, written) = self
+                .inner
+                .update(line, output.get_mut(len..).unwrap_or_default())
+                .unwrap_or_default();
+            len += written;
+            start += line.len() + 1;
+        }
+        self.position += chunk.len();
+        output.truncate(len);
+        Ok(output)
+    }
+}
+
+/// Returns a `TransformStream` which encodes `Uint8Array` chunks to string
+/// chunks.
+///
+/// # Errors
+/// Throws an `Error` if a chunk is not a `Uint8Array`.
+#[wasm_bindgen(js_name = encodeStream)]
+pub fn encode_stream() -> Result<TransformStream, JsValue> {
+    let encoder = Rc::new(RefCell::new(StreamEncoder::new()));
+    let flush_encoder = Rc::clone(&encoder);
+    transform_stream(
+        move |chunk, controller| {
+            let chunk = chunk
+                .dyn_into::<Uint8Array>()
+                .map_err(|_| JsError::new("chunks must be Uint8Arrays"))?;
+            let encoded = encoder.borrow_mut().push(&chunk.to_vec());
+            enqueue(controller, &encoded.into())
+        },
+        move |controller| {
+            let encoded = flush_encoder.borrow_mut().finish();
+            enqueue(controller, &encoded.into())
+        },
+    )
+}
+
+/// Returns a `TransformStream` which decodes string or `Uint8Array` chunks,
+/// skipping line breaks, to `Uint8Array` chunks.
+///
+/// # Errors
+/// Throws an `Error` if a chunk is neither a string nor a `Uint8Array`. The
+/// stream errors with the position of the problem on an invalid symbol.
+#[wasm_bindgen(js_name = decodeStream)]
+pub fn decode_stream() -> Result<TransformStream, JsValue> {
+    let decoder = Rc::new(RefCell::new(StreamDecoder::new()));
+    let flush_decoder = Rc::clone(&decoder);
+    transform_stream(
+        move |chunk, controller| {
+            let chunk = match chunk.as_string() {
+                Some(chunk) => chunk.into_bytes(),
+                None => chunk
+                    .dyn_into::<Uint8Array>()
+                    .map_err(|_| JsError::new("chunks must be strings or Uint8Arrays"))?
+                    .to_vec(),
+            };
+            let decoded = decoder.borrow_mut().push(&chunk)?;
+            enqueue(controller, &Uint8Array::from(&decoded[..]))
+        },
+        move |controller| {
+            let decoded = flush_decoder.borrow_mut().finish()?;
+            enqueue(controller, &Uint8Array::from(&decoded[..]))
+        },
+    )
+}
+
+// transform_stream creates a `TransformStream` calling `transform` for each
+// chunk and `flush` at the end.
+fn transform_stream(
+    transform: impl FnMut(JsValue, &TransformStreamDefaultController) -> Result<(), JsValue> + 'static,
+    flush: impl FnMut(&TransformStreamDefaultController) -> Result<(), JsValue> + 'static,
+) -> Result<TransformStream, JsValue> {
+    let mut transform = transform;
+    let mut flush = flush;
+    let transform = Closure::<
+        dyn FnMut(JsValue, TransformStreamDefaultController) -> Result<(), JsValue>,
+    >::new(move |chunk, controller: TransformStreamDefaultController| {
+        transform(chunk, &controller)
+    });
+    let flush = Closure::<dyn FnMut(TransformStreamDefaultController) -> Result<(), JsValue>>::new(
+        move |controller: TransformStreamDefaultController| flush(&controller),
+    );
+    let transformer = Object::new();
+    // The closures are owned by the transformer from now on.
+    Reflect::set(
+        &transformer,
+        &"transform".into(),
+        &transform.into_js_value(),
+    )?;
+    Reflect::set(&transformer, &"flush".into(), &flush.into_js_value())?;
+    TransformStream::new_with_transformer(&transformer)
+}
+
+// enqueue enqueues `chunk` unless it is empty.
+fn enqueue(controller: &TransformStreamDefaultController, chunk: &JsValue) -> Result<(), JsValue> {
+    let len = Reflect::get(chunk, &"length".into())?;
+    if len.as_f64() == Some(0.0) {
+        return Ok(());
+    }
+    controller.enqueue_with_chunk(chunk)
+}
+
+fn to_js_error(err: ParseError) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        assert_eq!(encode(b"Hello, world!"), "91JPRV3F5GG7EVVJDHJ22");
+        assert_eq!(
+            decode_checked(b"91jprv3f5gg7evvjdhj22").unwrap(),
+            b"Hello, world!"
+        );
+        assert_eq!(decode_checked(b"CSQPU").unwrap_err().position(), 4);
+    }
+
+    #[test]
+    fn test_stream() {
+        let mut encoder = StreamEncoder::new();
+        let mut encoded = String::new();
+        for chunk in [&b"Hel"[..], b"lo, world", b"", b"!"] {
+            encoded += &encoder.push(chunk);
+        }
+        assert_eq!(encoded, "91JPRV3F5GG7EVVJ");
+        encoded += &encoder.finish();
+        assert_eq!(encoded, "91JPRV3F5GG7EVVJDHJ22");
+
+        let mut decoder = StreamDecoder::new();
+        let mut decoded = Vec::new();
+        for chunk in [&b"91JPRV3F\r\n5GG"[..], b"7evvj\n", b"DHJ22\n"] {
+            decoded.extend(decoder.push_chunk(chunk).unwrap());
+        }
+        decoded.extend(decoder.finish().unwrap());
+        assert_eq!(decoded, b"Hello, world!");
+
+        let mut decoder = StreamDecoder::new();
+        decoder.push_chunk(b"91JPRV3F\n").unwrap();
+        let err = decoder.push_chunk(b"5GG7\nEVU").unwrap_err();
+        assert!(matches!(
+            err.error(),
+            crate::Error::InvalidSymbol { byte: b'U', .. }
+        ));
+        assert_eq!(err.position(), 16);
+    }
+
+    #[test]
+    fn test_buffer() {
+        let mut input = Buffer::new(16);
+        input.bytes[..5].copy_from_slice(b"fooba");
+        let mut output = Buffer::new(8);
+        assert_eq!(encode_into(&input, 5, &mut output).ok(), Some(8));
+        assert_eq!(output.bytes, b"CSQPYRK1");
+        assert_eq!(encode_into(&input, 0, &mut output).ok(), Some(0));
+
+        let mut decoded = Buffer::new(8);
+        assert_eq!(decode_into(&output, 8, &mut decoded).ok(), Some(5));
+        assert_eq!(&decoded.bytes[..5], b"fooba");
+        assert_eq!(decoded.length(), 8);
+
+        // Errors can't be thrown outside of WASM, so the checks are tested.
+        let err = decode_slice_checked(b"CSQUYRK1", &mut decoded.bytes).unwrap_err();
+        assert_eq!(err.position(), 3);
+        let err = decode_slice_checked(b"CSQPYRK1", &mut [0; 4]).unwrap_err();
+        assert_eq!(err.error(), &crate::Error::BufferTooSmall);
+    }
+
+    #[test]
+    fn test_check() {
+        assert!(check_input(b"csqpyr", false).is_ok());
+        assert_eq!(check_input(b"csqpyr", true).unwrap_err().position(), 0);
+        assert!(check_input(b"CSQPYRK1", true).is_ok());
+        assert!(check_input(b"CSQ-", false).is_err());
+    }
+}